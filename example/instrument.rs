@@ -1,16 +1,17 @@
 //! OKX Instruments API Example
 //! 
-//! 这个示例演示了如何使用 action/instrument.rs 中的 get_symbols 函数
-//! 来获取不同类型的交易产品信息：SPOT, MARGIN, SWAP, FUTURES
+//! 这个示例演示了如何使用 action/instrument.rs 中的 OkxClient::get_symbols
+//! 来获取不同类型的交易产品信息：SPOT, MARGIN, SWAP, FUTURES, OPTION
 //! 
-//! 对于 OPTION 类别，由于API需要额外的参数，这里暂时跳过
+//! OPTION 类别需要指定交易品种，get_symbols 会先获取所有期权交易品种再逐个查询
 
-use okx::action::instrument::get_symbols;
+use okx::action::client::{ClientConfig, OkxClient};
 use okx::model::symbol::Category;
 
 #[tokio::main]
 async fn main() {
     println!("=== OKX Instruments API Example ===\n");
+    let client = OkxClient::new(ClientConfig::default()).expect("failed to build okx client");
     
     // 定义要测试的所有类别
     let categories = vec![
@@ -18,16 +19,15 @@ async fn main() {
         Category::Margin, 
         Category::Swap,
         Category::Future,
-        // 注意：Option 类别需要额外的参数，这里暂时跳过
-        // Category::Option,
+        Category::Option,
     ];
     
     // 对每个类别执行 GET 请求并打印结果
     for category in categories {
         println!("🔍 正在获取 {} 交易对信息...", category.as_str());
         
-        match get_symbols(category.clone()).await {
-            Some(symbols) => {
+        match client.get_symbols(category.clone()).await {
+            Ok(symbols) => {
                 assert!(!symbols.is_empty(), "Expected non-empty symbols list");
                 println!("✅ 成功获取 {} 个 {} 交易对:", symbols.len(), category.as_str());
                 
@@ -54,8 +54,15 @@ async fn main() {
                                 symbol.state
                             );
                         }
-                        _ => {
-                            println!("  {}. 产品ID: {}, 状态: {:?}", i + 1, symbol.inst_id, symbol.state);
+                        Category::Option => {
+                            println!("  {}. 期权ID: {}, 类型: {}, 行权价: {}, 到期: {}, 状态: {:?}", 
+                                i + 1,
+                                symbol.inst_id,
+                                symbol.opt_type.map(|t| t.as_str()).unwrap_or_default(),
+                                symbol.stk.clone().unwrap_or_default(),
+                                symbol.expiry().map(|t| t.format("%Y-%m-%d").to_string()).unwrap_or_default(),
+                                symbol.state
+                            );
                         }
                     }
                 }
@@ -66,17 +73,27 @@ async fn main() {
                 
                 println!();
             }
-            None => {
-                println!("❌ 获取 {} 交易对失败", category.as_str());
+            Err(e) => {
+                println!("❌ 获取 {} 交易对失败: {}", category.as_str(), e);
                 println!();
             }
         }
     }
     
-    // 特别处理 OPTION 类别 - 需要指定 uly 参数
-    println!("🔍 正在获取 OPTION 交易对信息 (需要特殊处理)...");
-    println!("ℹ️  注意：OPTION 类别需要额外的 uly 或 instFamily 参数");
-    println!("   可以通过修改 get_symbols 函数来支持这些参数");
+    // 单独获取某个交易品种的期权
+    println!("🔍 正在获取 OPTION 交易品种...");
+    match client.get_option_families().await {
+        Ok(families) => {
+            println!("✅ 期权交易品种: {:?}", families);
+            if let Some(family) = families.first() {
+                match client.get_option_symbols(family).await {
+                    Ok(symbols) => println!("✅ {} 共有 {} 个期权", family, symbols.len()),
+                    Err(e) => println!("❌ 获取 {} 期权失败: {}", family, e),
+                }
+            }
+        }
+        Err(e) => println!("❌ 获取期权交易品种失败: {}", e),
+    }
     println!();
     
     println!("=== 示例执行完成 ===");
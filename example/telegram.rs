@@ -2,7 +2,6 @@
 // 此示例展示如何使用 telegram.rs 中的 broadcast 函数发送消息到 Telegram 群组
 
 use okx::action::telegram::broadcast;
-use tokio;
 
 #[tokio::main]
 async fn main() {
@@ -38,7 +37,7 @@ async fn main() {
 }
 
 /// 发送消息的包装函数
-async fn send_message(text: &String) -> bool {
+async fn send_message(text: &str) -> bool {
     broadcast(text).await
 }
 
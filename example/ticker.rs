@@ -1,53 +1,53 @@
 // OKX Ticker API 示例
-// 此示例展示如何使用 ticker.rs 中的 get_ticker 函数获取不同类别的市场行情数据
+// 此示例展示如何使用 ticker.rs 中的 OkxClient::get_ticker 获取不同类别的市场行情数据
 
-use okx::action::ticker::get_ticker;
+use okx::action::client::{ClientConfig, OkxClient};
 use okx::model::symbol::Category;
-use tokio;
 use bigdecimal::BigDecimal;
 
 #[tokio::main]
 async fn main() {
     println!("OKX Ticker API Example");
     println!("======================");
+    let client = OkxClient::new(ClientConfig::default()).expect("failed to build okx client");
     
     // 示例1: 获取现货市场行情
     println!("\n📊 获取现货市场行情...");
-    match get_ticker(Category::Spot).await {
-        Some(tickers) => {
+    match client.get_ticker(Category::Spot).await {
+        Ok(tickers) => {
             println!("✅ 成功获取到 {} 个现货交易对的行情数据", tickers.len());
             display_top_tickers(&tickers, 5, "现货");
         }
-        None => println!("❌ 获取现货行情失败"),
+        Err(e) => println!("❌ 获取现货行情失败: {}", e),
     }
     
     // 示例2: 获取永续合约行情
     println!("\n📊 获取永续合约行情...");
-    match get_ticker(Category::Swap).await {
-        Some(tickers) => {
+    match client.get_ticker(Category::Swap).await {
+        Ok(tickers) => {
             println!("✅ 成功获取到 {} 个永续合约的行情数据", tickers.len());
             display_top_tickers(&tickers, 5, "永续合约");
         }
-        None => println!("❌ 获取永续合约行情失败"),
+        Err(e) => println!("❌ 获取永续合约行情失败: {}", e),
     }
     
     // 示例3: 获取期货行情
     println!("\n📊 获取期货行情...");
-    match get_ticker(Category::Future).await {
-        Some(tickers) => {
+    match client.get_ticker(Category::Future).await {
+        Ok(tickers) => {
             println!("✅ 成功获取到 {} 个期货合约的行情数据", tickers.len());
             display_top_tickers(&tickers, 3, "期货");
         }
-        None => println!("❌ 获取期货行情失败"),
+        Err(e) => println!("❌ 获取期货行情失败: {}", e),
     }
     
     // 示例4: 分析市场数据
     println!("\n📈 市场数据分析...");
-    analyze_market_data().await;
+    analyze_market_data(&client).await;
     
     // 示例5: 监控特定交易对
     println!("\n🔍 监控特定交易对...");
-    monitor_specific_pairs().await;
+    monitor_specific_pairs(&client).await;
 }
 
 /// 显示前N个交易对的行情信息
@@ -67,8 +67,8 @@ fn display_top_tickers(tickers: &[okx::model::symbol::Ticker], count: usize, mar
         println!("   📈 24h变化: {}%", format_percentage(&change_24h));
         println!("   📊 24h成交量: {}", format_volume(&ticker.vol_24h));
         println!("   💹 买一/卖一: {} / {}", 
-            format_price(&ticker.bid_px), 
-            format_price(&ticker.ask_px)
+            ticker.bid_px.as_ref().map(format_price).unwrap_or("-".to_string()), 
+            ticker.ask_px.as_ref().map(format_price).unwrap_or("-".to_string())
         );
         println!();
     }
@@ -106,8 +106,8 @@ fn format_volume(volume: &BigDecimal) -> String {
 }
 
 /// 分析市场数据
-async fn analyze_market_data() {
-    if let Some(spot_tickers) = get_ticker(Category::Spot).await {
+async fn analyze_market_data(client: &OkxClient) {
+    if let Ok(spot_tickers) = client.get_ticker(Category::Spot).await {
         let mut stats = MarketStats::new();
         
         for ticker in &spot_tickers {
@@ -120,10 +120,10 @@ async fn analyze_market_data() {
 }
 
 /// 监控特定交易对
-async fn monitor_specific_pairs() {
+async fn monitor_specific_pairs(client: &OkxClient) {
     let target_pairs = vec!["BTC-USDT", "ETH-USDT", "SOL-USDT"];
     
-    if let Some(tickers) = get_ticker(Category::Spot).await {
+    if let Ok(tickers) = client.get_ticker(Category::Spot).await {
         println!("监控的交易对行情:");
         
         for target in &target_pairs {
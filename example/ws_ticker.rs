@@ -0,0 +1,38 @@
+// OKX WebSocket 行情示例
+// 此示例展示如何使用 ws/public.rs 中的 PublicWs 订阅行情和深度推送，断线后会自动重连并重新订阅
+
+use okx::action::client::Environment;
+use okx::action::ws::WsConfig;
+use okx::action::ws::public::{BookChannel, PublicEvent, PublicWs};
+
+#[tokio::main]
+async fn main() {
+    println!("OKX WebSocket Ticker Example");
+    println!("============================");
+    let (client, mut events) = PublicWs::connect(WsConfig::public(Environment::Live));
+    client
+        .subscribe_tickers(&["BTC-USDT", "BTC-USDT-SWAP", "ETH-USDT", "ETH-USDT-SWAP"])
+        .expect("websocket task stopped");
+    client
+        .subscribe_books(BookChannel::Books5, &["BTC-USDT"])
+        .expect("websocket task stopped");
+
+    while let Some(event) = events.recv().await {
+        match event {
+            PublicEvent::Connected => println!("✅ 已连接"),
+            PublicEvent::Disconnected => println!("⚠️  连接断开，正在重连..."),
+            PublicEvent::Ticker(ticker) => println!(
+                "{} 最新价格: {} 买一/卖一: {} / {}",
+                ticker.inst_id,
+                ticker.last,
+                ticker.bid_px.map(|p| p.to_string()).unwrap_or("-".to_string()),
+                ticker.ask_px.map(|p| p.to_string()).unwrap_or("-".to_string())
+            ),
+            PublicEvent::Book { channel, book } => {
+                let (bids, asks) = book.depth(5);
+                println!("{} {} 买盘 {} 档 / 卖盘 {} 档", book.inst_id, channel.as_str(), bids.len(), asks.len());
+            }
+            PublicEvent::Error { code, msg } => println!("❌ 错误: {} {}", code, msg),
+        }
+    }
+}
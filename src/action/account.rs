@@ -0,0 +1,495 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::action::client::OkxClient;
+use crate::action::error::OkxError;
+use crate::model::account::{
+    AccountBalance, AccountConfig, AccountMode, Balance, BalanceAndPosition, CashBalance, Leverage, MaxAvailSize, MaxSize,
+    Position, PositionMode, SetLeverage,
+};
+use crate::model::order::{PosSide, TdMode};
+use crate::model::symbol::Category;
+
+/// 空字符串和格式错误都解析为 None
+fn parse_optional(s: &str) -> Option<BigDecimal> {
+    BigDecimal::from_str(s).ok()
+}
+
+fn parse_time(s: &str) -> u64 {
+    s.parse().unwrap_or(0)
+}
+
+/// 账户资产，account 频道和 /api/v5/account/balance 的格式相同
+#[derive(Deserialize)]
+pub(crate) struct InstAccount {
+    #[serde(rename = "uTime", default)]
+    pub u_time: String,
+    #[serde(rename = "totalEq", default)]
+    pub total_eq: String,
+    #[serde(rename = "adjEq", default)]
+    pub adj_eq: String,
+    #[serde(default)]
+    pub imr: String,
+    #[serde(default)]
+    pub mmr: String,
+    #[serde(rename = "mgnRatio", default)]
+    pub mgn_ratio: String,
+    #[serde(default)]
+    pub details: Vec<InstBalance>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct InstBalance {
+    pub ccy: String,
+    #[serde(default)]
+    pub eq: String,
+    #[serde(rename = "cashBal", default)]
+    pub cash_bal: String,
+    #[serde(rename = "availBal", default)]
+    pub avail_bal: String,
+    #[serde(rename = "frozenBal", default)]
+    pub frozen_bal: String,
+    #[serde(default)]
+    pub upl: String,
+    #[serde(rename = "eqUsd", default)]
+    pub eq_usd: String,
+    #[serde(rename = "uTime", default)]
+    pub u_time: String,
+}
+
+impl InstAccount {
+    pub(crate) fn into_account(self) -> Option<AccountBalance> {
+        let Some(total_eq) = parse_optional(&self.total_eq) else {
+            warn!("invalid account totalEq: {:?}", self.total_eq);
+            return None;
+        };
+        let details = self
+            .details
+            .into_iter()
+            .filter_map(|b| {
+                let (Some(eq), Some(cash_bal)) = (parse_optional(&b.eq), parse_optional(&b.cash_bal)) else {
+                    warn!("invalid balance of {}: eq={}, cashBal={}", b.ccy, b.eq, b.cash_bal);
+                    return None;
+                };
+                Some(Balance {
+                    eq,
+                    cash_bal,
+                    avail_bal: parse_optional(&b.avail_bal),
+                    frozen_bal: parse_optional(&b.frozen_bal),
+                    upl: parse_optional(&b.upl),
+                    eq_usd: parse_optional(&b.eq_usd),
+                    u_time: parse_time(&b.u_time),
+                    ccy: b.ccy,
+                })
+            })
+            .collect();
+        Some(AccountBalance {
+            total_eq,
+            adj_eq: parse_optional(&self.adj_eq),
+            imr: parse_optional(&self.imr),
+            mmr: parse_optional(&self.mmr),
+            mgn_ratio: parse_optional(&self.mgn_ratio),
+            u_time: parse_time(&self.u_time),
+            details,
+        })
+    }
+}
+
+/// 持仓，positions 频道和 /api/v5/account/positions 的格式相同
+/// balance_and_position 频道中的持仓只有部分字段
+#[derive(Deserialize)]
+pub(crate) struct InstPosition {
+    #[serde(rename = "posId")]
+    pub pos_id: String,
+    #[serde(rename = "instId")]
+    pub inst_id: String,
+    #[serde(rename = "instType")]
+    pub inst_type: String,
+    #[serde(rename = "mgnMode")]
+    pub mgn_mode: String,
+    #[serde(rename = "posSide")]
+    pub pos_side: String,
+    pub pos: String,
+    #[serde(default)]
+    pub ccy: String,
+    #[serde(rename = "avgPx", default)]
+    pub avg_px: String,
+    #[serde(default)]
+    pub upl: String,
+    #[serde(default)]
+    pub lever: String,
+    #[serde(rename = "liqPx", default)]
+    pub liq_px: String,
+    #[serde(rename = "markPx", default)]
+    pub mark_px: String,
+    #[serde(default)]
+    pub imr: String,
+    #[serde(default)]
+    pub margin: String,
+    #[serde(rename = "mgnRatio", default)]
+    pub mgn_ratio: String,
+    #[serde(rename = "uTime", default)]
+    pub u_time: String,
+}
+
+impl InstPosition {
+    pub(crate) fn into_position(self) -> Option<Position> {
+        let category = Category::from_str(&self.inst_type);
+        let mgn_mode = TdMode::from_str(&self.mgn_mode);
+        let pos_side = PosSide::from_str(&self.pos_side);
+        let pos = parse_optional(&self.pos);
+        let (Some(category), Some(mgn_mode), Some(pos_side), Some(pos)) = (category, mgn_mode, pos_side, pos) else {
+            warn!(
+                "invalid position {} of {}: instType={}, mgnMode={}, posSide={}, pos={}",
+                self.pos_id, self.inst_id, self.inst_type, self.mgn_mode, self.pos_side, self.pos
+            );
+            return None;
+        };
+        // 逐仓的保证金在 margin 中，全仓在 imr 中
+        let margin = parse_optional(&self.margin).or_else(|| parse_optional(&self.imr));
+        Some(Position {
+            pos_id: self.pos_id,
+            inst_id: self.inst_id,
+            category,
+            mgn_mode,
+            pos_side,
+            pos,
+            ccy: self.ccy,
+            avg_px: parse_optional(&self.avg_px),
+            upl: parse_optional(&self.upl),
+            lever: parse_optional(&self.lever),
+            liq_px: parse_optional(&self.liq_px),
+            mark_px: parse_optional(&self.mark_px),
+            margin,
+            mgn_ratio: parse_optional(&self.mgn_ratio),
+            u_time: parse_time(&self.u_time),
+        })
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct InstCashBalance {
+    pub ccy: String,
+    #[serde(rename = "cashBal")]
+    pub cash_bal: String,
+    #[serde(rename = "uTime", default)]
+    pub u_time: String,
+}
+
+/// balance_and_position 频道的推送
+#[derive(Deserialize)]
+pub(crate) struct InstBalanceAndPosition {
+    #[serde(rename = "pTime", default)]
+    pub p_time: String,
+    #[serde(rename = "eventType", default)]
+    pub event_type: String,
+    #[serde(rename = "balData", default)]
+    pub bal_data: Vec<InstCashBalance>,
+    #[serde(rename = "posData", default)]
+    pub pos_data: Vec<InstPosition>,
+}
+
+impl InstBalanceAndPosition {
+    pub(crate) fn into_update(self) -> BalanceAndPosition {
+        let balances = self
+            .bal_data
+            .into_iter()
+            .filter_map(|b| match parse_optional(&b.cash_bal) {
+                Some(cash_bal) => Some(CashBalance {
+                    cash_bal,
+                    u_time: parse_time(&b.u_time),
+                    ccy: b.ccy,
+                }),
+                None => {
+                    warn!("invalid cashBal of {}: {:?}", b.ccy, b.cash_bal);
+                    None
+                }
+            })
+            .collect();
+        BalanceAndPosition {
+            p_time: parse_time(&self.p_time),
+            event_type: self.event_type,
+            balances,
+            positions: self.pos_data.into_iter().filter_map(InstPosition::into_position).collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub(crate) struct InstAccountConfig {
+    pub uid: String,
+    #[serde(rename = "acctLv")]
+    pub acct_lv: String,
+    #[serde(rename = "posMode")]
+    pub pos_mode: String,
+    #[serde(rename = "autoLoan", default)]
+    pub auto_loan: bool,
+    #[serde(default)]
+    pub level: String,
+}
+
+#[derive(Serialize)]
+struct InstSetLeverage<'a> {
+    #[serde(rename = "instId", skip_serializing_if = "Option::is_none")]
+    inst_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ccy: Option<&'a str>,
+    lever: String,
+    #[serde(rename = "mgnMode")]
+    mgn_mode: &'static str,
+    #[serde(rename = "posSide", skip_serializing_if = "Option::is_none")]
+    pos_side: Option<&'static str>,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct InstLeverage {
+    #[serde(rename = "instId", default)]
+    pub inst_id: String,
+    pub lever: String,
+    #[serde(rename = "mgnMode")]
+    pub mgn_mode: String,
+    #[serde(rename = "posSide", default)]
+    pub pos_side: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct InstMaxSize {
+    #[serde(rename = "instId")]
+    pub inst_id: String,
+    #[serde(default)]
+    pub ccy: String,
+    #[serde(rename = "maxBuy")]
+    pub max_buy: String,
+    #[serde(rename = "maxSell")]
+    pub max_sell: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct InstMaxAvailSize {
+    #[serde(rename = "instId")]
+    pub inst_id: String,
+    #[serde(rename = "availBuy")]
+    pub avail_buy: String,
+    #[serde(rename = "availSell")]
+    pub avail_sell: String,
+}
+
+impl OkxClient {
+    /// 账户余额，ccys 为空时返回所有余额不为 0 的币种
+    /// /api/v5/account/balance
+    pub async fn get_balance(&self, ccys: &[&str]) -> Result<Option<AccountBalance>, OkxError> {
+        let ccy = ccys.join(",");
+        let query: Vec<(&str, &str)> = if ccy.is_empty() { vec![] } else { vec![("ccy", ccy.as_str())] };
+        let data: Vec<InstAccount> = self.get_private("/api/v5/account/balance", &query).await?;
+        Ok(data.into_iter().next().and_then(InstAccount::into_account))
+    }
+
+    /// 持仓，category 和 inst_id 都为 None 时返回所有持仓
+    /// /api/v5/account/positions
+    pub async fn get_positions(&self, category: Option<Category>, inst_id: Option<&str>) -> Result<Vec<Position>, OkxError> {
+        let mut query = Vec::new();
+        if let Some(category) = &category {
+            query.push(("instType", category.as_str()));
+        }
+        if let Some(inst_id) = inst_id {
+            query.push(("instId", inst_id));
+        }
+        let data: Vec<InstPosition> = self.get_private("/api/v5/account/positions", &query).await?;
+        Ok(data.into_iter().filter_map(InstPosition::into_position).collect())
+    }
+
+    /// 账户配置，包括账户模式和持仓模式
+    /// /api/v5/account/config
+    pub async fn get_account_config(&self) -> Result<Option<AccountConfig>, OkxError> {
+        let data: Vec<InstAccountConfig> = self.get_private("/api/v5/account/config", &[]).await?;
+        Ok(data.into_iter().next().and_then(|c| {
+            let (Some(acct_lv), Some(pos_mode)) = (AccountMode::from_str(&c.acct_lv), PositionMode::from_str(&c.pos_mode)) else {
+                warn!("invalid account config: acctLv={}, posMode={}", c.acct_lv, c.pos_mode);
+                return None;
+            };
+            Some(AccountConfig {
+                uid: c.uid,
+                acct_lv,
+                pos_mode,
+                auto_loan: c.auto_loan,
+                level: c.level,
+            })
+        }))
+    }
+
+    /// 设置杠杆倍数，与下单一样受实盘下单开关限制
+    /// /api/v5/account/set-leverage
+    pub async fn set_leverage(&self, leverage: &SetLeverage) -> Result<Vec<Leverage>, OkxError> {
+        self.check_trading_allowed()?;
+        let body = InstSetLeverage {
+            inst_id: leverage.inst_id.as_deref(),
+            ccy: leverage.ccy.as_deref(),
+            lever: leverage.lever.to_plain_string(),
+            mgn_mode: leverage.mgn_mode.as_str(),
+            pos_side: leverage.pos_side.as_ref().map(|p| p.as_str()),
+        };
+        let data: Vec<InstLeverage> = self.post_private("/api/v5/account/set-leverage", &body).await?;
+        Ok(data
+            .into_iter()
+            .filter_map(|l| {
+                Some(Leverage {
+                    lever: parse_optional(&l.lever)?,
+                    mgn_mode: TdMode::from_str(&l.mgn_mode)?,
+                    pos_side: PosSide::from_str(&l.pos_side),
+                    inst_id: l.inst_id,
+                })
+            })
+            .collect())
+    }
+
+    /// 设置持仓模式，有持仓或挂单时不能修改
+    /// /api/v5/account/set-position-mode
+    pub async fn set_position_mode(&self, mode: PositionMode) -> Result<(), OkxError> {
+        self.check_trading_allowed()?;
+        let body = serde_json::json!({ "posMode": mode.as_str() });
+        let _: Vec<serde_json::Value> = self.post_private("/api/v5/account/set-position-mode", &body).await?;
+        Ok(())
+    }
+
+    /// 最大可下单数量，px 为 None 时按最新价计算
+    /// /api/v5/account/max-size
+    pub async fn get_max_size(&self, inst_id: &str, td_mode: TdMode, px: Option<&BigDecimal>) -> Result<Option<MaxSize>, OkxError> {
+        let px = px.map(|p| p.to_plain_string());
+        let mut query = vec![("instId", inst_id), ("tdMode", td_mode.as_str())];
+        if let Some(px) = &px {
+            query.push(("px", px.as_str()));
+        }
+        let data: Vec<InstMaxSize> = self.get_private("/api/v5/account/max-size", &query).await?;
+        Ok(data.into_iter().next().and_then(|m| {
+            Some(MaxSize {
+                max_buy: parse_optional(&m.max_buy)?,
+                max_sell: parse_optional(&m.max_sell)?,
+                inst_id: m.inst_id,
+                ccy: m.ccy,
+            })
+        }))
+    }
+
+    /// 最大可用余额或保证金
+    /// /api/v5/account/max-avail-size
+    pub async fn get_max_avail_size(&self, inst_id: &str, td_mode: TdMode, reduce_only: bool) -> Result<Option<MaxAvailSize>, OkxError> {
+        let mut query = vec![("instId", inst_id), ("tdMode", td_mode.as_str())];
+        if reduce_only {
+            query.push(("reduceOnly", "true"));
+        }
+        let data: Vec<InstMaxAvailSize> = self.get_private("/api/v5/account/max-avail-size", &query).await?;
+        Ok(data.into_iter().next().and_then(|m| {
+            Some(MaxAvailSize {
+                avail_buy: parse_optional(&m.avail_buy)?,
+                avail_sell: parse_optional(&m.avail_sell)?,
+                inst_id: m.inst_id,
+            })
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::auth::Credentials;
+    use crate::action::client::{ClientConfig, Environment};
+    use crate::action::mock::MockServer;
+
+    async fn client(routes: Vec<(&'static str, u16, String)>) -> (MockServer, OkxClient) {
+        let server = MockServer::start(routes).await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            credentials: Some(Credentials::new("key", "secret", "pass")),
+            environment: Environment::Demo,
+            ..Default::default()
+        })
+        .unwrap();
+        (server, client)
+    }
+
+    #[tokio::test]
+    async fn test_get_balance_and_positions() {
+        let balance = r#"{"code":"0","msg":"","data":[{"adjEq":"","imr":"","isoEq":"0","mgnRatio":"","mmr":"","totalEq":"1200.5","uTime":"1705474164160","details":[{"availBal":"1000","cashBal":"1000","ccy":"USDT","eq":"1000","eqUsd":"1000","frozenBal":"0","upl":"0","uTime":"1705449605015"},{"availBal":"","cashBal":"0.01","ccy":"BTC","eq":"0.01","eqUsd":"200.5","frozenBal":"0","upl":"","uTime":"1705449605015"}]}]}"#;
+        let positions = r#"{"code":"0","msg":"","data":[{"adl":"1","availPos":"","avgPx":"30000","cTime":"1","ccy":"USDT","imr":"60","instId":"BTC-USDT-SWAP","instType":"SWAP","lever":"5","liqPx":"","margin":"","markPx":"30010","mgnMode":"cross","mgnRatio":"10","pos":"-1","posId":"1","posSide":"net","upl":"-0.1","uTime":"2"}]}"#;
+        let (server, client) = client(vec![
+            ("/api/v5/account/balance", 200, balance.to_string()),
+            ("/api/v5/account/positions", 200, positions.to_string()),
+        ])
+        .await;
+
+        let account = client.get_balance(&["USDT", "BTC"]).await.unwrap().unwrap();
+        assert_eq!(account.total_eq.to_string(), "1200.5");
+        assert_eq!(account.adj_eq, None);
+        assert_eq!(account.details.len(), 2);
+        assert_eq!(account.details[1].avail_bal, None);
+
+        let positions = client.get_positions(Some(Category::Swap), None).await.unwrap();
+        assert_eq!(positions[0].pos, BigDecimal::from(-1));
+        assert_eq!(positions[0].margin, Some(BigDecimal::from(60)));
+        assert_eq!(positions[0].liq_px, None);
+
+        let requests = server.requests();
+        assert_eq!(requests[0].target, "/api/v5/account/balance?ccy=USDT%2CBTC");
+        assert_eq!(requests[1].target, "/api/v5/account/positions?instType=SWAP");
+    }
+
+    #[tokio::test]
+    async fn test_config_and_leverage() {
+        let config = r#"{"code":"0","msg":"","data":[{"acctLv":"2","autoLoan":false,"ctIsoMode":"automatic","greeksType":"PA","level":"Lv1","levelTmp":"","mgnIsoMode":"automatic","posMode":"net_mode","uid":"44705892343619584"}]}"#;
+        let leverage = r#"{"code":"0","msg":"","data":[{"lever":"5","mgnMode":"cross","instId":"BTC-USDT-SWAP","posSide":""}]}"#;
+        let mode = r#"{"code":"0","msg":"","data":[{"posMode":"long_short_mode"}]}"#;
+        let (server, client) = client(vec![
+            ("/api/v5/account/config", 200, config.to_string()),
+            ("/api/v5/account/set-leverage", 200, leverage.to_string()),
+            ("/api/v5/account/set-position-mode", 200, mode.to_string()),
+        ])
+        .await;
+
+        let config = client.get_account_config().await.unwrap().unwrap();
+        assert_eq!(config.acct_lv, AccountMode::SingleCurrencyMargin);
+        assert_eq!(config.pos_mode, PositionMode::Net);
+
+        let result = client
+            .set_leverage(&SetLeverage {
+                inst_id: Some("BTC-USDT-SWAP".to_string()),
+                ccy: None,
+                lever: BigDecimal::from(5),
+                mgn_mode: TdMode::Cross,
+                pos_side: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(result[0].lever, BigDecimal::from(5));
+        assert_eq!(result[0].pos_side, None);
+
+        client.set_position_mode(PositionMode::LongShort).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[1].body, r#"{"instId":"BTC-USDT-SWAP","lever":"5","mgnMode":"cross"}"#);
+        assert_eq!(requests[2].body, r#"{"posMode":"long_short_mode"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_max_sizes() {
+        let max_size = r#"{"code":"0","msg":"","data":[{"ccy":"USDT","instId":"BTC-USDT","maxBuy":"0.0331","maxSell":"0.5"}]}"#;
+        let avail = r#"{"code":"0","msg":"","data":[{"instId":"BTC-USDT","availBuy":"1000","availSell":"0.5"}]}"#;
+        let (server, client) = client(vec![
+            ("/api/v5/account/max-size", 200, max_size.to_string()),
+            ("/api/v5/account/max-avail-size", 200, avail.to_string()),
+        ])
+        .await;
+
+        let px = BigDecimal::from(30000);
+        let max = client.get_max_size("BTC-USDT", TdMode::Cash, Some(&px)).await.unwrap().unwrap();
+        assert_eq!(max.max_buy.to_string(), "0.0331");
+        let avail = client.get_max_avail_size("BTC-USDT", TdMode::Cash, false).await.unwrap().unwrap();
+        assert_eq!(avail.avail_buy, BigDecimal::from(1000));
+
+        let requests = server.requests();
+        assert_eq!(requests[0].target, "/api/v5/account/max-size?instId=BTC-USDT&tdMode=cash&px=30000");
+        assert_eq!(requests[1].target, "/api/v5/account/max-avail-size?instId=BTC-USDT&tdMode=cash");
+    }
+}
@@ -0,0 +1,461 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::action::client::OkxClient;
+use crate::action::error::{ApiCode, OkxError};
+use crate::model::algo::{
+    AlgoAck, AlgoOrder, AlgoOrderRequest, AlgoOrderType, AlgoParams, AlgoState, CancelAlgoRequest, PxDistance, TpSl,
+};
+use crate::model::order::{PosSide, Side, TdMode};
+use crate::model::symbol::{Category, Symbol};
+
+/// 策略委托下单请求的格式，不同类型使用不同的字段
+#[derive(Serialize, Default)]
+struct InstAlgoRequest<'a> {
+    #[serde(rename = "instId")]
+    inst_id: &'a str,
+    #[serde(rename = "tdMode")]
+    td_mode: &'static str,
+    side: &'static str,
+    #[serde(rename = "posSide", skip_serializing_if = "Option::is_none")]
+    pos_side: Option<&'static str>,
+    #[serde(rename = "ordType")]
+    ord_type: &'static str,
+    sz: String,
+    #[serde(rename = "reduceOnly", skip_serializing_if = "Option::is_none")]
+    reduce_only: Option<bool>,
+    #[serde(rename = "algoClOrdId", skip_serializing_if = "Option::is_none")]
+    algo_cl_ord_id: Option<&'a str>,
+    #[serde(rename = "tpTriggerPx", skip_serializing_if = "Option::is_none")]
+    tp_trigger_px: Option<String>,
+    #[serde(rename = "tpOrdPx", skip_serializing_if = "Option::is_none")]
+    tp_ord_px: Option<String>,
+    #[serde(rename = "tpTriggerPxType", skip_serializing_if = "Option::is_none")]
+    tp_trigger_px_type: Option<&'static str>,
+    #[serde(rename = "slTriggerPx", skip_serializing_if = "Option::is_none")]
+    sl_trigger_px: Option<String>,
+    #[serde(rename = "slOrdPx", skip_serializing_if = "Option::is_none")]
+    sl_ord_px: Option<String>,
+    #[serde(rename = "slTriggerPxType", skip_serializing_if = "Option::is_none")]
+    sl_trigger_px_type: Option<&'static str>,
+    #[serde(rename = "triggerPx", skip_serializing_if = "Option::is_none")]
+    trigger_px: Option<String>,
+    #[serde(rename = "orderPx", skip_serializing_if = "Option::is_none")]
+    order_px: Option<String>,
+    #[serde(rename = "triggerPxType", skip_serializing_if = "Option::is_none")]
+    trigger_px_type: Option<&'static str>,
+    #[serde(rename = "callbackRatio", skip_serializing_if = "Option::is_none")]
+    callback_ratio: Option<String>,
+    #[serde(rename = "callbackSpread", skip_serializing_if = "Option::is_none")]
+    callback_spread: Option<String>,
+    #[serde(rename = "activePx", skip_serializing_if = "Option::is_none")]
+    active_px: Option<String>,
+    #[serde(rename = "pxVar", skip_serializing_if = "Option::is_none")]
+    px_var: Option<String>,
+    #[serde(rename = "pxSpread", skip_serializing_if = "Option::is_none")]
+    px_spread: Option<String>,
+    #[serde(rename = "szLimit", skip_serializing_if = "Option::is_none")]
+    sz_limit: Option<String>,
+    #[serde(rename = "pxLimit", skip_serializing_if = "Option::is_none")]
+    px_limit: Option<String>,
+    #[serde(rename = "timeInterval", skip_serializing_if = "Option::is_none")]
+    time_interval: Option<String>,
+}
+
+impl<'a> From<&'a AlgoOrderRequest> for InstAlgoRequest<'a> {
+    fn from(order: &'a AlgoOrderRequest) -> Self {
+        let mut request = InstAlgoRequest {
+            inst_id: &order.inst_id,
+            td_mode: order.td_mode.as_str(),
+            side: order.side.as_str(),
+            pos_side: order.pos_side.as_ref().map(|p| p.as_str()),
+            ord_type: order.params.ord_type().as_str(),
+            sz: order.sz.to_plain_string(),
+            reduce_only: order.reduce_only,
+            algo_cl_ord_id: order.algo_cl_ord_id.as_deref(),
+            ..Default::default()
+        };
+        let tp = |r: &mut InstAlgoRequest, tp: &TpSl| {
+            r.tp_trigger_px = Some(tp.trigger_px.to_plain_string());
+            r.tp_ord_px = Some(tp.order_px.to_param());
+            r.tp_trigger_px_type = tp.trigger_px_type.as_ref().map(|t| t.as_str());
+        };
+        let sl = |r: &mut InstAlgoRequest, sl: &TpSl| {
+            r.sl_trigger_px = Some(sl.trigger_px.to_plain_string());
+            r.sl_ord_px = Some(sl.order_px.to_param());
+            r.sl_trigger_px_type = sl.trigger_px_type.as_ref().map(|t| t.as_str());
+        };
+        match &order.params {
+            AlgoParams::Conditional { tp: take, sl: stop } => {
+                if let Some(take) = take {
+                    tp(&mut request, take);
+                }
+                if let Some(stop) = stop {
+                    sl(&mut request, stop);
+                }
+            }
+            AlgoParams::Oco { tp: take, sl: stop } => {
+                tp(&mut request, take);
+                sl(&mut request, stop);
+            }
+            AlgoParams::Trigger(trigger) => {
+                request.trigger_px = Some(trigger.trigger_px.to_plain_string());
+                request.order_px = Some(trigger.order_px.to_param());
+                request.trigger_px_type = trigger.trigger_px_type.as_ref().map(|t| t.as_str());
+            }
+            AlgoParams::MoveOrderStop { callback, active_px } => {
+                match callback {
+                    PxDistance::Ratio(ratio) => request.callback_ratio = Some(ratio.to_plain_string()),
+                    PxDistance::Spread(spread) => request.callback_spread = Some(spread.to_plain_string()),
+                }
+                request.active_px = active_px.as_ref().map(|p| p.to_plain_string());
+            }
+            AlgoParams::Twap {
+                px_variance,
+                sz_limit,
+                px_limit,
+                time_interval,
+            } => {
+                match px_variance {
+                    PxDistance::Ratio(ratio) => request.px_var = Some(ratio.to_plain_string()),
+                    PxDistance::Spread(spread) => request.px_spread = Some(spread.to_plain_string()),
+                }
+                request.sz_limit = Some(sz_limit.to_plain_string());
+                request.px_limit = Some(px_limit.to_plain_string());
+                request.time_interval = Some(time_interval.to_string());
+            }
+        }
+        request
+    }
+}
+
+#[derive(Serialize)]
+struct InstCancelAlgoRequest<'a> {
+    #[serde(rename = "instId")]
+    inst_id: &'a str,
+    #[serde(rename = "algoId")]
+    algo_id: &'a str,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct InstAlgoAck {
+    #[serde(rename = "algoId", default)]
+    pub algo_id: String,
+    #[serde(rename = "algoClOrdId", default)]
+    pub algo_cl_ord_id: String,
+    #[serde(rename = "sCode")]
+    pub s_code: String,
+    #[serde(rename = "sMsg", default)]
+    pub s_msg: String,
+}
+
+impl From<InstAlgoAck> for AlgoAck {
+    fn from(ack: InstAlgoAck) -> Self {
+        AlgoAck {
+            algo_id: ack.algo_id,
+            algo_cl_ord_id: Some(ack.algo_cl_ord_id).filter(|s| !s.is_empty()),
+            s_code: ack.s_code,
+            s_msg: ack.s_msg,
+        }
+    }
+}
+
+/// 策略委托，/api/v5/trade/orders-algo-pending 和 orders-algo-history 的格式相同
+#[derive(Deserialize)]
+pub(crate) struct InstAlgoOrder {
+    #[serde(rename = "algoId")]
+    pub algo_id: String,
+    #[serde(rename = "algoClOrdId", default)]
+    pub algo_cl_ord_id: String,
+    #[serde(rename = "instId")]
+    pub inst_id: String,
+    #[serde(rename = "instType")]
+    pub inst_type: String,
+    #[serde(rename = "ordType")]
+    pub ord_type: String,
+    pub side: String,
+    #[serde(rename = "posSide", default)]
+    pub pos_side: String,
+    #[serde(rename = "tdMode")]
+    pub td_mode: String,
+    pub sz: String,
+    pub state: String,
+    #[serde(rename = "tpTriggerPx", default)]
+    pub tp_trigger_px: String,
+    #[serde(rename = "tpOrdPx", default)]
+    pub tp_ord_px: String,
+    #[serde(rename = "slTriggerPx", default)]
+    pub sl_trigger_px: String,
+    #[serde(rename = "slOrdPx", default)]
+    pub sl_ord_px: String,
+    #[serde(rename = "triggerPx", default)]
+    pub trigger_px: String,
+    #[serde(rename = "ordPx", default)]
+    pub ord_px: String,
+    #[serde(rename = "callbackRatio", default)]
+    pub callback_ratio: String,
+    #[serde(rename = "callbackSpread", default)]
+    pub callback_spread: String,
+    #[serde(rename = "activePx", default)]
+    pub active_px: String,
+    #[serde(rename = "ordId", default)]
+    pub ord_id: String,
+    #[serde(rename = "cTime", default)]
+    pub c_time: String,
+    #[serde(rename = "uTime", default)]
+    pub u_time: String,
+}
+
+impl InstAlgoOrder {
+    pub(crate) fn into_algo_order(self) -> Option<AlgoOrder> {
+        let parse_optional = |s: &str| -> Option<BigDecimal> { BigDecimal::from_str(s).ok() };
+        let non_empty = |s: String| -> Option<String> { Some(s).filter(|s| !s.is_empty()) };
+
+        let category = Category::from_str(&self.inst_type);
+        let ord_type = AlgoOrderType::from_str(&self.ord_type);
+        let side = Side::from_str(&self.side);
+        let td_mode = TdMode::from_str(&self.td_mode);
+        let state = AlgoState::from_str(&self.state);
+        let sz = parse_optional(&self.sz);
+        let (Some(category), Some(ord_type), Some(side), Some(td_mode), Some(state), Some(sz)) =
+            (category, ord_type, side, td_mode, state, sz)
+        else {
+            warn!(
+                "invalid algo order {} of {}: instType={}, ordType={}, side={}, tdMode={}, state={}, sz={}",
+                self.algo_id, self.inst_id, self.inst_type, self.ord_type, self.side, self.td_mode, self.state, self.sz
+            );
+            return None;
+        };
+        Some(AlgoOrder {
+            algo_id: self.algo_id,
+            algo_cl_ord_id: non_empty(self.algo_cl_ord_id),
+            inst_id: self.inst_id,
+            category,
+            ord_type,
+            side,
+            pos_side: PosSide::from_str(&self.pos_side),
+            td_mode,
+            sz,
+            state,
+            tp_trigger_px: parse_optional(&self.tp_trigger_px),
+            tp_ord_px: parse_optional(&self.tp_ord_px),
+            sl_trigger_px: parse_optional(&self.sl_trigger_px),
+            sl_ord_px: parse_optional(&self.sl_ord_px),
+            trigger_px: parse_optional(&self.trigger_px),
+            ord_px: parse_optional(&self.ord_px),
+            callback_ratio: parse_optional(&self.callback_ratio),
+            callback_spread: parse_optional(&self.callback_spread),
+            active_px: parse_optional(&self.active_px),
+            ord_id: non_empty(self.ord_id),
+            c_time: self.c_time.parse().unwrap_or(0),
+            u_time: self.u_time.parse().unwrap_or(0),
+        })
+    }
+}
+
+impl OkxClient {
+    /// 策略委托下单
+    /// /api/v5/trade/order-algo
+    /// 发送前按 symbol 的交易规则检查，被拒绝时返回 sCode/sMsg 对应的错误
+    pub async fn place_algo_order(&self, symbol: &Symbol, order: &AlgoOrderRequest) -> Result<AlgoAck, OkxError> {
+        self.check_trading_allowed()?;
+        order.validate(symbol).map_err(|reason| OkxError::InvalidOrder {
+            inst_id: order.inst_id.clone(),
+            reason,
+        })?;
+        let acks: Vec<InstAlgoAck> = self.post_private("/api/v5/trade/order-algo", &InstAlgoRequest::from(order)).await?;
+        let Some(ack) = acks.into_iter().next().map(AlgoAck::from) else {
+            return Err(OkxError::Api {
+                code: ApiCode::Other("1".to_string()),
+                msg: "empty algo order acknowledgement".to_string(),
+            });
+        };
+        if !ack.is_success() {
+            return Err(OkxError::Api {
+                code: ApiCode::from_code(&ack.s_code),
+                msg: ack.s_msg,
+            });
+        }
+        Ok(ack)
+    }
+
+    /// 撤销策略委托，每次最多 10 个
+    /// /api/v5/trade/cancel-algos
+    pub async fn cancel_algos(&self, cancels: &[CancelAlgoRequest]) -> Result<Vec<AlgoAck>, OkxError> {
+        let body: Vec<InstCancelAlgoRequest> = cancels
+            .iter()
+            .map(|c| InstCancelAlgoRequest {
+                inst_id: &c.inst_id,
+                algo_id: &c.algo_id,
+            })
+            .collect();
+        let acks: Vec<InstAlgoAck> = self.post_private("/api/v5/trade/cancel-algos", &body).await?;
+        Ok(acks.into_iter().map(AlgoAck::from).collect())
+    }
+
+    /// 未完成的策略委托
+    /// /api/v5/trade/orders-algo-pending
+    pub async fn get_algo_orders_pending(&self, ord_type: AlgoOrderType, inst_id: Option<&str>) -> Result<Vec<AlgoOrder>, OkxError> {
+        let mut query = vec![("ordType", ord_type.as_str())];
+        if let Some(inst_id) = inst_id {
+            query.push(("instId", inst_id));
+        }
+        let data: Vec<InstAlgoOrder> = self.get_private("/api/v5/trade/orders-algo-pending", &query).await?;
+        Ok(data.into_iter().filter_map(InstAlgoOrder::into_algo_order).collect())
+    }
+
+    /// 历史策略委托，最近 3 个月
+    /// /api/v5/trade/orders-algo-history
+    pub async fn get_algo_orders_history(&self, ord_type: AlgoOrderType, state: AlgoState) -> Result<Vec<AlgoOrder>, OkxError> {
+        let query = [("ordType", ord_type.as_str()), ("state", state.as_str())];
+        let data: Vec<InstAlgoOrder> = self.get_private("/api/v5/trade/orders-algo-history", &query).await?;
+        Ok(data.into_iter().filter_map(InstAlgoOrder::into_algo_order).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::{self, dec};
+    use crate::action::auth::Credentials;
+    use crate::action::client::{ClientConfig, Environment};
+    use crate::action::mock::MockServer;
+    use crate::model::algo::OrderPx;
+    use serde_json::json;
+
+    fn swap() -> Symbol {
+        fixture::swap("BTC-USDT-SWAP", "0.01", "0.01")
+    }
+
+    async fn client(routes: Vec<(&'static str, u16, String)>) -> (MockServer, OkxClient) {
+        let server = MockServer::start(routes).await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            credentials: Some(Credentials::new("key", "secret", "pass")),
+            environment: Environment::Demo,
+            ..Default::default()
+        })
+        .unwrap();
+        (server, client)
+    }
+
+    #[test]
+    fn test_request_fields_by_type() {
+        let oco = AlgoOrderRequest::new(
+            "BTC-USDT-SWAP",
+            TdMode::Cross,
+            Side::Sell,
+            dec("1"),
+            AlgoParams::Oco {
+                tp: TpSl::market(dec("35000")),
+                sl: TpSl {
+                    trigger_px: dec("25000"),
+                    order_px: OrderPx::Limit(dec("24900")),
+                    trigger_px_type: Some(crate::model::algo::TriggerPxType::Mark),
+                },
+            },
+        );
+        assert_eq!(
+            serde_json::to_value(InstAlgoRequest::from(&oco)).unwrap(),
+            json!({"instId": "BTC-USDT-SWAP", "tdMode": "cross", "side": "sell", "ordType": "oco", "sz": "1",
+                "tpTriggerPx": "35000", "tpOrdPx": "-1", "slTriggerPx": "25000", "slOrdPx": "24900", "slTriggerPxType": "mark"})
+        );
+
+        let twap = AlgoOrderRequest::new(
+            "BTC-USDT-SWAP",
+            TdMode::Cross,
+            Side::Buy,
+            dec("10"),
+            AlgoParams::Twap {
+                px_variance: PxDistance::Ratio(dec("0.001")),
+                sz_limit: dec("1"),
+                px_limit: dec("31000"),
+                time_interval: 10,
+            },
+        );
+        assert_eq!(
+            serde_json::to_value(InstAlgoRequest::from(&twap)).unwrap(),
+            json!({"instId": "BTC-USDT-SWAP", "tdMode": "cross", "side": "buy", "ordType": "twap", "sz": "10",
+                "pxVar": "0.001", "szLimit": "1", "pxLimit": "31000", "timeInterval": "10"})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_place_trailing_stop() {
+        let body = r#"{"code":"0","msg":"","data":[{"algoId":"12345689","clOrdId":"","algoClOrdId":"t1","sCode":"0","sMsg":""}]}"#;
+        let (server, client) = client(vec![("/api/v5/trade/order-algo", 200, body.to_string())]).await;
+
+        let order = AlgoOrderRequest {
+            reduce_only: Some(true),
+            algo_cl_ord_id: Some("t1".to_string()),
+            ..AlgoOrderRequest::new(
+                "BTC-USDT-SWAP",
+                TdMode::Cross,
+                Side::Sell,
+                dec("2"),
+                AlgoParams::MoveOrderStop {
+                    callback: PxDistance::Ratio(dec("0.05")),
+                    active_px: Some(dec("32000")),
+                },
+            )
+        };
+        let ack = client.place_algo_order(&swap(), &order).await.unwrap();
+        assert_eq!(ack.algo_id, "12345689");
+        assert_eq!(ack.algo_cl_ord_id.as_deref(), Some("t1"));
+
+        let sent: serde_json::Value = serde_json::from_str(&server.requests()[0].body).unwrap();
+        assert_eq!(sent["ordType"], "move_order_stop");
+        assert_eq!(sent["callbackRatio"], "0.05");
+        assert_eq!(sent["activePx"], "32000");
+        assert_eq!(sent["reduceOnly"], true);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_query_algos() {
+        let cancel = r#"{"code":"2","msg":"","data":[{"algoId":"1","sCode":"0","sMsg":""},{"algoId":"2","sCode":"51400","sMsg":"Cancellation failed as the order has been filled, canceled or does not exist."}]}"#;
+        let pending = r#"{"code":"0","msg":"","data":[{"algoId":"1","algoClOrdId":"","instId":"BTC-USDT-SWAP","instType":"SWAP","ordType":"conditional","side":"sell","posSide":"net","tdMode":"cross","sz":"1","state":"live","tpTriggerPx":"","tpOrdPx":"","slTriggerPx":"25000","slOrdPx":"-1","triggerPx":"","ordPx":"","ordId":"","cTime":"1","uTime":"2"}]}"#;
+        let (server, client) = client(vec![
+            ("/api/v5/trade/cancel-algos", 200, cancel.to_string()),
+            ("/api/v5/trade/orders-algo-pending", 200, pending.to_string()),
+        ])
+        .await;
+
+        let acks = client
+            .cancel_algos(&[
+                CancelAlgoRequest {
+                    inst_id: "BTC-USDT-SWAP".to_string(),
+                    algo_id: "1".to_string(),
+                },
+                CancelAlgoRequest {
+                    inst_id: "BTC-USDT-SWAP".to_string(),
+                    algo_id: "2".to_string(),
+                },
+            ])
+            .await
+            .unwrap();
+        assert!(acks[0].is_success());
+        assert_eq!(acks[1].s_code, "51400");
+
+        let orders = client
+            .get_algo_orders_pending(AlgoOrderType::Conditional, Some("BTC-USDT-SWAP"))
+            .await
+            .unwrap();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].state, AlgoState::Live);
+        assert_eq!(orders[0].sl_ord_px, Some(dec("-1")));
+        assert_eq!(orders[0].tp_trigger_px, None);
+
+        let requests = server.requests();
+        assert_eq!(
+            requests[0].body,
+            r#"[{"instId":"BTC-USDT-SWAP","algoId":"1"},{"instId":"BTC-USDT-SWAP","algoId":"2"}]"#
+        );
+        assert_eq!(
+            requests[1].target,
+            "/api/v5/trade/orders-algo-pending?ordType=conditional&instId=BTC-USDT-SWAP"
+        );
+    }
+}
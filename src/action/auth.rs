@@ -0,0 +1,100 @@
+use std::fmt;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+/// 私有接口使用的 API Key
+/// 在 OKX 网页端创建 API Key 时会同时得到 key、secret，passphrase 由用户自己设置
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub api_key: String,
+    pub secret_key: String,
+    pub passphrase: String,
+}
+
+impl Credentials {
+    pub fn new(api_key: &str, secret_key: &str, passphrase: &str) -> Self {
+        Credentials {
+            api_key: api_key.to_string(),
+            secret_key: secret_key.to_string(),
+            passphrase: passphrase.to_string(),
+        }
+    }
+
+    /// 对请求签名
+    /// 见 [`sign`]
+    pub fn sign(&self, timestamp: &str, method: &str, request_path: &str, body: &str) -> String {
+        sign(&self.secret_key, timestamp, method, request_path, body)
+    }
+}
+
+// 避免在日志中打印出 secret 和 passphrase
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("api_key", &self.api_key)
+            .field("secret_key", &"***")
+            .field("passphrase", &"***")
+            .finish()
+    }
+}
+
+/// OK-ACCESS-SIGN 的计算方法
+/// sign = Base64(HMAC_SHA256(timestamp + method + requestPath + body, SecretKey))
+/// method 为大写的 GET/POST，requestPath 包含 query 参数，如 /api/v5/account/balance?ccy=BTC
+/// GET 请求的 body 为空字符串
+pub fn sign(secret_key: &str, timestamp: &str, method: &str, request_path: &str, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret_key.as_bytes()).expect("HMAC can take key of any size");
+    mac.update(timestamp.as_bytes());
+    mac.update(method.as_bytes());
+    mac.update(request_path.as_bytes());
+    mac.update(body.as_bytes());
+    STANDARD.encode(mac.finalize().into_bytes())
+}
+
+/// OK-ACCESS-TIMESTAMP 使用的 ISO 格式时间，精确到毫秒，如 2020-12-08T09:08:57.715Z
+pub fn timestamp() -> String {
+    Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 文档示例中的 SecretKey
+    const SECRET: &str = "22582BD0CFF14C41EDBF1AB98506286D";
+
+    #[test]
+    fn test_sign_get() {
+        // GET /api/v5/account/balance?ccy=BTC
+        let sign = sign(SECRET, "2020-12-08T09:08:57.715Z", "GET", "/api/v5/account/balance?ccy=BTC", "");
+        assert_eq!(sign, "HiZhvSfMtWJA3uUIVXV3a/bSXNPCWvYFXoGCVS8V4zY=");
+    }
+
+    #[test]
+    fn test_sign_post() {
+        // POST /api/v5/trade/order
+        let body = r#"{"instId":"BTC-USDT","tdMode":"cash","side":"buy","ordType":"market","sz":"100"}"#;
+        let sign = sign(SECRET, "2020-12-08T09:08:57.715Z", "POST", "/api/v5/trade/order", body);
+        assert_eq!(sign, "gLoEHvSwOThUNff+Hv0T7J9JHtjIwa/gKHKIkc4vvhM=");
+    }
+
+    #[test]
+    fn test_timestamp_format() {
+        let ts = timestamp();
+        assert_eq!(ts.len(), "2020-12-08T09:08:57.715Z".len());
+        assert!(ts.ends_with('Z'));
+        assert!(chrono::DateTime::parse_from_rfc3339(&ts).is_ok());
+    }
+
+    #[test]
+    fn test_debug_hides_secret() {
+        let credentials = Credentials::new("key", "secret", "passphrase");
+        let debug = format!("{:?}", credentials);
+        assert!(!debug.contains("\"secret\""));
+        assert!(!debug.contains("\"passphrase\""));
+    }
+}
@@ -0,0 +1,106 @@
+use log::warn;
+use serde::Deserialize;
+
+use crate::action::client::OkxClient;
+use crate::action::error::OkxError;
+use crate::model::order_book::{BookUpdate, Level, OrderBook};
+
+/// 深度数据，REST 和 WebSocket 的格式相同
+/// 每一档为 [价格, 数量, 已废弃字段, 订单数]
+#[derive(Deserialize)]
+pub(crate) struct InstBook {
+    pub asks: Vec<Vec<String>>,
+    pub bids: Vec<Vec<String>>,
+    pub ts: String,
+    #[serde(default)]
+    pub checksum: Option<i32>,
+    #[serde(rename = "seqId", default)]
+    pub seq_id: Option<i64>,
+    #[serde(rename = "prevSeqId", default)]
+    pub prev_seq_id: Option<i64>,
+}
+
+impl InstBook {
+    /// 解析失败时返回出错的字段和值
+    pub(crate) fn to_update(&self) -> Result<BookUpdate, String> {
+        let parse_levels = |levels: &[Vec<String>]| -> Result<Vec<Level>, String> {
+            levels
+                .iter()
+                .map(|l| {
+                    let (px, sz) = match (l.first(), l.get(1)) {
+                        (Some(px), Some(sz)) => (px, sz),
+                        _ => return Err(format!("level {:?}", l)),
+                    };
+                    let orders = l.get(3).and_then(|o| o.parse().ok()).unwrap_or(0);
+                    Level::parse(px, sz, orders).ok_or_else(|| format!("level {:?}", l))
+                })
+                .collect()
+        };
+        Ok(BookUpdate {
+            bids: parse_levels(&self.bids)?,
+            asks: parse_levels(&self.asks)?,
+            ts: self.ts.parse().map_err(|_| format!("ts {:?}", self.ts))?,
+            seq_id: self.seq_id,
+            prev_seq_id: self.prev_seq_id,
+            checksum: self.checksum,
+        })
+    }
+}
+
+impl OkxClient {
+    /// 获取产品深度快照
+    /// /api/v5/market/books
+    /// depth 为买卖各多少档，最大 400，默认 1
+    pub async fn get_order_book(&self, inst_id: &str, depth: Option<u32>) -> Result<Option<OrderBook>, OkxError> {
+        let depth = depth.map(|d| d.to_string());
+        let mut query = vec![("instId", inst_id)];
+        if let Some(depth) = &depth {
+            query.push(("sz", depth));
+        }
+        let data: Vec<InstBook> = self.get("/api/v5/market/books", &query).await?;
+        let Some(book) = data.first() else {
+            return Ok(None);
+        };
+        let update = match book.to_update() {
+            Ok(update) => update,
+            Err(e) => {
+                warn!("invalid order book of {}: {}", inst_id, e);
+                return Ok(None);
+            }
+        };
+        let mut order_book = OrderBook::new(inst_id);
+        // REST 快照没有校验和，不会返回错误
+        if let Err(e) = order_book.apply_snapshot(update) {
+            warn!("invalid order book of {}: {}", inst_id, e);
+            return Ok(None);
+        }
+        Ok(Some(order_book))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::client::ClientConfig;
+    use crate::action::mock::MockServer;
+    use bigdecimal::BigDecimal;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    async fn test_get_order_book() {
+        let body = r#"{"code":"0","msg":"","data":[{"asks":[["41006.8","0.60038921","0","1"],["41007","1.2","0","3"]],"bids":[["41006.3","0.30178218","0","2"]],"ts":"1629966436396"}]}"#;
+        let server = MockServer::start(vec![("/api/v5/market/books", 200, body.to_string())]).await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let book = client.get_order_book("BTC-USDT", Some(20)).await.unwrap().unwrap();
+        assert_eq!(book.inst_id, "BTC-USDT");
+        assert_eq!(book.ts, 1629966436396);
+        assert_eq!(book.best_bid().unwrap().px, BigDecimal::from_str("41006.3").unwrap());
+        assert_eq!(book.asks().count(), 2);
+        assert_eq!(server.requests()[0].target, "/api/v5/market/books?instId=BTC-USDT&sz=20");
+    }
+}
@@ -0,0 +1,496 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use reqwest::{Method, Url};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::action::auth::{self, Credentials};
+use crate::action::error::{ApiCode, OkxError};
+use crate::action::rate_limit::{EndpointMetrics, RateLimitConfig, RateLimiter};
+use crate::action::retry::RetryPolicy;
+use crate::global;
+
+/// OKX 接口统一的返回包装
+/// {"code":"0","msg":"","data":[...]}
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct ApiResponse<T> {
+    pub code: String,
+    pub msg: String,
+    pub data: Vec<T>,
+}
+
+/// 交易环境
+/// Demo 为 OKX 模拟盘，REST 请求需要带上 x-simulated-trading: 1，WebSocket 使用单独的域名
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    Live,
+    Demo,
+}
+
+impl Environment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Environment::Live => "live",
+            Environment::Demo => "demo",
+        }
+    }
+
+    pub fn ws_public_url(&self) -> &'static str {
+        match self {
+            Environment::Live => global::WS_PUBLIC_HOST,
+            Environment::Demo => global::WS_DEMO_PUBLIC_HOST,
+        }
+    }
+
+    pub fn ws_private_url(&self) -> &'static str {
+        match self {
+            Environment::Live => global::WS_PRIVATE_HOST,
+            Environment::Demo => global::WS_DEMO_PRIVATE_HOST,
+        }
+    }
+
+    pub fn ws_business_url(&self) -> &'static str {
+        match self {
+            Environment::Live => global::WS_BUSINESS_HOST,
+            Environment::Demo => global::WS_DEMO_BUSINESS_HOST,
+        }
+    }
+}
+
+/// OkxClient 的配置
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// REST 接口地址，如 https://www.okx.com、https://aws.okx.com 或本地 mock 地址
+    pub base_url: String,
+    /// 单次请求的总超时时间
+    pub timeout: Duration,
+    /// 建立连接的超时时间
+    pub connect_timeout: Duration,
+    /// 请求头中的 User-Agent
+    pub user_agent: String,
+    /// 私有接口使用的 API Key，只访问公共接口时可以为空
+    pub credentials: Option<Credentials>,
+    /// 实盘还是模拟盘
+    pub environment: Environment,
+    /// 是否允许在实盘下单，只有配置中显式指定 live 时才会打开
+    /// 模拟盘下单不受该开关限制
+    pub live_trading: bool,
+    /// 各接口的限速规则
+    pub rate_limit: RateLimitConfig,
+    /// GET 请求失败后的重试策略
+    /// POST 请求（如下单）不是幂等的，不会自动重试
+    pub retry: RetryPolicy,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        ClientConfig {
+            base_url: global::REST_API_HOST.to_string(),
+            timeout: Duration::from_secs(10),
+            connect_timeout: Duration::from_secs(5),
+            user_agent: format!("okx-rs/{}", env!("CARGO_PKG_VERSION")),
+            credentials: None,
+            environment: Environment::Live,
+            live_trading: false,
+            rate_limit: RateLimitConfig::default(),
+            retry: RetryPolicy::default(),
+        }
+    }
+}
+
+/// OKX REST 客户端
+/// 内部持有带连接池的 reqwest::Client，clone 代价很小，可在多个任务间共享
+/// 所有 REST 接口都以方法的形式挂在该类型上，见 action 下的各个模块
+#[derive(Clone)]
+pub struct OkxClient {
+    http: reqwest::Client,
+    config: ClientConfig,
+    limiter: Arc<RateLimiter>,
+}
+
+impl OkxClient {
+    pub fn new(config: ClientConfig) -> Result<Self, OkxError> {
+        let http = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .connect_timeout(config.connect_timeout)
+            .user_agent(config.user_agent.clone())
+            .build()?;
+        let limiter = Arc::new(RateLimiter::new(config.rate_limit.clone()));
+        Ok(OkxClient { http, config, limiter })
+    }
+
+    pub fn config(&self) -> &ClientConfig {
+        &self.config
+    }
+
+    /// 各接口的限速统计
+    pub fn rate_limit_metrics(&self) -> Vec<(String, EndpointMetrics)> {
+        self.limiter.metrics()
+    }
+
+    pub fn environment(&self) -> Environment {
+        self.config.environment
+    }
+
+    /// 下单类接口在发送前调用，实盘环境下未打开 live_trading 时拒绝下单
+    pub fn check_trading_allowed(&self) -> Result<(), OkxError> {
+        match self.config.environment {
+            Environment::Demo => Ok(()),
+            Environment::Live if self.config.live_trading => Ok(()),
+            Environment::Live => Err(OkxError::LiveTradingDisabled),
+        }
+    }
+
+    /// 拼接完整的请求地址，path 形如 /api/v5/market/tickers
+    fn url(&self, path: &str, query: &[(&str, &str)]) -> Result<Url, OkxError> {
+        let base = self.config.base_url.trim_end_matches('/');
+        let raw = format!("{}{}", base, path);
+        let mut url = Url::parse(&raw).map_err(|_| OkxError::InvalidUrl(raw))?;
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+        Ok(url)
+    }
+
+    /// 发送公共 GET 请求并解析 data 字段
+    pub async fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<Vec<T>, OkxError> {
+        self.request(Method::GET, path, query, None, false).await
+    }
+
+    /// 发送需要签名的 GET 请求，用于账户、交易等私有接口
+    pub async fn get_private<T: DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<Vec<T>, OkxError> {
+        self.request(Method::GET, path, query, None, true).await
+    }
+
+    /// 发送需要签名的 POST 请求，body 序列化为 JSON
+    pub async fn post_private<T: DeserializeOwned, B: Serialize>(&self, path: &str, body: &B) -> Result<Vec<T>, OkxError> {
+        let body = serde_json::to_string(body).map_err(OkxError::Encode)?;
+        self.request(Method::POST, path, &[], Some(body), true).await
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<String>,
+        signed: bool,
+    ) -> Result<Vec<T>, OkxError> {
+        if method != Method::GET {
+            return self.send(method, path, query, body.as_deref(), signed).await;
+        }
+        self.config
+            .retry
+            .run(path, || self.send(method.clone(), path, query, body.as_deref(), signed))
+            .await
+    }
+
+    /// 发送一次请求，每次都重新计算签名
+    async fn send<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, &str)],
+        body: Option<&str>,
+        signed: bool,
+    ) -> Result<Vec<T>, OkxError> {
+        let url = self.url(path, query)?;
+        // 私有接口按 User ID 限速，这里用 API Key 区分用户
+        let scope = match (signed, &self.config.credentials) {
+            (true, Some(credentials)) => Some(credentials.api_key.as_str()),
+            _ => None,
+        };
+        self.limiter.acquire(path, scope).await?;
+
+        let mut builder = self.http.request(method.clone(), url.clone());
+        if self.config.environment == Environment::Demo {
+            builder = builder.header("x-simulated-trading", "1");
+        }
+        if signed {
+            let credentials = self.config.credentials.as_ref().ok_or(OkxError::MissingCredentials)?;
+            // 签名使用的 requestPath 需要带上 query 参数
+            let request_path = match url.query() {
+                Some(query) => format!("{}?{}", url.path(), query),
+                None => url.path().to_string(),
+            };
+            let timestamp = auth::timestamp();
+            let sign = credentials.sign(&timestamp, method.as_str(), &request_path, body.unwrap_or_default());
+            builder = builder
+                .header("OK-ACCESS-KEY", &credentials.api_key)
+                .header("OK-ACCESS-SIGN", sign)
+                .header("OK-ACCESS-TIMESTAMP", timestamp)
+                .header("OK-ACCESS-PASSPHRASE", &credentials.passphrase);
+        }
+        if let Some(body) = body {
+            builder = builder.header("Content-Type", "application/json").body(body.to_string());
+        }
+
+        let response = builder.send().await?;
+        let status = response.status();
+        let body = response.text().await?;
+        parse_response(status.as_u16(), body)
+    }
+}
+
+/// 解析 OKX 的返回内容
+/// 限频等错误时 OKX 会同时返回非 2xx 的状态码和错误包装，此时优先使用包装中的 code
+fn parse_response<T: DeserializeOwned>(status: u16, body: String) -> Result<Vec<T>, OkxError> {
+    if !(200..300).contains(&status) {
+        return match serde_json::from_str::<ApiResponse<serde_json::Value>>(&body) {
+            Ok(api_response) if api_response.code != "0" => Err(OkxError::Api {
+                code: ApiCode::from_code(&api_response.code),
+                msg: api_response.msg,
+            }),
+            _ => Err(OkxError::Status { status, body }),
+        };
+    }
+
+    // 先只解析外层包装，这样 data 结构变化时也能拿到 code
+    let api_response: ApiResponse<serde_json::Value> = match serde_json::from_str(&body) {
+        Ok(api_response) => api_response,
+        Err(source) => return Err(OkxError::Decode { source, body }),
+    };
+    // code 为 "0" 表示成功
+    // 下单、撤单等接口失败时 code 为 "1"，批量部分成功时为 "2"，每个订单的结果在 data 的 sCode 中，交给调用方处理
+    let per_order = (api_response.code == "1" || api_response.code == "2")
+        && !api_response.data.is_empty()
+        && api_response.data.iter().all(|d| d.get("sCode").is_some());
+    if api_response.code != "0" && !per_order {
+        return Err(OkxError::Api {
+            code: ApiCode::from_code(&api_response.code),
+            msg: api_response.msg,
+        });
+    }
+    match serde_json::from_str::<ApiResponse<T>>(&body) {
+        Ok(api_response) => Ok(api_response.data),
+        Err(source) => Err(OkxError::Decode { source, body }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::mock::MockServer;
+    use crate::action::rate_limit::{Limit, LimitMode};
+
+    #[tokio::test]
+    async fn test_get_uses_configured_host() {
+        let server = MockServer::start(vec![(
+            "/api/v5/public/time",
+            200,
+            r#"{"code":"0","msg":"","data":[{"ts":"1597026383085"}]}"#.to_string(),
+        )])
+        .await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let data: Vec<serde_json::Value> = client.get("/api/v5/public/time", &[("a", "b")]).await.unwrap();
+        assert_eq!(data[0]["ts"], "1597026383085");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "GET");
+        assert_eq!(requests[0].target, "/api/v5/public/time?a=b");
+        assert!(requests[0].header("user-agent").unwrap().starts_with("okx-rs/"));
+    }
+
+    #[tokio::test]
+    async fn test_get_maps_api_error() {
+        let server = MockServer::start(vec![(
+            "/api/v5/public/time",
+            200,
+            r#"{"code":"50011","msg":"Too Many Requests","data":[]}"#.to_string(),
+        )])
+        .await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            retry: RetryPolicy::none(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let err = client.get::<serde_json::Value>("/api/v5/public/time", &[]).await.unwrap_err();
+        assert!(err.is_rate_limited());
+    }
+
+    #[tokio::test]
+    async fn test_get_retries_transient_errors() {
+        let server = MockServer::start(vec![
+            ("/api/v5/public/time", 503, "service unavailable".to_string()),
+            ("/api/v5/public/time", 429, r#"{"code":"50011","msg":"Too Many Requests","data":[]}"#.to_string()),
+            ("/api/v5/public/time", 200, r#"{"code":"0","msg":"","data":[{"ts":"1"}]}"#.to_string()),
+        ])
+        .await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            retry: RetryPolicy {
+                base_delay: Duration::from_millis(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .unwrap();
+
+        let data: Vec<serde_json::Value> = client.get("/api/v5/public/time", &[]).await.unwrap();
+        assert_eq!(data.len(), 1);
+        assert_eq!(server.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_post_not_retried() {
+        let server = MockServer::start(vec![
+            ("/api/v5/trade/order", 503, "service unavailable".to_string()),
+            ("/api/v5/trade/order", 200, r#"{"code":"0","msg":"","data":[]}"#.to_string()),
+        ])
+        .await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            credentials: Some(Credentials::new("key", "secret", "passphrase")),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let err = client
+            .post_private::<serde_json::Value, _>("/api/v5/trade/order", &serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, OkxError::Status { status: 503, .. }));
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_private_request_signed() {
+        let server = MockServer::start(vec![(
+            "/api/v5/trade/order",
+            200,
+            r#"{"code":"0","msg":"","data":[]}"#.to_string(),
+        )])
+        .await;
+        let credentials = Credentials::new("key", "secret", "passphrase");
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            credentials: Some(credentials.clone()),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let body = serde_json::json!({"instId": "BTC-USDT"});
+        let _: Vec<serde_json::Value> = client.post_private("/api/v5/trade/order", &body).await.unwrap();
+
+        let request = &server.requests()[0];
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.body, r#"{"instId":"BTC-USDT"}"#);
+        assert_eq!(request.header("OK-ACCESS-KEY"), Some("key"));
+        assert_eq!(request.header("OK-ACCESS-PASSPHRASE"), Some("passphrase"));
+        let timestamp = request.header("OK-ACCESS-TIMESTAMP").unwrap();
+        let expected = credentials.sign(timestamp, "POST", "/api/v5/trade/order", &request.body);
+        assert_eq!(request.header("OK-ACCESS-SIGN"), Some(expected.as_str()));
+    }
+
+    #[tokio::test]
+    async fn test_private_request_requires_credentials() {
+        let client = OkxClient::new(ClientConfig::default()).unwrap();
+        let err = client.get_private::<serde_json::Value>("/api/v5/account/balance", &[]).await.unwrap_err();
+        assert!(matches!(err, OkxError::MissingCredentials));
+    }
+
+    #[tokio::test]
+    async fn test_demo_environment_header() {
+        let server = MockServer::start(vec![(
+            "/api/v5/public/time",
+            200,
+            r#"{"code":"0","msg":"","data":[]}"#.to_string(),
+        )])
+        .await;
+        let live = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            ..Default::default()
+        })
+        .unwrap();
+        let demo = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            environment: Environment::Demo,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let _: Vec<serde_json::Value> = live.get("/api/v5/public/time", &[]).await.unwrap();
+        let _: Vec<serde_json::Value> = demo.get("/api/v5/public/time", &[]).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests[0].header("x-simulated-trading"), None);
+        assert_eq!(requests[1].header("x-simulated-trading"), Some("1"));
+    }
+
+    #[tokio::test]
+    async fn test_requests_go_through_rate_limiter() {
+        let server = MockServer::start(vec![(
+            "/api/v5/market/tickers",
+            200,
+            r#"{"code":"0","msg":"","data":[]}"#.to_string(),
+        )])
+        .await;
+        let mut rate_limit = RateLimitConfig {
+            mode: LimitMode::Reject,
+            ..Default::default()
+        };
+        rate_limit.limits.insert("/api/v5/market/tickers".to_string(), Limit::new(1, 60_000));
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            rate_limit,
+            ..Default::default()
+        })
+        .unwrap();
+
+        let _: Vec<serde_json::Value> = client.get("/api/v5/market/tickers", &[]).await.unwrap();
+        let err = client.get::<serde_json::Value>("/api/v5/market/tickers", &[]).await.unwrap_err();
+        assert!(matches!(err, OkxError::RateLimitExceeded { .. }));
+        // 被本地拒绝的请求不会发到服务端
+        assert_eq!(server.requests().len(), 1);
+
+        let metrics = client.rate_limit_metrics();
+        assert_eq!(metrics[0].1.allowed, 1);
+        assert_eq!(metrics[0].1.rejected, 1);
+    }
+
+    #[test]
+    fn test_check_trading_allowed() {
+        let client = |environment, live_trading| {
+            OkxClient::new(ClientConfig {
+                environment,
+                live_trading,
+                ..Default::default()
+            })
+            .unwrap()
+        };
+        assert!(client(Environment::Demo, false).check_trading_allowed().is_ok());
+        assert!(client(Environment::Live, true).check_trading_allowed().is_ok());
+        assert!(matches!(
+            client(Environment::Live, false).check_trading_allowed(),
+            Err(OkxError::LiveTradingDisabled)
+        ));
+    }
+
+    #[test]
+    fn test_parse_response_errors() {
+        // 限频时返回 429 和错误包装
+        let err = parse_response::<serde_json::Value>(429, r#"{"code":"50011","msg":"Too Many Requests","data":[]}"#.to_string()).unwrap_err();
+        assert!(matches!(err, OkxError::Api { code: ApiCode::RateLimited, .. }));
+
+        let err = parse_response::<serde_json::Value>(502, "<html>bad gateway</html>".to_string()).unwrap_err();
+        assert!(matches!(err, OkxError::Status { status: 502, .. }));
+
+        // data 结构变化时保留原始内容
+        let body = r#"{"code":"0","msg":"","data":[{"ts":1}]}"#;
+        let err = parse_response::<Vec<String>>(200, body.to_string()).unwrap_err();
+        match err {
+            OkxError::Decode { body: raw, .. } => assert_eq!(raw, body),
+            other => panic!("unexpected error: {}", other),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use std::fmt;
+use std::time::Duration;
+
+/// OKX 返回的业务错误码
+/// 完整列表见 https://www.okx.com/docs-v5/zh/#error-code
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiCode {
+    /// 50001 服务暂时不可用
+    ServiceUnavailable,
+    /// 50004 接口请求超时
+    Timeout,
+    /// 50011 请求频率过高
+    RateLimited,
+    /// 50013 系统繁忙
+    SystemBusy,
+    /// 51000 参数错误
+    InvalidParameter,
+    /// 51001 产品不存在
+    InstrumentNotExist,
+    /// 其他未单独列出的错误码
+    Other(String),
+}
+
+impl ApiCode {
+    pub fn from_code(code: &str) -> ApiCode {
+        match code {
+            "50001" => ApiCode::ServiceUnavailable,
+            "50004" => ApiCode::Timeout,
+            "50011" => ApiCode::RateLimited,
+            "50013" => ApiCode::SystemBusy,
+            "51000" => ApiCode::InvalidParameter,
+            "51001" => ApiCode::InstrumentNotExist,
+            other => ApiCode::Other(other.to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            ApiCode::ServiceUnavailable => "50001",
+            ApiCode::Timeout => "50004",
+            ApiCode::RateLimited => "50011",
+            ApiCode::SystemBusy => "50013",
+            ApiCode::InvalidParameter => "51000",
+            ApiCode::InstrumentNotExist => "51001",
+            ApiCode::Other(code) => code,
+        }
+    }
+}
+
+/// REST 调用的错误类型
+#[derive(Debug)]
+pub enum OkxError {
+    /// 网络层错误：连接失败、超时等
+    Transport(reqwest::Error),
+    /// HTTP 状态码不是 2xx，且返回内容不是 OKX 的错误包装
+    Status { status: u16, body: String },
+    /// 返回内容无法解析为预期结构，保留原始内容方便排查
+    Decode { source: serde_json::Error, body: String },
+    /// 请求 body 序列化失败
+    Encode(serde_json::Error),
+    /// OKX 返回的业务错误，即返回包装中 code 不为 "0"
+    Api { code: ApiCode, msg: String },
+    /// 请求地址无法构造
+    InvalidUrl(String),
+    /// 调用私有接口但没有配置 API Key
+    MissingCredentials,
+    /// 本地限速器拒绝了请求，wait 为需要等待的时间
+    RateLimitExceeded { endpoint: String, wait: Duration },
+    /// 实盘环境下未显式打开下单开关
+    LiveTradingDisabled,
+    /// WebSocket 连接任务已经退出
+    WsClosed,
+    /// 订单不符合产品的交易规则，没有发送
+    InvalidOrder { inst_id: String, reason: String },
+    /// 下单结果不确定或一直查询不到订单结束，成交数量未知，没有拿到订单号时 ord_id 为空
+    OrderUnknown { inst_id: String, ord_id: String },
+}
+
+impl OkxError {
+    /// 是否被交易所限频
+    pub fn is_rate_limited(&self) -> bool {
+        match self {
+            OkxError::Api { code, .. } => *code == ApiCode::RateLimited,
+            OkxError::Status { status, .. } => *status == 429,
+            OkxError::RateLimitExceeded { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// 是否是暂时性的错误，重试可能成功
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            OkxError::Transport(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            OkxError::Status { status, .. } => *status == 429 || *status >= 500,
+            OkxError::Api { code, .. } => matches!(
+                code,
+                ApiCode::RateLimited | ApiCode::ServiceUnavailable | ApiCode::Timeout | ApiCode::SystemBusy
+            ),
+            OkxError::RateLimitExceeded { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// 请求可能已经被交易所处理但结果未知，下单时不能当作被拒绝
+    pub fn is_ambiguous(&self) -> bool {
+        match self {
+            OkxError::Transport(e) => !e.is_connect() && !e.is_builder(),
+            OkxError::Status { status, .. } => *status >= 500,
+            OkxError::Decode { .. } => true,
+            OkxError::Api { code, .. } => *code == ApiCode::Timeout,
+            _ => false,
+        }
+    }
+
+    /// 是否是返回结构变化导致的解析失败
+    pub fn is_decode(&self) -> bool {
+        matches!(self, OkxError::Decode { .. })
+    }
+}
+
+impl fmt::Display for OkxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OkxError::Transport(e) => write!(f, "http request failed: {}", e),
+            OkxError::Status { status, body } => write!(f, "http status {}: {}", status, body),
+            OkxError::Decode { source, body } => write!(f, "failed to decode response: {}, body: {}", source, body),
+            OkxError::Encode(e) => write!(f, "failed to encode request: {}", e),
+            OkxError::Api { code, msg } => write!(f, "okx api error: code={}, msg={}", code.as_str(), msg),
+            OkxError::InvalidUrl(url) => write!(f, "invalid url: {}", url),
+            OkxError::MissingCredentials => write!(f, "api credentials are required for private endpoints"),
+            OkxError::RateLimitExceeded { endpoint, wait } => {
+                write!(f, "rate limit exceeded on {}, need to wait {}ms", endpoint, wait.as_millis())
+            }
+            OkxError::LiveTradingDisabled => write!(f, "live trading is disabled, set environment: live in the config to enable it"),
+            OkxError::WsClosed => write!(f, "websocket connection task has stopped"),
+            OkxError::InvalidOrder { inst_id, reason } => write!(f, "invalid order for {}: {}", inst_id, reason),
+            OkxError::OrderUnknown { inst_id, ord_id } if ord_id.is_empty() => {
+                write!(f, "order of {} may have been placed, its state is unknown", inst_id)
+            }
+            OkxError::OrderUnknown { inst_id, ord_id } => write!(f, "state of order {} of {} is unknown", ord_id, inst_id),
+        }
+    }
+}
+
+impl std::error::Error for OkxError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OkxError::Transport(e) => Some(e),
+            OkxError::Decode { source, .. } => Some(source),
+            OkxError::Encode(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for OkxError {
+    fn from(e: reqwest::Error) -> Self {
+        OkxError::Transport(e)
+    }
+}
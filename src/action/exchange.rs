@@ -0,0 +1,33 @@
+use std::future::Future;
+
+use crate::action::client::OkxClient;
+use crate::action::error::OkxError;
+use crate::model::order::{CancelRequest, Order, OrderAck, OrderRequest};
+use crate::model::symbol::Symbol;
+
+/// 策略使用的交易接口
+/// OkxClient 直接调用 REST 接口，测试和模拟盘可以替换为其他实现
+pub trait Exchange: Send + Sync {
+    /// 下单，订单被拒绝时返回错误
+    fn place_order(&self, symbol: &Symbol, order: &OrderRequest) -> impl Future<Output = Result<OrderAck, OkxError>> + Send;
+
+    /// 撤单
+    fn cancel_order(&self, cancel: &CancelRequest) -> impl Future<Output = Result<OrderAck, OkxError>> + Send;
+
+    /// 查询订单，订单不存在时返回 None
+    fn get_order(&self, inst_id: &str, ord_id: &str) -> impl Future<Output = Result<Option<Order>, OkxError>> + Send;
+}
+
+impl Exchange for OkxClient {
+    fn place_order(&self, symbol: &Symbol, order: &OrderRequest) -> impl Future<Output = Result<OrderAck, OkxError>> + Send {
+        OkxClient::place_order(self, symbol, order)
+    }
+
+    fn cancel_order(&self, cancel: &CancelRequest) -> impl Future<Output = Result<OrderAck, OkxError>> + Send {
+        OkxClient::cancel_order(self, cancel)
+    }
+
+    fn get_order(&self, inst_id: &str, ord_id: &str) -> impl Future<Output = Result<Option<Order>, OkxError>> + Send {
+        OkxClient::get_order(self, inst_id, ord_id)
+    }
+}
@@ -0,0 +1,164 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use log::warn;
+use serde::Deserialize;
+
+use crate::action::client::OkxClient;
+use crate::action::error::OkxError;
+use crate::model::funding::{FundingRate, FundingRateHistory};
+
+/// /api/v5/public/funding-rate 的返回
+/// nextFundingRate 在部分合约上为空字符串
+#[derive(Deserialize)]
+pub(crate) struct InstFundingRate {
+    #[serde(rename = "instId")]
+    pub inst_id: String,
+    #[serde(rename = "fundingRate")]
+    pub funding_rate: String,
+    #[serde(rename = "nextFundingRate", default)]
+    pub next_funding_rate: String,
+    #[serde(rename = "fundingTime")]
+    pub funding_time: String,
+    #[serde(rename = "nextFundingTime", default)]
+    pub next_funding_time: String,
+    #[serde(rename = "minFundingRate", default)]
+    pub min_funding_rate: String,
+    #[serde(rename = "maxFundingRate", default)]
+    pub max_funding_rate: String,
+    #[serde(default)]
+    pub ts: String,
+}
+
+impl InstFundingRate {
+    pub(crate) fn into_funding_rate(self) -> Option<FundingRate> {
+        let (Ok(funding_rate), Ok(funding_time)) = (BigDecimal::from_str(&self.funding_rate), self.funding_time.parse()) else {
+            warn!(
+                "invalid funding rate of {}: fundingRate={}, fundingTime={}",
+                self.inst_id, self.funding_rate, self.funding_time
+            );
+            return None;
+        };
+        Some(FundingRate {
+            funding_rate,
+            next_funding_rate: BigDecimal::from_str(&self.next_funding_rate).ok(),
+            funding_time,
+            next_funding_time: self.next_funding_time.parse().unwrap_or(0),
+            min_funding_rate: BigDecimal::from_str(&self.min_funding_rate).ok(),
+            max_funding_rate: BigDecimal::from_str(&self.max_funding_rate).ok(),
+            ts: self.ts.parse().unwrap_or(0),
+            inst_id: self.inst_id,
+        })
+    }
+}
+
+/// /api/v5/public/funding-rate-history 的返回
+#[derive(Deserialize)]
+pub(crate) struct InstFundingRateHistory {
+    #[serde(rename = "instId")]
+    pub inst_id: String,
+    #[serde(rename = "fundingRate")]
+    pub funding_rate: String,
+    #[serde(rename = "realizedRate")]
+    pub realized_rate: String,
+    #[serde(rename = "fundingTime")]
+    pub funding_time: String,
+}
+
+impl InstFundingRateHistory {
+    pub(crate) fn into_history(self) -> Option<FundingRateHistory> {
+        let funding_rate = BigDecimal::from_str(&self.funding_rate).ok();
+        let realized_rate = BigDecimal::from_str(&self.realized_rate).ok();
+        let funding_time = self.funding_time.parse().ok();
+        let (Some(funding_rate), Some(realized_rate), Some(funding_time)) = (funding_rate, realized_rate, funding_time) else {
+            warn!(
+                "invalid funding rate history of {}: fundingRate={}, realizedRate={}, fundingTime={}",
+                self.inst_id, self.funding_rate, self.realized_rate, self.funding_time
+            );
+            return None;
+        };
+        Some(FundingRateHistory {
+            inst_id: self.inst_id,
+            funding_rate,
+            realized_rate,
+            funding_time,
+        })
+    }
+}
+
+impl OkxClient {
+    /// 获取永续合约当前的资金费率
+    /// /api/v5/public/funding-rate
+    pub async fn get_funding_rate(&self, inst_id: &str) -> Result<Option<FundingRate>, OkxError> {
+        let data: Vec<InstFundingRate> = self.get("/api/v5/public/funding-rate", &[("instId", inst_id)]).await?;
+        Ok(data.into_iter().next().and_then(InstFundingRate::into_funding_rate))
+    }
+
+    /// 获取永续合约的历史资金费率，按时间倒序
+    /// /api/v5/public/funding-rate-history
+    /// after 为 fundingTime，返回更早的数据；limit 最大 100，默认 100
+    pub async fn get_funding_rate_history(
+        &self,
+        inst_id: &str,
+        after: Option<u64>,
+        limit: Option<u32>,
+    ) -> Result<Vec<FundingRateHistory>, OkxError> {
+        let after = after.map(|a| a.to_string());
+        let limit = limit.map(|l| l.to_string());
+        let mut query = vec![("instId", inst_id)];
+        if let Some(after) = &after {
+            query.push(("after", after));
+        }
+        if let Some(limit) = &limit {
+            query.push(("limit", limit));
+        }
+        let data: Vec<InstFundingRateHistory> = self.get("/api/v5/public/funding-rate-history", &query).await?;
+        Ok(data.into_iter().filter_map(InstFundingRateHistory::into_history).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::client::ClientConfig;
+    use crate::action::mock::MockServer;
+
+    #[tokio::test]
+    async fn test_get_funding_rate() {
+        let body = r#"{"code":"0","msg":"","data":[{"fundingRate":"0.0000792386885340","fundingTime":"1703088000000","impactValue":"","instId":"BTC-USDT-SWAP","instType":"SWAP","interestRate":"","maxFundingRate":"0.00375","method":"current_period","minFundingRate":"-0.00375","nextFundingRate":"","nextFundingTime":"1703116800000","premium":"0.0001","settFundingRate":"0.0000908391683377","settState":"settled","ts":"1703070685309"}]}"#;
+        let server = MockServer::start(vec![("/api/v5/public/funding-rate", 200, body.to_string())]).await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let funding = client.get_funding_rate("BTC-USDT-SWAP").await.unwrap().unwrap();
+        assert_eq!(funding.funding_rate, BigDecimal::from_str("0.0000792386885340").unwrap());
+        assert_eq!(funding.next_funding_rate, None);
+        assert_eq!(funding.funding_time, 1703088000000);
+        assert_eq!(funding.interval_ms(), 8 * 3600 * 1000);
+        assert_eq!(funding.max_funding_rate, Some(BigDecimal::from_str("0.00375").unwrap()));
+        assert_eq!(server.requests()[0].target, "/api/v5/public/funding-rate?instId=BTC-USDT-SWAP");
+    }
+
+    #[tokio::test]
+    async fn test_get_funding_rate_history() {
+        let body = r#"{"code":"0","msg":"","data":[{"formulaType":"noRate","fundingRate":"0.0000746604960499","fundingTime":"1703059200000","instId":"BTC-USDT-SWAP","instType":"SWAP","method":"next_period","realizedRate":"0.0000746572360545"},{"formulaType":"noRate","fundingRate":"","fundingTime":"1703030400000","instId":"BTC-USDT-SWAP","instType":"SWAP","method":"next_period","realizedRate":""}]}"#;
+        let server = MockServer::start(vec![("/api/v5/public/funding-rate-history", 200, body.to_string())]).await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let history = client.get_funding_rate_history("BTC-USDT-SWAP", Some(1703088000000), Some(2)).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].realized_rate, BigDecimal::from_str("0.0000746572360545").unwrap());
+        assert_eq!(history[0].funding_time, 1703059200000);
+        assert_eq!(
+            server.requests()[0].target,
+            "/api/v5/public/funding-rate-history?instId=BTC-USDT-SWAP&after=1703088000000&limit=2"
+        );
+    }
+}
@@ -1,6 +1,13 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use log::warn;
 use serde::{Deserialize, Serialize};
 
-use crate::{global, model::symbol::{Category, State, Symbol}};
+use crate::action::client::OkxClient;
+use crate::action::error::OkxError;
+use crate::model::inst_id::{InstId, InstKind};
+use crate::model::symbol::{Category, ContractType, FutureAlias, OpenType, OptionType, RuleType, State, Symbol};
 
 /// /api/v5/public/instruments 接口的返回结果
 /// instType	String	产品类型
@@ -82,6 +89,7 @@ use crate::{global, model::symbol::{Category, State, Symbol}};
 /// instIdCode	Integer	产品唯一标识代码。
 /// 对于简单二进制编码，您必须使用 instIdCode 而不是 instId。
 /// 对于同一instId，实盘和模拟盘的值可能会不一样。
+#[allow(clippy::tabs_in_doc_comments)]
 #[derive(Debug, Serialize, Deserialize)]
 struct InstSymbol {
     #[serde(rename = "instId")]
@@ -118,89 +126,228 @@ struct InstSymbol {
     pub max_lmt_sz: Option<String>,
     #[serde(rename = "maxMktSz")]
     pub max_mkt_sz: Option<String>,
+    #[serde(rename = "ctType")]
+    pub ct_type: Option<String>,
+    #[serde(rename = "ruleType")]
+    pub rule_type: Option<String>,
+    #[serde(rename = "openType")]
+    pub open_type: Option<String>,
+    #[serde(rename = "optType")]
+    pub opt_type: Option<String>,
+    pub stk: Option<String>,
+    pub alias: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct ApiResponse {
-    pub code: String,
-    pub msg: String,
-    data: Vec<InstSymbol>,
+impl InstSymbol {
+    /// 转换为 Symbol，base/quote 由调用方根据类别确定
+    /// 不适用的字段 OKX 返回空字符串，这里统一转换为 None
+    fn into_symbol(self, category: &Category, base: String, quote: String) -> Option<Symbol> {
+        let parse_decimal = |s: &str| -> Option<BigDecimal> { BigDecimal::from_str(s).ok() };
+        let non_empty = |s: Option<String>| -> Option<String> { s.filter(|s| !s.is_empty()) };
+
+        let tick_sz = parse_decimal(&self.tick_sz);
+        let lot_sz = parse_decimal(&self.lot_sz);
+        let min_sz = parse_decimal(&self.min_sz);
+        let (Some(tick_sz), Some(lot_sz), Some(min_sz)) = (tick_sz, lot_sz, min_sz) else {
+            warn!("invalid trading rules for {}: tickSz={}, lotSz={}, minSz={}", self.inst_id, self.tick_sz, self.lot_sz, self.min_sz);
+            return None;
+        };
+
+        Some(Symbol {
+            inst_id: self.inst_id,
+            base,
+            quote,
+            category: category.clone(),
+            list_time: self.list_time.parse().unwrap_or(0),
+            exp_time: self.exp_time.unwrap_or("0".to_string()).parse().unwrap_or(0),
+            state: State::from_str(&self.state).unwrap_or(State::Suspend),
+            inst_family: self.inst_family,
+            settle_ccy: non_empty(self.settle_ccy),
+            tick_sz,
+            lot_sz,
+            min_sz,
+            ct_val: self.ct_val.as_deref().and_then(parse_decimal),
+            ct_mult: self.ct_mult.as_deref().and_then(parse_decimal),
+            ct_val_ccy: non_empty(self.ct_val_ccy),
+            ct_type: self.ct_type.as_deref().and_then(ContractType::from_str),
+            lever: self.lever.as_deref().and_then(parse_decimal),
+            max_lmt_sz: self.max_lmt_sz.as_deref().and_then(parse_decimal),
+            max_mkt_sz: self.max_mkt_sz.as_deref().and_then(parse_decimal),
+            rule_type: self.rule_type.as_deref().and_then(RuleType::from_str).unwrap_or(RuleType::Normal),
+            open_type: self.open_type.as_deref().and_then(OpenType::from_str),
+            opt_type: self.opt_type.as_deref().and_then(OptionType::from_str),
+            stk: self.stk.as_deref().and_then(parse_decimal),
+            alias: self.alias.as_deref().and_then(FutureAlias::from_str),
+        })
+    }
 }
 
-/// 获取交易对列表
-/// /api/v5/public/instruments
-pub async fn get_symbols(category: Category) -> Option<Vec<Symbol>> {
-    let url = format!("{}/api/v5/public/instruments?instType={}", 
-        global::REST_API_HOST,
-        category.as_str()
-    );
-
-    // 创建 HTTP 客户端
-    let client = reqwest::Client::new();
-    
-    // 发送 GET 请求
-    match client.get(&url).send().await {
-        Ok(response) => {
-            match response.json::<ApiResponse>().await {
-                Ok(api_response) => {
-                    if api_response.code == "0" {
-                        if category == Category::Spot {
-                            Some(api_response.data.into_iter().map(|inst| {
-                                Symbol {
-                                    inst_id: inst.inst_id,
-                                    base: inst.base_ccy.unwrap_or_default().to_uppercase(),
-                                    quote: inst.quote_ccy.unwrap_or_default().to_uppercase(),
-                                    category: category.clone(),
-                                    list_time: inst.list_time.parse().unwrap_or(0),
-                                    exp_time: inst.exp_time.unwrap_or("0".to_string()).parse().unwrap_or(0),
-                                    state: State::from_str(&inst.state).unwrap_or(State::Suspend),
-                                }
-                            }).collect())
-                        } else if Category::Swap == category || Category::Future == category {
-                            Some(api_response.data.into_iter().
-                            filter(|inst| {
-                                let inst_family = inst.inst_family.clone();
-                                let inst_family: Vec<&str> = inst_family.split('-').collect();
-
-                                if inst_family.len() < 2{
-                                    eprintln!("invalid inst_family: {}", inst.inst_family);
-                                    return false;
-                                }
-                                true
-                            }).
-                            map(|inst| {
-                                let inst_family = inst.inst_family.clone();
-                                let inst_family: Vec<&str> = inst_family.split('-').collect();
-
-                                Symbol {
-                                    inst_id: inst.inst_id,
-                                    base: inst_family[0].to_uppercase().to_string(),
-                                    quote: inst_family[1].to_uppercase().to_string(),
-                                    category: category.clone(),
-                                    list_time: inst.list_time.parse().unwrap_or(0),
-                                    exp_time: inst.exp_time.unwrap_or("0".to_string()).parse().unwrap_or(0),
-                                    state: State::from_str(&inst.state).unwrap_or(State::Suspend),
-                                }
-                            }).collect())
-                        } else {
-                            // 其他类别暂不支持
-                            None
-
-                        }
-                    } else {
-                        eprintln!("API Error: code={}, msg={}", api_response.code, api_response.msg);
-                        None
-                    }
-                }
-                Err(e) => {
-                    eprintln!("Failed to parse JSON response: {}", e);
-                    None
-                }
+impl OkxClient {
+    /// 获取交易对列表
+    /// /api/v5/public/instruments
+    /// 期权需要指定交易品种，这里会先获取所有期权交易品种再逐个查询
+    pub async fn get_symbols(&self, category: Category) -> Result<Vec<Symbol>, OkxError> {
+        if category == Category::Option {
+            let mut symbols = Vec::new();
+            for inst_family in self.get_option_families().await? {
+                symbols.extend(self.get_option_symbols(&inst_family).await?);
             }
+            return Ok(symbols);
         }
-        Err(e) => {
-            eprintln!("HTTP request failed: {}", e);
-            None
+        self.get_instruments(category, None).await
+    }
+
+    /// 获取某个交易品种下的期权，如 BTC-USD
+    pub async fn get_option_symbols(&self, inst_family: &str) -> Result<Vec<Symbol>, OkxError> {
+        self.get_instruments(Category::Option, Some(inst_family)).await
+    }
+
+    /// 获取所有期权交易品种，如 ["BTC-USD", "ETH-USD"]
+    /// /api/v5/public/underlying
+    pub async fn get_option_families(&self) -> Result<Vec<String>, OkxError> {
+        // data 为二维数组 [["BTC-USD","ETH-USD"]]
+        let data: Vec<Vec<String>> = self
+            .get("/api/v5/public/underlying", &[("instType", Category::Option.as_str())])
+            .await?;
+        Ok(data.into_iter().flatten().collect())
+    }
+
+    async fn get_instruments(&self, category: Category, inst_family: Option<&str>) -> Result<Vec<Symbol>, OkxError> {
+        let mut query = vec![("instType", category.as_str())];
+        if let Some(inst_family) = inst_family {
+            query.push(("instFamily", inst_family));
+        }
+        let data: Vec<InstSymbol> = self.get("/api/v5/public/instruments", &query).await?;
+
+        match category {
+            // 币币和杠杆使用 baseCcy/quoteCcy
+            Category::Spot | Category::Margin => Ok(data.into_iter().filter_map(|inst| {
+                let base = inst.base_ccy.clone().unwrap_or_default().to_uppercase();
+                let quote = inst.quote_ccy.clone().unwrap_or_default().to_uppercase();
+                inst.into_symbol(&category, base, quote)
+            }).collect()),
+            // 衍生品使用 instFamily，如 BTC-USD
+            Category::Swap | Category::Future | Category::Option => Ok(data.into_iter().
+            filter_map(|inst| {
+                let inst_family = match InstId::from_str(&inst.inst_family) {
+                    Ok(inst_family) if inst_family.kind == InstKind::Spot => inst_family,
+                    _ => {
+                        warn!("invalid inst_family: {}", inst.inst_family);
+                        return None;
+                    }
+                };
+                let base = inst_family.base.to_uppercase();
+                let quote = inst_family.quote.to_uppercase();
+                inst.into_symbol(&category, base, quote)
+            }).collect()),
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::client::ClientConfig;
+    use crate::action::mock::MockServer;
+
+    // /api/v5/public/instruments?instType=SWAP 的返回示例
+    const SWAP_INSTRUMENTS: &str = r#"{"code":"0","msg":"","data":[{"alias":"","baseCcy":"","category":"1","ctMult":"1","ctType":"linear","ctVal":"0.01","ctValCcy":"BTC","expTime":"","instFamily":"BTC-USDT","instId":"BTC-USDT-SWAP","instType":"SWAP","lever":"100","listTime":"1573557408000","lotSz":"0.01","maxIcebergSz":"100000000.0000000000000000","maxLmtAmt":"20000000","maxLmtSz":"100000000","maxMktAmt":"","maxMktSz":"12000","maxStopSz":"12000","maxTriggerSz":"100000000.0000000000000000","maxTwapSz":"100000000.0000000000000000","minSz":"0.01","optType":"","quoteCcy":"","ruleType":"normal","settleCcy":"USDT","state":"live","stk":"","tickSz":"0.1","uly":"BTC-USDT","openType":""},{"alias":"","baseCcy":"","category":"1","ctMult":"1","ctType":"inverse","ctVal":"100","ctValCcy":"USD","expTime":"","instFamily":"BTC-USD","instId":"BTC-USD-SWAP","instType":"SWAP","lever":"100","listTime":"1573557408000","lotSz":"1","maxLmtSz":"100000","maxMktSz":"5000","minSz":"1","optType":"","quoteCcy":"","ruleType":"normal","settleCcy":"BTC","state":"live","stk":"","tickSz":"0.1","uly":"BTC-USD","openType":""},{"alias":"","baseCcy":"","category":"1","ctMult":"1","ctType":"linear","ctVal":"10","ctValCcy":"NEW","expTime":"","instFamily":"NEW-USDT","instId":"NEW-USDT-SWAP","instType":"SWAP","lever":"5","listTime":"1733557408000","lotSz":"1","maxLmtSz":"100000","maxMktSz":"5000","minSz":"1","optType":"","quoteCcy":"","ruleType":"pre_market","settleCcy":"USDT","state":"live","stk":"","tickSz":"0.0001","uly":"NEW-USDT","openType":""}]}"#;
+
+    #[tokio::test]
+    async fn test_get_swap_symbols_keep_trading_rules() {
+        let server = MockServer::start(vec![("/api/v5/public/instruments", 200, SWAP_INSTRUMENTS.to_string())]).await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let symbols = client.get_symbols(Category::Swap).await.unwrap();
+        assert_eq!(symbols.len(), 3);
+
+        let btc = &symbols[0];
+        assert_eq!(btc.base, "BTC");
+        assert_eq!(btc.quote, "USDT");
+        assert_eq!(btc.inst_family, "BTC-USDT");
+        assert_eq!(btc.settle_ccy.as_deref(), Some("USDT"));
+        assert_eq!(btc.tick_sz, BigDecimal::from_str("0.1").unwrap());
+        assert_eq!(btc.lot_sz, BigDecimal::from_str("0.01").unwrap());
+        assert_eq!(btc.ct_val, Some(BigDecimal::from_str("0.01").unwrap()));
+        assert_eq!(btc.ct_type, Some(ContractType::Linear));
+        assert_eq!(btc.lever, Some(BigDecimal::from(100)));
+        assert_eq!(btc.max_mkt_sz, Some(BigDecimal::from(12000)));
+        assert_eq!(btc.open_type, None);
+        assert!(!btc.is_pre_market());
+
+        assert_eq!(symbols[1].ct_type, Some(ContractType::Inverse));
+        assert_eq!(symbols[1].ct_val_ccy.as_deref(), Some("USD"));
+        assert!(symbols[2].is_pre_market());
+    }
+
+    #[tokio::test]
+    async fn test_get_option_symbols_for_all_families() {
+        let option = r#"{"code":"0","msg":"","data":[{"alias":"","baseCcy":"","category":"","ctMult":"0.1","ctType":"","ctVal":"1","ctValCcy":"BTC","expTime":"1743148800000","instFamily":"BTC-USD","instId":"BTC-USD-250328-60000-C","instType":"OPTION","lever":"","listTime":"1727424000000","lotSz":"1","maxLmtSz":"10000","maxMktSz":"5000","minSz":"1","optType":"C","quoteCcy":"","ruleType":"normal","settleCcy":"BTC","state":"live","stk":"60000","tickSz":"0.0005","uly":"BTC-USD"}]}"#;
+        let server = MockServer::start(vec![
+            ("/api/v5/public/underlying", 200, r#"{"code":"0","msg":"","data":[["BTC-USD","ETH-USD"]]}"#.to_string()),
+            ("/api/v5/public/instruments", 200, option.to_string()),
+        ])
+        .await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let symbols = client.get_symbols(Category::Option).await.unwrap();
+        // 每个交易品种查询一次
+        assert_eq!(symbols.len(), 2);
+        let requests = server.requests();
+        assert_eq!(requests[0].target, "/api/v5/public/underlying?instType=OPTION");
+        assert_eq!(requests[1].target, "/api/v5/public/instruments?instType=OPTION&instFamily=BTC-USD");
+        assert_eq!(requests[2].target, "/api/v5/public/instruments?instType=OPTION&instFamily=ETH-USD");
+
+        let option = &symbols[0];
+        assert_eq!(option.category, Category::Option);
+        assert_eq!(option.base, "BTC");
+        assert_eq!(option.quote, "USD");
+        assert_eq!(option.opt_type, Some(OptionType::Call));
+        assert_eq!(option.stk, Some(BigDecimal::from(60000)));
+        assert_eq!(option.expiry().unwrap().to_rfc3339(), "2025-03-28T08:00:00+00:00");
+        assert_eq!(option.lever, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_margin_symbols() {
+        let margin = r#"{"code":"0","msg":"","data":[{"alias":"","baseCcy":"BTC","category":"1","ctMult":"","ctType":"","ctVal":"","ctValCcy":"","expTime":"","instFamily":"","instId":"BTC-USDT","instType":"MARGIN","lever":"10","listTime":"1606468572000","lotSz":"0.00000001","maxLmtSz":"9999999999","maxMktSz":"1000000","minSz":"0.00001","optType":"","quoteCcy":"USDT","ruleType":"normal","settleCcy":"","state":"live","stk":"","tickSz":"0.1","uly":"","openType":"call_auction"}]}"#;
+        let server = MockServer::start(vec![("/api/v5/public/instruments", 200, margin.to_string())]).await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let symbols = client.get_symbols(Category::Margin).await.unwrap();
+        assert_eq!(symbols[0].base, "BTC");
+        assert_eq!(symbols[0].quote, "USDT");
+        assert_eq!(symbols[0].lever, Some(BigDecimal::from(10)));
+        assert_eq!(symbols[0].open_type, Some(OpenType::CallAuction));
+        assert_eq!(symbols[0].ct_val, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_future_symbols_with_alias() {
+        let future = r#"{"code":"0","msg":"","data":[{"alias":"quarter","baseCcy":"","category":"1","ctMult":"1","ctType":"inverse","ctVal":"100","ctValCcy":"USD","expTime":"1743148800000","instFamily":"BTC-USD","instId":"BTC-USD-250328","instType":"FUTURES","lever":"100","listTime":"1727424000000","lotSz":"1","maxLmtSz":"1000000","maxMktSz":"3000","minSz":"1","optType":"","quoteCcy":"","ruleType":"normal","settleCcy":"BTC","state":"live","stk":"","tickSz":"0.1","uly":"BTC-USD","openType":""}]}"#;
+        let server = MockServer::start(vec![("/api/v5/public/instruments", 200, future.to_string())]).await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let symbols = client.get_symbols(Category::Future).await.unwrap();
+        assert_eq!(symbols[0].base, "BTC");
+        assert_eq!(symbols[0].quote, "USD");
+        assert_eq!(symbols[0].alias, Some(FutureAlias::Quarter));
+        assert_eq!(symbols[0].exp_time, 1743148800000);
+    }
+}
@@ -1,3 +1,19 @@
+pub mod account;
+pub mod algo;
+pub mod auth;
+pub mod book;
+pub mod client;
+pub mod error;
+pub mod exchange;
+pub mod funding;
 pub mod instrument;
+pub mod rate_limit;
+pub mod retry;
+pub mod sim;
 pub mod telegram;
-pub mod ticker;
\ No newline at end of file
+pub mod ticker;
+pub mod trade;
+pub mod ws;
+
+#[cfg(test)]
+pub(crate) mod mock;
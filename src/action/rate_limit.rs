@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::action::error::OkxError;
+
+/// 单个接口的限速规则：interval_ms 毫秒内最多 requests 次
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Limit {
+    pub requests: u32,
+    pub interval_ms: u64,
+}
+
+impl Limit {
+    pub const fn new(requests: u32, interval_ms: u64) -> Self {
+        Limit { requests, interval_ms }
+    }
+
+    fn refill_per_sec(&self) -> f64 {
+        self.requests as f64 * 1000.0 / self.interval_ms as f64
+    }
+}
+
+/// 超出限速时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LimitMode {
+    /// 等待令牌补充后再发送，等待时间超过 max_wait 时拒绝
+    Delay,
+    /// 直接拒绝
+    Reject,
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// 按接口 path 配置的限速，如 /api/v5/market/tickers
+    pub limits: HashMap<String, Limit>,
+    /// 未单独配置的接口使用的限速，为空时不限速
+    pub default_limit: Option<Limit>,
+    pub mode: LimitMode,
+    /// Delay 模式下单次请求最长等待时间
+    pub max_wait: Duration,
+}
+
+impl Default for RateLimitConfig {
+    /// OKX 文档中的限速规则
+    /// 行情、公共数据按 IP 限速，账户、交易按 User ID 限速
+    fn default() -> Self {
+        let limits = [
+            ("/api/v5/market/tickers", Limit::new(20, 2000)),
+            ("/api/v5/market/ticker", Limit::new(20, 2000)),
+            ("/api/v5/market/books", Limit::new(40, 2000)),
+            ("/api/v5/market/index-tickers", Limit::new(20, 2000)),
+            ("/api/v5/public/instruments", Limit::new(20, 2000)),
+            ("/api/v5/public/underlying", Limit::new(20, 2000)),
+            ("/api/v5/public/funding-rate", Limit::new(20, 2000)),
+            ("/api/v5/public/funding-rate-history", Limit::new(10, 2000)),
+            ("/api/v5/trade/order", Limit::new(60, 2000)),
+            ("/api/v5/trade/batch-orders", Limit::new(300, 2000)),
+            ("/api/v5/trade/cancel-order", Limit::new(60, 2000)),
+            ("/api/v5/trade/cancel-batch-orders", Limit::new(300, 2000)),
+            ("/api/v5/trade/amend-order", Limit::new(60, 2000)),
+            ("/api/v5/trade/order-algo", Limit::new(20, 2000)),
+            ("/api/v5/trade/cancel-algos", Limit::new(20, 2000)),
+            ("/api/v5/trade/orders-algo-pending", Limit::new(20, 2000)),
+            ("/api/v5/trade/orders-algo-history", Limit::new(20, 2000)),
+            ("/api/v5/account/balance", Limit::new(10, 2000)),
+            ("/api/v5/account/positions", Limit::new(10, 2000)),
+            ("/api/v5/account/config", Limit::new(5, 2000)),
+            ("/api/v5/account/set-leverage", Limit::new(20, 2000)),
+            ("/api/v5/account/set-position-mode", Limit::new(5, 2000)),
+            ("/api/v5/account/max-size", Limit::new(20, 2000)),
+            ("/api/v5/account/max-avail-size", Limit::new(20, 2000)),
+        ];
+        RateLimitConfig {
+            limits: limits.into_iter().map(|(path, limit)| (path.to_string(), limit)).collect(),
+            default_limit: Some(Limit::new(10, 2000)),
+            mode: LimitMode::Delay,
+            max_wait: Duration::from_secs(5),
+        }
+    }
+}
+
+/// 每个接口的限速统计
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct EndpointMetrics {
+    /// 无需等待直接放行的请求数
+    pub allowed: u64,
+    /// 等待后放行的请求数
+    pub delayed: u64,
+    /// 被拒绝的请求数
+    pub rejected: u64,
+    /// 累计等待时间
+    pub total_wait: Duration,
+}
+
+struct Bucket {
+    /// 可以为负数，表示已经有请求在排队等待
+    tokens: f64,
+    capacity: f64,
+    refill_per_sec: f64,
+    last: Instant,
+}
+
+impl Bucket {
+    fn new(limit: &Limit) -> Self {
+        Bucket {
+            tokens: limit.requests as f64,
+            capacity: limit.requests as f64,
+            refill_per_sec: limit.refill_per_sec(),
+            last: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last = now;
+    }
+}
+
+/// 令牌桶限速器
+/// 按 接口 + 范围 分桶，范围对公共接口为空，对私有接口为 API Key（即 User ID）
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<HashMap<String, Bucket>>,
+    metrics: Mutex<HashMap<String, EndpointMetrics>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        RateLimiter {
+            config,
+            buckets: Mutex::new(HashMap::new()),
+            metrics: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn limit(&self, endpoint: &str) -> Option<Limit> {
+        self.config.limits.get(endpoint).copied().or(self.config.default_limit)
+    }
+
+    /// 发送请求前获取令牌
+    /// Delay 模式下会等待令牌补充，Reject 模式或等待时间过长时返回 OkxError::RateLimitExceeded
+    pub async fn acquire(&self, endpoint: &str, scope: Option<&str>) -> Result<(), OkxError> {
+        let Some(limit) = self.limit(endpoint) else {
+            return Ok(());
+        };
+        let key = match scope {
+            Some(scope) => format!("{}#{}", endpoint, scope),
+            None => endpoint.to_string(),
+        };
+
+        let wait = {
+            let mut buckets = self.buckets.lock().unwrap();
+            let bucket = buckets.entry(key).or_insert_with(|| Bucket::new(&limit));
+            bucket.refill(Instant::now());
+            bucket.tokens -= 1.0;
+            if bucket.tokens >= 0.0 {
+                Duration::ZERO
+            } else {
+                let wait = Duration::from_secs_f64(-bucket.tokens / bucket.refill_per_sec);
+                if self.config.mode == LimitMode::Reject || wait > self.config.max_wait {
+                    bucket.tokens += 1.0;
+                    self.record(endpoint, |m| m.rejected += 1);
+                    return Err(OkxError::RateLimitExceeded {
+                        endpoint: endpoint.to_string(),
+                        wait,
+                    });
+                }
+                wait
+            }
+        };
+
+        if wait.is_zero() {
+            self.record(endpoint, |m| m.allowed += 1);
+        } else {
+            self.record(endpoint, |m| {
+                m.delayed += 1;
+                m.total_wait += wait;
+            });
+            tokio::time::sleep(wait).await;
+        }
+        Ok(())
+    }
+
+    fn record(&self, endpoint: &str, f: impl FnOnce(&mut EndpointMetrics)) {
+        let mut metrics = self.metrics.lock().unwrap();
+        f(metrics.entry(endpoint.to_string()).or_default());
+    }
+
+    /// 各接口的限速统计，按接口排序
+    pub fn metrics(&self) -> Vec<(String, EndpointMetrics)> {
+        let metrics = self.metrics.lock().unwrap();
+        let mut metrics: Vec<(String, EndpointMetrics)> = metrics.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+        metrics.sort_by(|a, b| a.0.cmp(&b.0));
+        metrics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(mode: LimitMode) -> RateLimitConfig {
+        RateLimitConfig {
+            limits: HashMap::from([("/api/v5/market/tickers".to_string(), Limit::new(2, 2000))]),
+            default_limit: None,
+            mode,
+            max_wait: Duration::from_secs(5),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_delay_until_refill() {
+        let limiter = RateLimiter::new(config(LimitMode::Delay));
+        let start = Instant::now();
+        for _ in 0..3 {
+            limiter.acquire("/api/v5/market/tickers", None).await.unwrap();
+        }
+        // 第三个请求需要等待一个令牌补充，即 1 秒
+        assert_eq!(start.elapsed().as_millis(), 1000);
+
+        let metrics = limiter.metrics();
+        assert_eq!(metrics[0].0, "/api/v5/market/tickers");
+        assert_eq!(metrics[0].1.allowed, 2);
+        assert_eq!(metrics[0].1.delayed, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reject_mode() {
+        let limiter = RateLimiter::new(config(LimitMode::Reject));
+        limiter.acquire("/api/v5/market/tickers", None).await.unwrap();
+        limiter.acquire("/api/v5/market/tickers", None).await.unwrap();
+        let err = limiter.acquire("/api/v5/market/tickers", None).await.unwrap_err();
+        assert!(err.is_rate_limited());
+
+        // 不同用户分别计数
+        limiter.acquire("/api/v5/market/tickers", Some("other-user")).await.unwrap();
+        // 未配置的接口不限速
+        for _ in 0..10 {
+            limiter.acquire("/api/v5/public/time", None).await.unwrap();
+        }
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        limiter.acquire("/api/v5/market/tickers", None).await.unwrap();
+        assert_eq!(limiter.metrics()[0].1.rejected, 1);
+    }
+}
@@ -0,0 +1,211 @@
+use std::future::Future;
+use std::time::Duration;
+
+use log::warn;
+use rand::Rng;
+use tokio::time::Instant;
+
+use crate::action::error::OkxError;
+use crate::util::log::log_okx_error;
+
+/// 请求失败后的重试策略
+/// 等待时间为 base_delay * 2^(n-1)，不超过 max_delay，再乘以 [0.5, 1.0) 之间的随机数避免多个任务同时重试
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// 最多尝试次数，包含第一次请求，为 1 时不重试
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// 从第一次请求开始计算的总时长，超过后不再重试
+    pub deadline: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            deadline: Some(Duration::from_secs(20)),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 不重试
+    pub fn none() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// 第 attempt 次失败后的等待时间，attempt 从 1 开始
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self.base_delay.saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)));
+        let delay = exp.min(self.max_delay);
+        delay.mul_f64(rand::rng().random_range(0.5..1.0))
+    }
+
+    /// 按策略执行 f，只有可重试的错误才会重试
+    pub async fn run<T, F, Fut>(&self, what: &str, mut f: F) -> Result<T, OkxError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, OkxError>>,
+    {
+        let start = Instant::now();
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let e = match f().await {
+                Ok(v) => return Ok(v),
+                Err(e) => e,
+            };
+            if !e.is_retryable() || attempt >= self.max_attempts {
+                return Err(e);
+            }
+
+            let mut delay = self.backoff(attempt);
+            // 本地限速器给出了需要等待的时间
+            if let OkxError::RateLimitExceeded { wait, .. } = &e {
+                delay = delay.max(*wait);
+            }
+            if let Some(deadline) = self.deadline
+                && start.elapsed() + delay > deadline
+            {
+                return Err(e);
+            }
+            warn!("{} failed (attempt {}/{}), retry in {}ms: {}", what, attempt, self.max_attempts, delay.as_millis(), e);
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+/// 启动阶段使用：一直等待直到拿到非空的结果
+/// 无论什么错误都不会放弃，等待时间按指数退避增长，最长 1 分钟
+pub async fn wait_for_data<T, F, Fut>(what: &str, mut f: F) -> Vec<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<Vec<T>, OkxError>>,
+{
+    let policy = RetryPolicy {
+        max_attempts: u32::MAX,
+        base_delay: Duration::from_secs(1),
+        max_delay: Duration::from_secs(60),
+        deadline: None,
+    };
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        match f().await {
+            Ok(data) if !data.is_empty() => return data,
+            Ok(_) => warn!("{} returned no data", what),
+            Err(e) => log_okx_error(what, &e),
+        }
+        let delay = policy.backoff(attempt);
+        warn!("{} not ready, retry in {}ms", what, delay.as_millis());
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::error::ApiCode;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(300),
+            deadline: None,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_data() {
+        let calls = AtomicU32::new(0);
+        let data = wait_for_data("test", || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(OkxError::Decode {
+                    source: serde_json::from_str::<u8>("x").unwrap_err(),
+                    body: "x".to_string(),
+                }),
+                1 => Ok(vec![]),
+                _ => Ok(vec![1]),
+            }
+        })
+        .await;
+        assert_eq!(data, vec![1]);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_backoff_bounds() {
+        let policy = policy();
+        for _ in 0..100 {
+            let first = policy.backoff(1);
+            assert!(first >= Duration::from_millis(50) && first < Duration::from_millis(100));
+            // 不超过 max_delay
+            let tenth = policy.backoff(10);
+            assert!(tenth >= Duration::from_millis(150) && tenth < Duration::from_millis(300));
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_until_success() {
+        let calls = AtomicU32::new(0);
+        let result = policy()
+            .run("test", || async {
+                if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                    Err(OkxError::Api { code: ApiCode::SystemBusy, msg: String::new() })
+                } else {
+                    Ok(1)
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_no_retry_on_permanent_error() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), OkxError> = policy()
+            .run("test", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(OkxError::Api { code: ApiCode::InvalidParameter, msg: String::new() })
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stop_at_max_attempts_and_deadline() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), OkxError> = policy()
+            .run("test", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(OkxError::Status { status: 503, body: String::new() })
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        let calls = AtomicU32::new(0);
+        let policy = RetryPolicy {
+            deadline: Some(Duration::from_millis(120)),
+            ..policy()
+        };
+        let _: Result<(), OkxError> = policy
+            .run("test", || async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Err(OkxError::Status { status: 503, body: String::new() })
+            })
+            .await;
+        // 第二次重试的等待时间至少 100ms，会超过 120ms 的总时长
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,683 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bigdecimal::{BigDecimal, One, Signed, Zero};
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::action::error::{ApiCode, OkxError};
+use crate::action::exchange::Exchange;
+use crate::model::account::Position;
+use crate::model::order::{CancelRequest, Order, OrderAck, OrderRequest, OrderState, OrderType, PosSide, Side};
+use crate::model::order_book::{Level, OrderBook};
+use crate::model::symbol::{Category, ContractType, Symbol, Ticker};
+
+/// 模拟交易所的配置，对应 conf/okx.yml 中的 paper
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SimConfig {
+    /// 挂单成交的手续费率
+    pub maker_fee: BigDecimal,
+    /// 吃单成交的手续费率
+    pub taker_fee: BigDecimal,
+    /// 下单和撤单的延迟，期间行情可能变化
+    pub latency_ms: u64,
+    /// 吃单成交价相对盘口的滑点比例，买入加价、卖出减价
+    pub slippage: BigDecimal,
+    /// 初始余额，按币种
+    pub balances: HashMap<String, BigDecimal>,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        SimConfig {
+            // 普通用户币币费率 0.08% / 0.1%
+            maker_fee: BigDecimal::new(8.into(), 4),
+            taker_fee: BigDecimal::new(1.into(), 3),
+            latency_ms: 0,
+            slippage: BigDecimal::zero(),
+            balances: HashMap::from([("USDT".to_string(), BigDecimal::from(10000))]),
+        }
+    }
+}
+
+/// 模拟盘，实现和 OkxClient 相同的交易接口，不需要 API Key
+/// 行情由调用方通过 update_ticker/update_book 输入，可以来自实时推送或录制的数据
+/// 吃单按深度逐档成交，没有深度时按 ticker 的买一/卖一成交；成交不会消耗深度
+/// 未成交的限价单在之后的行情中价格穿过时按挂单价成交
+/// 币币按币种记账，正向合约按净持仓记账，平仓盈亏和手续费计入保证金币种；不支持反向合约，不计算保证金
+#[derive(Clone)]
+pub struct SimExchange {
+    config: Arc<SimConfig>,
+    state: Arc<Mutex<SimState>>,
+}
+
+struct SimState {
+    tickers: HashMap<String, Ticker>,
+    books: HashMap<String, OrderBook>,
+    balances: HashMap<String, BigDecimal>,
+    /// 按 instId 的净持仓
+    positions: HashMap<String, Position>,
+    orders: HashMap<String, SimOrder>,
+    next_id: u64,
+}
+
+struct SimOrder {
+    order: Order,
+    symbol: Symbol,
+}
+
+/// 一次成交，px 为成交均价
+struct Fill {
+    sz: BigDecimal,
+    px: BigDecimal,
+}
+
+impl Fill {
+    fn none() -> Self {
+        Fill {
+            sz: BigDecimal::zero(),
+            px: BigDecimal::zero(),
+        }
+    }
+}
+
+fn insufficient(ccy: &str) -> OkxError {
+    OkxError::Api {
+        code: ApiCode::Other("51008".to_string()),
+        msg: format!("Order failed. Insufficient {} balance in account.", ccy),
+    }
+}
+
+fn invalid(order: &OrderRequest, reason: &str) -> OkxError {
+    OkxError::InvalidOrder {
+        inst_id: order.inst_id.clone(),
+        reason: reason.to_string(),
+    }
+}
+
+fn now_ms() -> u64 {
+    Utc::now().timestamp_millis() as u64
+}
+
+impl SimExchange {
+    pub fn new(config: SimConfig) -> Self {
+        let balances = config.balances.clone();
+        SimExchange {
+            config: Arc::new(config),
+            state: Arc::new(Mutex::new(SimState {
+                tickers: HashMap::new(),
+                books: HashMap::new(),
+                balances,
+                positions: HashMap::new(),
+                orders: HashMap::new(),
+                next_id: 1,
+            })),
+        }
+    }
+
+    /// 更新行情，并撮合该产品上价格已经穿过的挂单
+    pub fn update_ticker(&self, ticker: Ticker) {
+        let mut state = self.state.lock().unwrap();
+        let inst_id = ticker.inst_id.clone();
+        state.tickers.insert(inst_id.clone(), ticker);
+        self.match_resting(&mut state, &inst_id);
+    }
+
+    pub fn update_tickers(&self, tickers: impl IntoIterator<Item = Ticker>) {
+        for ticker in tickers {
+            self.update_ticker(ticker);
+        }
+    }
+
+    /// 更新深度，之后该产品的吃单按深度成交
+    pub fn update_book(&self, book: OrderBook) {
+        let mut state = self.state.lock().unwrap();
+        let inst_id = book.inst_id.clone();
+        state.books.insert(inst_id.clone(), book);
+        self.match_resting(&mut state, &inst_id);
+    }
+
+    pub fn balance(&self, ccy: &str) -> BigDecimal {
+        self.state.lock().unwrap().balances.get(ccy).cloned().unwrap_or_default()
+    }
+
+    pub fn balances(&self) -> HashMap<String, BigDecimal> {
+        self.state.lock().unwrap().balances.clone()
+    }
+
+    /// 未平仓的持仓
+    pub fn positions(&self) -> Vec<Position> {
+        self.state.lock().unwrap().positions.values().cloned().collect()
+    }
+
+    /// 未完成的订单
+    pub fn open_orders(&self) -> Vec<Order> {
+        let state = self.state.lock().unwrap();
+        state.orders.values().filter(|o| !o.order.state.is_final()).map(|o| o.order.clone()).collect()
+    }
+
+    fn place(&self, symbol: &Symbol, request: &OrderRequest) -> Result<OrderAck, OkxError> {
+        request.validate(symbol).map_err(|reason| invalid(request, &reason))?;
+        if matches!(symbol.category, Category::Swap | Category::Future) && linear_ct_val(symbol).is_none() {
+            return Err(invalid(request, "only linear contracts are supported by the simulator"));
+        }
+
+        let mut state = self.state.lock().unwrap();
+        let Some((best, mut levels)) = state.quote(&request.inst_id, request.side) else {
+            return Err(invalid(request, "no market data"));
+        };
+        let ts = now_ms();
+        let mut order = Order {
+            ord_id: state.next_id.to_string(),
+            cl_ord_id: request.cl_ord_id.clone(),
+            inst_id: request.inst_id.clone(),
+            category: symbol.category.clone(),
+            side: request.side,
+            pos_side: request.pos_side,
+            td_mode: request.td_mode,
+            ord_type: request.ord_type,
+            px: request.px.clone(),
+            sz: request.sz.clone(),
+            acc_fill_sz: BigDecimal::zero(),
+            avg_px: None,
+            state: OrderState::Live,
+            fill_px: None,
+            fill_sz: None,
+            trade_id: None,
+            fee: None,
+            fee_ccy: None,
+            c_time: ts,
+            u_time: ts,
+        };
+
+        // 币币市价买单默认以计价货币下单，按最优价换算成币的数量
+        let quote_sz = request.is_quote_sized(&symbol.category);
+        let mut sz = if quote_sz { &request.sz / &best } else { request.sz.clone() };
+        if request.reduce_only == Some(true) {
+            sz = state.reducible(&request.inst_id, request.side, &sz).ok_or_else(|| invalid(request, "no position to reduce"))?;
+        }
+
+        let limit = match request.ord_type {
+            OrderType::Market | OrderType::OptimalLimitIoc => None,
+            _ => request.px.as_ref(),
+        };
+        let fill = if levels.is_empty() {
+            // 只有 ticker 时按最优价全部成交
+            match limit {
+                Some(px) if !crosses(request.side, &best, px) => Fill::none(),
+                _ => Fill { sz: sz.clone(), px: best },
+            }
+        } else {
+            if let Some(px) = limit {
+                levels.retain(|l| crosses(request.side, &l.px, px));
+            }
+            take(&levels, &sz)
+        };
+        let crossed = !fill.sz.is_zero();
+        match request.ord_type {
+            OrderType::PostOnly if crossed => {
+                order.state = OrderState::Canceled;
+            }
+            OrderType::Fok if fill.sz < sz => {
+                order.state = OrderState::Canceled;
+            }
+            _ => {
+                if crossed {
+                    let px = self.slipped(request.side, &fill.px);
+                    state.settle(symbol, &mut order, &fill.sz, &px, &self.config.taker_fee)?;
+                }
+                let rest = &sz - &order.acc_fill_sz;
+                order.state = match request.ord_type {
+                    _ if rest.is_zero() => OrderState::Filled,
+                    OrderType::Limit | OrderType::PostOnly => {
+                        if order.acc_fill_sz.is_zero() {
+                            OrderState::Live
+                        } else {
+                            OrderState::PartiallyFilled
+                        }
+                    }
+                    _ => OrderState::Canceled,
+                };
+            }
+        }
+        // 以计价货币下单时 sz 也记为币的数量，和成交数量的单位一致
+        order.sz = sz;
+
+        let ord_id = order.ord_id.clone();
+        state.next_id += 1;
+        state.orders.insert(
+            ord_id.clone(),
+            SimOrder {
+                order,
+                symbol: symbol.clone(),
+            },
+        );
+        Ok(OrderAck {
+            ord_id,
+            cl_ord_id: request.cl_ord_id.clone(),
+            req_id: None,
+            s_code: "0".to_string(),
+            s_msg: String::new(),
+            ts,
+        })
+    }
+
+    fn slipped(&self, side: Side, px: &BigDecimal) -> BigDecimal {
+        match side {
+            Side::Buy => px * (BigDecimal::one() + &self.config.slippage),
+            Side::Sell => px * (BigDecimal::one() - &self.config.slippage),
+        }
+    }
+
+    /// 挂单在价格穿过时按挂单价全部成交，余额不足时撤单
+    fn match_resting(&self, state: &mut SimState, inst_id: &str) {
+        let resting: Vec<String> = state
+            .orders
+            .values()
+            .filter(|o| o.order.inst_id == inst_id && !o.order.state.is_final())
+            .map(|o| o.order.ord_id.clone())
+            .collect();
+        for ord_id in resting {
+            let Some(SimOrder { mut order, symbol }) = state.orders.remove(&ord_id) else {
+                continue;
+            };
+            let Some(px) = order.px.clone() else {
+                continue;
+            };
+            let touched = state.quote(inst_id, order.side).is_some_and(|(best, _)| crosses(order.side, &best, &px));
+            if touched {
+                let rest = &order.sz - &order.acc_fill_sz;
+                order.state = match state.settle(&symbol, &mut order, &rest, &px, &self.config.maker_fee) {
+                    Ok(()) => OrderState::Filled,
+                    Err(_) => OrderState::Canceled,
+                };
+                order.u_time = now_ms();
+            }
+            state.orders.insert(ord_id, SimOrder { order, symbol });
+        }
+    }
+}
+
+impl SimState {
+    /// 对手方向的最优价和可成交的档位，买入吃卖盘，卖出吃买盘
+    /// 没有深度时档位为空，按 ticker 的买一/卖一成交，数量不限；没有盘口时用最新成交价
+    fn quote(&self, inst_id: &str, side: Side) -> Option<(BigDecimal, Vec<Level>)> {
+        if let Some(book) = self.books.get(inst_id) {
+            let levels: Vec<Level> = match side {
+                Side::Buy => book.asks().cloned().collect(),
+                Side::Sell => book.bids().cloned().collect(),
+            };
+            if let Some(best) = levels.first() {
+                return Some((best.px.clone(), levels));
+            }
+        }
+        let ticker = self.tickers.get(inst_id)?;
+        let px = match side {
+            Side::Buy => ticker.ask_px.clone(),
+            Side::Sell => ticker.bid_px.clone(),
+        };
+        // 从未成交的产品 last 为 0，不能作为成交价
+        let px = px.or_else(|| Some(ticker.last.clone()).filter(|last| !last.is_zero()))?;
+        Some((px, Vec::new()))
+    }
+
+    /// 只减仓订单可以成交的数量，不能超过反向的持仓
+    fn reducible(&self, inst_id: &str, side: Side, sz: &BigDecimal) -> Option<BigDecimal> {
+        let pos = &self.positions.get(inst_id)?.pos;
+        let opposite = match side {
+            Side::Buy => pos.is_negative(),
+            Side::Sell => pos.is_positive(),
+        };
+        opposite.then(|| sz.clone().min(pos.abs()))
+    }
+
+    /// 成交后更新余额、持仓和订单，余额不足时不成交
+    fn settle(&mut self, symbol: &Symbol, order: &mut Order, sz: &BigDecimal, px: &BigDecimal, fee_rate: &BigDecimal) -> Result<(), OkxError> {
+        let (fee, fee_ccy) = match linear_ct_val(symbol) {
+            Some(ct_val) if symbol.category != Category::Spot && symbol.category != Category::Margin => {
+                let settle_ccy = symbol.settle_ccy.clone().unwrap_or_else(|| symbol.quote.clone());
+                let fee = sz * &ct_val * px * fee_rate;
+                let pnl = self.apply_position(symbol, order, sz, px, &ct_val, &settle_ccy);
+                *self.balances.entry(settle_ccy.clone()).or_default() += pnl - &fee;
+                (fee, settle_ccy)
+            }
+            _ => self.exchange_spot(symbol, order, sz, px, fee_rate)?,
+        };
+
+        let filled = &order.acc_fill_sz + sz;
+        let notional = order.avg_px.clone().unwrap_or_default() * &order.acc_fill_sz + px * sz;
+        order.avg_px = Some(notional / &filled);
+        order.acc_fill_sz = filled;
+        order.fill_px = Some(px.clone());
+        order.fill_sz = Some(sz.clone());
+        order.trade_id = Some(self.next_id.to_string());
+        order.fee = Some(order.fee.clone().unwrap_or_default() - fee);
+        order.fee_ccy = Some(fee_ccy);
+        self.next_id += 1;
+        Ok(())
+    }
+
+    /// 币币成交，非保证金模式下不能透支
+    fn exchange_spot(&mut self, symbol: &Symbol, order: &Order, sz: &BigDecimal, px: &BigDecimal, fee_rate: &BigDecimal) -> Result<(BigDecimal, String), OkxError> {
+        let cash = order.td_mode == crate::model::order::TdMode::Cash;
+        let (base, quote) = (&symbol.base, &symbol.quote);
+        let value = sz * px;
+        match order.side {
+            Side::Buy => {
+                if cash && self.balances.get(quote).cloned().unwrap_or_default() < value {
+                    return Err(insufficient(quote));
+                }
+                let fee = sz * fee_rate;
+                *self.balances.entry(quote.clone()).or_default() -= value;
+                *self.balances.entry(base.clone()).or_default() += sz - &fee;
+                Ok((fee, base.clone()))
+            }
+            Side::Sell => {
+                if cash && self.balances.get(base).cloned().unwrap_or_default() < *sz {
+                    return Err(insufficient(base));
+                }
+                let fee = &value * fee_rate;
+                *self.balances.entry(base.clone()).or_default() -= sz;
+                *self.balances.entry(quote.clone()).or_default() += value - &fee;
+                Ok((fee, quote.clone()))
+            }
+        }
+    }
+
+    /// 更新净持仓，返回平仓部分的盈亏
+    fn apply_position(&mut self, symbol: &Symbol, order: &Order, sz: &BigDecimal, px: &BigDecimal, ct_val: &BigDecimal, ccy: &str) -> BigDecimal {
+        let delta = match order.side {
+            Side::Buy => sz.clone(),
+            Side::Sell => -sz.clone(),
+        };
+        let position = self.positions.entry(symbol.inst_id.clone()).or_insert_with(|| Position {
+            pos_id: symbol.inst_id.clone(),
+            inst_id: symbol.inst_id.clone(),
+            category: symbol.category.clone(),
+            mgn_mode: order.td_mode,
+            pos_side: PosSide::Net,
+            pos: BigDecimal::zero(),
+            ccy: ccy.to_string(),
+            avg_px: None,
+            upl: None,
+            lever: None,
+            liq_px: None,
+            mark_px: None,
+            margin: None,
+            mgn_ratio: None,
+            u_time: 0,
+        });
+        let old = position.pos.clone();
+        let avg = position.avg_px.clone().unwrap_or_else(|| px.clone());
+        let mut pnl = BigDecimal::zero();
+        if old.is_zero() || old.is_positive() == delta.is_positive() {
+            // 加仓，重新计算均价
+            let total = old.abs() + sz;
+            position.avg_px = Some((avg * old.abs() + px * sz) / &total);
+        } else {
+            // 减仓或反手，平掉的部分按开仓均价计算盈亏
+            let closed = old.abs().min(sz.clone());
+            let per_ct = if old.is_positive() { px - &avg } else { &avg - px };
+            pnl = per_ct * &closed * ct_val;
+            if *sz > old.abs() {
+                position.avg_px = Some(px.clone());
+            }
+        }
+        position.pos = old + delta;
+        position.u_time = now_ms();
+        if position.pos.is_zero() {
+            self.positions.remove(&symbol.inst_id);
+        }
+        pnl
+    }
+}
+
+/// 正向合约每张对应的币数量
+fn linear_ct_val(symbol: &Symbol) -> Option<BigDecimal> {
+    if symbol.ct_type == Some(ContractType::Inverse) {
+        return None;
+    }
+    symbol.contract_value()
+}
+
+/// 对手价是否满足限价
+fn crosses(side: Side, px: &BigDecimal, limit: &BigDecimal) -> bool {
+    match side {
+        Side::Buy => px <= limit,
+        Side::Sell => px >= limit,
+    }
+}
+
+/// 按档位逐档成交，深度不足时只成交一部分
+fn take(levels: &[Level], sz: &BigDecimal) -> Fill {
+    let mut remaining = sz.clone();
+    let mut notional = BigDecimal::zero();
+    for level in levels {
+        let qty = level.sz.clone().min(remaining.clone());
+        notional += &qty * &level.px;
+        remaining -= qty;
+        if remaining.is_zero() {
+            break;
+        }
+    }
+    let filled = sz - remaining;
+    if filled.is_zero() {
+        return Fill::none();
+    }
+    Fill {
+        px: notional / &filled,
+        sz: filled,
+    }
+}
+
+impl Exchange for SimExchange {
+    async fn place_order(&self, symbol: &Symbol, order: &OrderRequest) -> Result<OrderAck, OkxError> {
+        tokio::time::sleep(Duration::from_millis(self.config.latency_ms)).await;
+        self.place(symbol, order)
+    }
+
+    async fn cancel_order(&self, cancel: &CancelRequest) -> Result<OrderAck, OkxError> {
+        tokio::time::sleep(Duration::from_millis(self.config.latency_ms)).await;
+        let mut state = self.state.lock().unwrap();
+        let order = cancel
+            .ord_id
+            .as_ref()
+            .and_then(|id| state.orders.get_mut(id))
+            .filter(|o| o.order.inst_id == cancel.inst_id)
+            .map(|o| &mut o.order);
+        let Some(order) = order else {
+            return Err(OkxError::Api {
+                code: ApiCode::Other("51400".to_string()),
+                msg: "Order cancellation failed as the order has been filled, canceled or does not exist.".to_string(),
+            });
+        };
+        if order.state.is_final() {
+            return Err(OkxError::Api {
+                code: ApiCode::Other("51400".to_string()),
+                msg: "Order cancellation failed as the order has been filled, canceled or does not exist.".to_string(),
+            });
+        }
+        order.state = OrderState::Canceled;
+        order.u_time = now_ms();
+        Ok(OrderAck {
+            ord_id: order.ord_id.clone(),
+            cl_ord_id: order.cl_ord_id.clone(),
+            req_id: None,
+            s_code: "0".to_string(),
+            s_msg: String::new(),
+            ts: order.u_time,
+        })
+    }
+
+    async fn get_order(&self, inst_id: &str, ord_id: &str) -> Result<Option<Order>, OkxError> {
+        let state = self.state.lock().unwrap();
+        Ok(state.orders.get(ord_id).map(|o| o.order.clone()).filter(|o| o.inst_id == inst_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::{self, dec, ticker};
+    use crate::logic::hedge::{HedgeConfig, HedgeEvent, HedgeExecutor};
+    use crate::model::basis::Basis;
+    use crate::model::order::{TdMode, TgtCcy};
+    use crate::model::order_book::BookUpdate;
+
+    fn spot() -> Symbol {
+        fixture::spot("BTC-USDT", "0.0001")
+    }
+
+    fn swap() -> Symbol {
+        fixture::swap("BTC-USDT-SWAP", "1", "0.01")
+    }
+
+    fn sim(config: SimConfig) -> SimExchange {
+        let sim = SimExchange::new(config);
+        sim.update_ticker(ticker("BTC-USDT", "99", "100"));
+        sim.update_ticker(ticker("BTC-USDT-SWAP", "101", "102"));
+        sim
+    }
+
+    fn market(inst_id: &str, td_mode: TdMode, side: Side, sz: &str) -> OrderRequest {
+        OrderRequest {
+            tgt_ccy: (td_mode == TdMode::Cash).then_some(TgtCcy::BaseCcy),
+            ..OrderRequest::market(inst_id, td_mode, side, dec(sz))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spot_market_order_with_fee_and_slippage() {
+        let sim = sim(SimConfig {
+            slippage: dec("0.01"),
+            ..Default::default()
+        });
+        let ack = sim.place_order(&spot(), &market("BTC-USDT", TdMode::Cash, Side::Buy, "2")).await.unwrap();
+        let order = sim.get_order("BTC-USDT", &ack.ord_id).await.unwrap().unwrap();
+        assert_eq!(order.state, OrderState::Filled);
+        assert_eq!(order.acc_fill_sz, dec("2"));
+        assert_eq!(order.avg_px, Some(dec("101")));
+        assert_eq!(order.fee, Some(dec("-0.002")));
+        assert_eq!(order.fee_ccy.as_deref(), Some("BTC"));
+        assert_eq!(sim.balance("USDT"), dec("9798"));
+        assert_eq!(sim.balance("BTC"), dec("1.998"));
+
+        // 默认以计价货币下市价买单
+        let by_quote = OrderRequest::market("BTC-USDT", TdMode::Cash, Side::Buy, dec("100"));
+        let ack = sim.place_order(&spot(), &by_quote).await.unwrap();
+        let order = sim.get_order("BTC-USDT", &ack.ord_id).await.unwrap().unwrap();
+        assert_eq!(order.acc_fill_sz, dec("1"));
+
+        let too_much = market("BTC-USDT", TdMode::Cash, Side::Sell, "5");
+        match sim.place_order(&spot(), &too_much).await {
+            Err(OkxError::Api { code, .. }) => assert_eq!(code, ApiCode::Other("51008".to_string())),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_swap_position_and_realized_pnl() {
+        let sim = sim(SimConfig {
+            taker_fee: BigDecimal::zero(),
+            ..Default::default()
+        });
+        // 以 101 开空 100 张，即 1 BTC
+        sim.place_order(&swap(), &market("BTC-USDT-SWAP", TdMode::Cross, Side::Sell, "100")).await.unwrap();
+        let positions = sim.positions();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].pos, dec("-100"));
+        assert_eq!(positions[0].avg_px, Some(dec("101")));
+
+        // 价格下跌后以 91 平仓，盈利 10 USDT
+        sim.update_ticker(ticker("BTC-USDT-SWAP", "90", "91"));
+        let close = OrderRequest {
+            reduce_only: Some(true),
+            ..market("BTC-USDT-SWAP", TdMode::Cross, Side::Buy, "150")
+        };
+        let ack = sim.place_order(&swap(), &close).await.unwrap();
+        let order = sim.get_order("BTC-USDT-SWAP", &ack.ord_id).await.unwrap().unwrap();
+        assert_eq!(order.acc_fill_sz, dec("100"));
+        assert!(sim.positions().is_empty());
+        assert_eq!(sim.balance("USDT"), dec("10010"));
+
+        let reduce = OrderRequest {
+            reduce_only: Some(true),
+            ..market("BTC-USDT-SWAP", TdMode::Cross, Side::Buy, "1")
+        };
+        assert!(matches!(sim.place_order(&swap(), &reduce).await, Err(OkxError::InvalidOrder { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_fill_against_book_and_resting_limit() {
+        let sim = sim(SimConfig::default());
+        let mut book = OrderBook::new("BTC-USDT");
+        book.apply_snapshot(BookUpdate {
+            asks: vec![Level::parse("100", "1", 1).unwrap(), Level::parse("101", "1", 1).unwrap(), Level::parse("103", "5", 1).unwrap()],
+            bids: vec![Level::parse("99", "1", 1).unwrap()],
+            ..Default::default()
+        })
+        .unwrap();
+        sim.update_book(book);
+
+        // 限价 101 只能吃到前两档，剩余部分挂单
+        let limit = OrderRequest::limit("BTC-USDT", TdMode::Cash, Side::Buy, dec("3"), dec("101"));
+        let ack = sim.place_order(&spot(), &limit).await.unwrap();
+        let order = sim.get_order("BTC-USDT", &ack.ord_id).await.unwrap().unwrap();
+        assert_eq!(order.state, OrderState::PartiallyFilled);
+        assert_eq!(order.acc_fill_sz, dec("2"));
+        assert_eq!(order.avg_px, Some(dec("100.5")));
+        assert_eq!(sim.open_orders().len(), 1);
+
+        // 卖一价跌到 101 以下时按挂单价成交
+        let mut book = OrderBook::new("BTC-USDT");
+        book.apply_snapshot(BookUpdate {
+            asks: vec![Level::parse("100.5", "10", 1).unwrap()],
+            ..Default::default()
+        })
+        .unwrap();
+        sim.update_book(book);
+        let order = sim.get_order("BTC-USDT", &ack.ord_id).await.unwrap().unwrap();
+        assert_eq!(order.state, OrderState::Filled);
+        assert_eq!(order.acc_fill_sz, dec("3"));
+        assert!(sim.open_orders().is_empty());
+
+        // 挂单可以撤销，已结束的订单不能撤销
+        let resting = OrderRequest::limit("BTC-USDT", TdMode::Cash, Side::Buy, dec("1"), dec("90"));
+        let ack = sim.place_order(&spot(), &resting).await.unwrap();
+        sim.cancel_order(&CancelRequest::new("BTC-USDT", &ack.ord_id)).await.unwrap();
+        assert!(sim.cancel_order(&CancelRequest::new("BTC-USDT", &ack.ord_id)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_hedge_executor_runs_on_simulator() {
+        let sim = sim(SimConfig::default());
+        let mut executor = HedgeExecutor::new(
+            sim.clone(),
+            HedgeConfig {
+                notional: dec("1000"),
+                poll_interval_ms: 1,
+                ..Default::default()
+            },
+        );
+        executor.set_symbols([spot(), swap()]);
+
+        let event = executor.on_basis("BTC-USDT", "BTC-USDT-SWAP", &Basis::new(dec("99"), dec("100"), dec("102"), dec("103")).unwrap()).await;
+        let Some(HedgeEvent::Opened(pair)) = event else {
+            panic!("unexpected event: {:?}", event);
+        };
+        // 10 BTC 扣除手续费后为 9.99 BTC，对冲 999 张
+        assert_eq!(pair.swap_sz, dec("999"));
+        assert_eq!(sim.positions()[0].pos, dec("-999"));
+        assert_eq!(sim.balance("BTC"), pair.spot_sz);
+
+        sim.update_ticker(ticker("BTC-USDT", "100", "100.1"));
+        sim.update_ticker(ticker("BTC-USDT-SWAP", "100.1", "100.1"));
+        let basis = Basis::new(dec("100"), dec("100.1"), dec("100.1"), dec("100.1")).unwrap();
+        let event = executor.on_basis("BTC-USDT", "BTC-USDT-SWAP", &basis).await;
+        assert!(matches!(event, Some(HedgeEvent::Closed { .. })), "unexpected event: {:?}", event);
+        assert!(sim.positions().is_empty());
+        assert!(sim.balance("BTC") < dec("0.0001"));
+    }
+}
@@ -79,10 +79,10 @@ struct ChatInfo {
     pub all_members_are_administrators: bool,
 }
 
-pub async fn broadcast(text: &String) -> bool {
+pub async fn broadcast(text: &str) -> bool {
     let msg = Message {
         chat_id: -1003342269888,
-        text: text.clone(),
+        text: text.to_string(),
     };
     let msg = json!(msg);
     let client = reqwest::Client::new();
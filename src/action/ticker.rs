@@ -1,19 +1,16 @@
-use crate::global;
+use crate::action::client::OkxClient;
+use crate::action::error::OkxError;
+use crate::model::inst_id::InstId;
 use crate::model::symbol::Category;
-use crate::model::symbol::Ticker;
+use crate::model::symbol::{IndexTicker, Ticker};
 use serde::{Deserialize, Serialize};
 use bigdecimal::BigDecimal;
+use log::warn;
+use std::fmt;
 use std::str::FromStr;
 
 #[derive(Serialize, Deserialize)]
-struct PriceResponse {
-    pub code: String,
-    pub msg: String,
-    pub data: Vec<InstTicker>,
-}
-
-#[derive(Serialize, Deserialize)]
-struct InstTicker {
+pub(crate) struct InstTicker {
     #[serde(rename = "instId")]
     pub inst_id: String,
     #[serde(rename = "instType")]
@@ -45,67 +42,247 @@ struct InstTicker {
     pub sod_utc8: String,
     pub ts: String,
 }
+/// 单个产品的行情解析失败
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TickerParseError {
+    pub inst_id: String,
+    /// 解析失败的字段，使用 OKX 的字段名
+    pub field: &'static str,
+    pub value: String,
+}
+
+impl fmt::Display for TickerParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to parse {} of {}: {:?}", self.field, self.inst_id, self.value)
+    }
+}
+
+/// 一次行情查询的结果，包含解析成功的行情和解析失败的产品
+#[derive(Debug, Clone)]
+pub struct TickerReport {
+    pub tickers: Vec<Ticker>,
+    pub failures: Vec<TickerParseError>,
+}
+
 impl InstTicker {
-    fn to_ticker(&self) -> Option<Ticker> {
-        let parse_decimal = |s: &str| -> Option<BigDecimal> {
-            BigDecimal::from_str(s).ok()
+    /// 盘口字段在没有挂单时为空字符串，解析为 None
+    /// 从未成交的产品成交价、开盘价和成交量等字段也为空字符串，解析为 0；非空时必须能解析
+    pub(crate) fn to_ticker(&self) -> Result<Ticker, TickerParseError> {
+        let err = |field: &'static str, value: &str| TickerParseError {
+            inst_id: self.inst_id.clone(),
+            field,
+            value: value.to_string(),
         };
-        
-        let parse_u64 = |s: &str| -> Option<u64> {
-            s.parse::<u64>().ok()
+        let parse_decimal = |field: &'static str, s: &str| -> Result<BigDecimal, TickerParseError> {
+            BigDecimal::from_str(s).map_err(|_| err(field, s))
         };
-
-        // Extract base and quote from inst_id (e.g., "BTC-USD-SWAP" -> base: "BTC", quote: "USD")
-        let parts: Vec<&str> = self.inst_id.split('-').collect();
-        let (base, quote) = if parts.len() >= 2 {
-            (parts[0].to_string(), parts[1].to_string())
-        } else {
-            return None;
+        let parse_optional = |field: &'static str, s: &str| -> Result<Option<BigDecimal>, TickerParseError> {
+            if s.is_empty() {
+                return Ok(None);
+            }
+            parse_decimal(field, s).map(Some)
+        };
+        let parse_or_zero = |field: &'static str, s: &str| -> Result<BigDecimal, TickerParseError> {
+            parse_optional(field, s).map(Option::unwrap_or_default)
         };
 
-        Some(Ticker {
+        // 从 inst_id 中解析 base 和 quote，如 BTC-USD-SWAP -> BTC, USD
+        let inst_id = InstId::from_str(&self.inst_id).map_err(|_| err("instId", &self.inst_id))?;
+        let (base, quote) = (inst_id.base, inst_id.quote);
+
+        Ok(Ticker {
             inst_id: self.inst_id.clone(),
             base,
             quote,
-            last: parse_decimal(&self.last)?,
-            last_sz: parse_decimal(&self.last_sz)?,
-            bid_px: parse_decimal(&self.bid_px)?,
-            bid_sz: parse_decimal(&self.bid_sz)?,
-            ask_px: parse_decimal(&self.ask_px)?,
-            ask_sz: parse_decimal(&self.ask_sz)?,
-            open_24h: parse_decimal(&self.open_24h)?,
-            high_24h: parse_decimal(&self.high_24h)?,
-            low_24h: parse_decimal(&self.low_24h)?,
-            vol_24h: parse_decimal(&self.vol_24h)?,
-            vol_ccy_24h: parse_decimal(&self.vol_ccy_24h)?,
-            ts: parse_u64(&self.ts)?,
+            last: parse_or_zero("last", &self.last)?,
+            last_sz: parse_or_zero("lastSz", &self.last_sz)?,
+            bid_px: parse_optional("bidPx", &self.bid_px)?,
+            bid_sz: parse_optional("bidSz", &self.bid_sz)?,
+            ask_px: parse_optional("askPx", &self.ask_px)?,
+            ask_sz: parse_optional("askSz", &self.ask_sz)?,
+            open_24h: parse_or_zero("open24h", &self.open_24h)?,
+            high_24h: parse_or_zero("high24h", &self.high_24h)?,
+            low_24h: parse_or_zero("low24h", &self.low_24h)?,
+            vol_24h: parse_or_zero("vol24h", &self.vol_24h)?,
+            vol_ccy_24h: parse_or_zero("volCcy24h", &self.vol_ccy_24h)?,
+            sod_utc0: parse_or_zero("sodUtc0", &self.sod_utc0)?,
+            sod_utc8: parse_or_zero("sodUtc8", &self.sod_utc8)?,
+            ts: self.ts.parse::<u64>().map_err(|_| err("ts", &self.ts))?,
+        })
+    }
+}
+
+/// /api/v5/market/index-tickers 的返回
+#[derive(Deserialize)]
+pub(crate) struct InstIndexTicker {
+    #[serde(rename = "instId")]
+    pub inst_id: String,
+    #[serde(rename = "idxPx")]
+    pub idx_px: String,
+    #[serde(rename = "open24h")]
+    pub open_24h: String,
+    #[serde(rename = "high24h")]
+    pub high_24h: String,
+    #[serde(rename = "low24h")]
+    pub low_24h: String,
+    pub ts: String,
+}
+
+impl InstIndexTicker {
+    pub(crate) fn into_index_ticker(self) -> Option<IndexTicker> {
+        let parse = |s: &str| BigDecimal::from_str(s).ok();
+        let (Some(idx_px), Some(open_24h), Some(high_24h), Some(low_24h), Ok(ts)) = (
+            parse(&self.idx_px),
+            parse(&self.open_24h),
+            parse(&self.high_24h),
+            parse(&self.low_24h),
+            self.ts.parse(),
+        ) else {
+            warn!("invalid index ticker of {}: idxPx={}, ts={}", self.inst_id, self.idx_px, self.ts);
+            return None;
+        };
+        Some(IndexTicker {
+            inst_id: self.inst_id,
+            idx_px,
+            open_24h,
+            high_24h,
+            low_24h,
+            ts,
         })
     }
 }
 
-pub async fn get_ticker(category: Category) -> Option<Vec<Ticker>> {
-    let url = format!("{}/api/v5/market/tickers?instType={}", 
-        global::REST_API_HOST,
-        category.as_str()
-    );
-    
-    let client = reqwest::Client::new();
-    let response = client.get(&url).send().await.ok()?;
-    let response_text = response.text().await.ok()?;
-    
-    let price_response: PriceResponse = serde_json::from_str(&response_text).ok()?;
-    
-    // Check if the response is successful (code "0")
-    if price_response.code != "0" {
-        return None;
+impl OkxClient {
+    /// 获取以 quote_ccy 计价的全部指数行情，如 USD 返回 BTC-USD、USDT-USD、USDC-USD 等
+    /// /api/v5/market/index-tickers
+    pub async fn get_index_tickers(&self, quote_ccy: &str) -> Result<Vec<IndexTicker>, OkxError> {
+        let data: Vec<InstIndexTicker> = self
+            .get("/api/v5/market/index-tickers", &[("quoteCcy", quote_ccy)])
+            .await?;
+        Ok(data.into_iter().filter_map(InstIndexTicker::into_index_ticker).collect())
+    }
+
+    /// 获取某一类别下所有产品的行情
+    /// /api/v5/market/tickers
+    /// 解析失败的产品会记录日志，需要具体失败原因时使用 get_ticker_report
+    pub async fn get_ticker(&self, category: Category) -> Result<Vec<Ticker>, OkxError> {
+        let report = self.get_ticker_report(category).await?;
+        for failure in report.failures.iter() {
+            warn!("{}", failure);
+        }
+        Ok(report.tickers)
     }
-    
-    let mut tickers = Vec::new();
-    for inst_ticker in price_response.data {
-        if let Some(ticker) = inst_ticker.to_ticker() {
-            tickers.push(ticker);
+
+    /// 获取某一类别下所有产品的行情，同时返回解析失败的产品
+    pub async fn get_ticker_report(&self, category: Category) -> Result<TickerReport, OkxError> {
+        let data: Vec<InstTicker> = self
+            .get("/api/v5/market/tickers", &[("instType", category.as_str())])
+            .await?;
+
+        let mut tickers = Vec::new();
+        let mut failures = Vec::new();
+        for inst_ticker in data {
+            match inst_ticker.to_ticker() {
+                Ok(ticker) => tickers.push(ticker),
+                Err(e) => failures.push(e),
+            }
         }
+
+        Ok(TickerReport { tickers, failures })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::client::ClientConfig;
+    use crate::action::mock::MockServer;
+
+    #[tokio::test]
+    async fn test_get_ticker() {
+        let body = r#"{"code":"0","msg":"","data":[{"instType":"SWAP","instId":"BTC-USDT-SWAP","last":"9999.99","lastSz":"1","askPx":"9999.99","askSz":"11","bidPx":"8888.88","bidSz":"5","open24h":"9000","high24h":"10000","low24h":"8888.88","volCcy24h":"2222","vol24h":"2222","sodUtc0":"0.1","sodUtc8":"0.1","ts":"1597026383085"}]}"#;
+        let server = MockServer::start(vec![("/api/v5/market/tickers", 200, body.to_string())]).await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let tickers = client.get_ticker(Category::Swap).await.unwrap();
+        assert_eq!(tickers.len(), 1);
+        assert_eq!(tickers[0].base, "BTC");
+        assert_eq!(tickers[0].quote, "USDT");
+        assert_eq!(tickers[0].ts, 1597026383085);
+        assert_eq!(server.requests()[0].target, "/api/v5/market/tickers?instType=SWAP");
     }
-    
-    Some(tickers)
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_get_ticker_report_tolerates_empty_book() {
+        // 第一个产品没有挂单，第二个 last 字段格式错误
+        let body = r#"{"code":"0","msg":"","data":[{"instType":"SPOT","instId":"NEW-USDT","last":"0.5","lastSz":"10","askPx":"","askSz":"","bidPx":"","bidSz":"","open24h":"0.4","high24h":"0.6","low24h":"0.4","volCcy24h":"100","vol24h":"200","sodUtc0":"0.45","sodUtc8":"0.42","ts":"1597026383085"},{"instType":"SPOT","instId":"BAD-USDT","last":"n/a","lastSz":"1","askPx":"1","askSz":"1","bidPx":"1","bidSz":"1","open24h":"1","high24h":"1","low24h":"1","volCcy24h":"1","vol24h":"1","sodUtc0":"1","sodUtc8":"1","ts":"1597026383085"}]}"#;
+        let server = MockServer::start(vec![("/api/v5/market/tickers", 200, body.to_string())]).await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let report = client.get_ticker_report(Category::Spot).await.unwrap();
+        assert_eq!(report.tickers.len(), 1);
+        let ticker = &report.tickers[0];
+        assert_eq!(ticker.inst_id, "NEW-USDT");
+        assert_eq!(ticker.bid_px, None);
+        assert_eq!(ticker.ask_sz, None);
+        assert_eq!(ticker.sod_utc0, BigDecimal::from_str("0.45").unwrap());
+        assert_eq!(ticker.sod_utc8, BigDecimal::from_str("0.42").unwrap());
+
+        assert_eq!(
+            report.failures,
+            vec![TickerParseError {
+                inst_id: "BAD-USDT".to_string(),
+                field: "last",
+                value: "n/a".to_string(),
+            }]
+        );
+
+        let tickers = client.get_ticker(Category::Spot).await.unwrap();
+        assert_eq!(tickers.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_ticker_never_traded() {
+        // 刚上线还没有成交的产品，成交价和 24 小时统计都为空
+        let body = r#"{"code":"0","msg":"","data":[{"instType":"SPOT","instId":"NEW-USDT","last":"","lastSz":"","askPx":"0.6","askSz":"100","bidPx":"0.4","bidSz":"50","open24h":"","high24h":"","low24h":"","volCcy24h":"0","vol24h":"0","sodUtc0":"","sodUtc8":"","ts":"1597026383085"}]}"#;
+        let server = MockServer::start(vec![("/api/v5/market/tickers", 200, body.to_string())]).await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let report = client.get_ticker_report(Category::Spot).await.unwrap();
+        assert!(report.failures.is_empty());
+        let ticker = &report.tickers[0];
+        assert_eq!(ticker.last, BigDecimal::from(0));
+        assert_eq!(ticker.open_24h, BigDecimal::from(0));
+        assert_eq!(ticker.sod_utc8, BigDecimal::from(0));
+        assert_eq!(ticker.bid_px, Some(BigDecimal::from_str("0.4").unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_get_index_tickers() {
+        let body = r#"{"code":"0","msg":"","data":[{"instId":"USDT-USD","idxPx":"0.9996","high24h":"1.0001","sodUtc0":"0.9998","open24h":"0.9997","low24h":"0.9993","sodUtc8":"0.9997","ts":"1597026383085"},{"instId":"BAD-USD","idxPx":"","high24h":"","sodUtc0":"","open24h":"","low24h":"","sodUtc8":"","ts":"1597026383085"}]}"#;
+        let server = MockServer::start(vec![("/api/v5/market/index-tickers", 200, body.to_string())]).await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let index = client.get_index_tickers("USD").await.unwrap();
+        assert_eq!(index.len(), 1);
+        assert_eq!(index[0].inst_id, "USDT-USD");
+        assert_eq!(index[0].idx_px, BigDecimal::from_str("0.9996").unwrap());
+        assert_eq!(server.requests()[0].target, "/api/v5/market/index-tickers?quoteCcy=USD");
+    }
+}
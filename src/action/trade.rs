@@ -0,0 +1,454 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use log::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::action::client::OkxClient;
+use crate::action::error::{ApiCode, OkxError};
+use crate::model::order::{AmendRequest, CancelRequest, Order, OrderAck, OrderRequest, OrderState, OrderType, PosSide, Side, TdMode};
+use crate::model::symbol::{Category, Symbol};
+
+/// 订单，orders 频道和 /api/v5/trade/order 的格式相同
+#[derive(Deserialize)]
+pub(crate) struct InstOrder {
+    #[serde(rename = "ordId")]
+    pub ord_id: String,
+    #[serde(rename = "clOrdId", default)]
+    pub cl_ord_id: String,
+    #[serde(rename = "instId")]
+    pub inst_id: String,
+    #[serde(rename = "instType")]
+    pub inst_type: String,
+    pub side: String,
+    #[serde(rename = "posSide", default)]
+    pub pos_side: String,
+    #[serde(rename = "tdMode")]
+    pub td_mode: String,
+    #[serde(rename = "ordType")]
+    pub ord_type: String,
+    #[serde(default)]
+    pub px: String,
+    pub sz: String,
+    #[serde(rename = "accFillSz", default)]
+    pub acc_fill_sz: String,
+    #[serde(rename = "avgPx", default)]
+    pub avg_px: String,
+    pub state: String,
+    #[serde(rename = "fillPx", default)]
+    pub fill_px: String,
+    #[serde(rename = "fillSz", default)]
+    pub fill_sz: String,
+    #[serde(rename = "tradeId", default)]
+    pub trade_id: String,
+    #[serde(default)]
+    pub fee: String,
+    #[serde(rename = "feeCcy", default)]
+    pub fee_ccy: String,
+    #[serde(rename = "cTime", default)]
+    pub c_time: String,
+    #[serde(rename = "uTime", default)]
+    pub u_time: String,
+}
+
+impl InstOrder {
+    pub(crate) fn into_order(self) -> Option<Order> {
+        let parse_optional = |s: &str| -> Option<BigDecimal> { BigDecimal::from_str(s).ok() };
+        let non_empty = |s: String| -> Option<String> { Some(s).filter(|s| !s.is_empty()) };
+
+        let category = Category::from_str(&self.inst_type);
+        let side = Side::from_str(&self.side);
+        let td_mode = TdMode::from_str(&self.td_mode);
+        let ord_type = OrderType::from_str(&self.ord_type);
+        let state = OrderState::from_str(&self.state);
+        let sz = parse_optional(&self.sz);
+        let (Some(category), Some(side), Some(td_mode), Some(ord_type), Some(state), Some(sz)) =
+            (category, side, td_mode, ord_type, state, sz)
+        else {
+            warn!(
+                "invalid order {} of {}: instType={}, side={}, tdMode={}, ordType={}, state={}, sz={}",
+                self.ord_id, self.inst_id, self.inst_type, self.side, self.td_mode, self.ord_type, self.state, self.sz
+            );
+            return None;
+        };
+        // 没有成交时 fillSz 为 "0"
+        let fill_sz = parse_optional(&self.fill_sz).filter(|s| *s != BigDecimal::from(0));
+        Some(Order {
+            ord_id: self.ord_id,
+            cl_ord_id: non_empty(self.cl_ord_id),
+            inst_id: self.inst_id,
+            category,
+            side,
+            pos_side: PosSide::from_str(&self.pos_side),
+            td_mode,
+            ord_type,
+            px: parse_optional(&self.px),
+            sz,
+            acc_fill_sz: parse_optional(&self.acc_fill_sz).unwrap_or_default(),
+            avg_px: parse_optional(&self.avg_px).filter(|p| *p != BigDecimal::from(0)),
+            state,
+            fill_px: fill_sz.as_ref().and_then(|_| parse_optional(&self.fill_px)),
+            fill_sz,
+            trade_id: non_empty(self.trade_id),
+            fee: parse_optional(&self.fee),
+            fee_ccy: non_empty(self.fee_ccy),
+            c_time: self.c_time.parse().unwrap_or(0),
+            u_time: self.u_time.parse().unwrap_or(0),
+        })
+    }
+}
+
+/// 下单请求的格式，数字以字符串传递
+#[derive(Serialize)]
+struct InstOrderRequest<'a> {
+    #[serde(rename = "instId")]
+    inst_id: &'a str,
+    #[serde(rename = "tdMode")]
+    td_mode: &'static str,
+    side: &'static str,
+    #[serde(rename = "posSide", skip_serializing_if = "Option::is_none")]
+    pos_side: Option<&'static str>,
+    #[serde(rename = "ordType")]
+    ord_type: &'static str,
+    sz: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    px: Option<String>,
+    #[serde(rename = "clOrdId", skip_serializing_if = "Option::is_none")]
+    cl_ord_id: Option<&'a str>,
+    #[serde(rename = "reduceOnly", skip_serializing_if = "Option::is_none")]
+    reduce_only: Option<bool>,
+    #[serde(rename = "tgtCcy", skip_serializing_if = "Option::is_none")]
+    tgt_ccy: Option<&'static str>,
+}
+
+impl<'a> From<&'a OrderRequest> for InstOrderRequest<'a> {
+    fn from(order: &'a OrderRequest) -> Self {
+        InstOrderRequest {
+            inst_id: &order.inst_id,
+            td_mode: order.td_mode.as_str(),
+            side: order.side.as_str(),
+            pos_side: order.pos_side.as_ref().map(|p| p.as_str()),
+            ord_type: order.ord_type.as_str(),
+            sz: order.sz.to_plain_string(),
+            px: order.px.as_ref().map(|p| p.to_plain_string()),
+            cl_ord_id: order.cl_ord_id.as_deref(),
+            reduce_only: order.reduce_only,
+            tgt_ccy: order.tgt_ccy.as_ref().map(|t| t.as_str()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct InstCancelRequest<'a> {
+    #[serde(rename = "instId")]
+    inst_id: &'a str,
+    #[serde(rename = "ordId", skip_serializing_if = "Option::is_none")]
+    ord_id: Option<&'a str>,
+    #[serde(rename = "clOrdId", skip_serializing_if = "Option::is_none")]
+    cl_ord_id: Option<&'a str>,
+}
+
+impl<'a> From<&'a CancelRequest> for InstCancelRequest<'a> {
+    fn from(cancel: &'a CancelRequest) -> Self {
+        InstCancelRequest {
+            inst_id: &cancel.inst_id,
+            ord_id: cancel.ord_id.as_deref(),
+            cl_ord_id: cancel.cl_ord_id.as_deref(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct InstAmendRequest<'a> {
+    #[serde(rename = "instId")]
+    inst_id: &'a str,
+    #[serde(rename = "ordId", skip_serializing_if = "Option::is_none")]
+    ord_id: Option<&'a str>,
+    #[serde(rename = "clOrdId", skip_serializing_if = "Option::is_none")]
+    cl_ord_id: Option<&'a str>,
+    #[serde(rename = "newSz", skip_serializing_if = "Option::is_none")]
+    new_sz: Option<String>,
+    #[serde(rename = "newPx", skip_serializing_if = "Option::is_none")]
+    new_px: Option<String>,
+    #[serde(rename = "cxlOnFail", skip_serializing_if = "Option::is_none")]
+    cxl_on_fail: Option<bool>,
+    #[serde(rename = "reqId", skip_serializing_if = "Option::is_none")]
+    req_id: Option<&'a str>,
+}
+
+impl<'a> From<&'a AmendRequest> for InstAmendRequest<'a> {
+    fn from(amend: &'a AmendRequest) -> Self {
+        InstAmendRequest {
+            inst_id: &amend.inst_id,
+            ord_id: amend.ord_id.as_deref(),
+            cl_ord_id: amend.cl_ord_id.as_deref(),
+            new_sz: amend.new_sz.as_ref().map(|s| s.to_plain_string()),
+            new_px: amend.new_px.as_ref().map(|p| p.to_plain_string()),
+            cxl_on_fail: amend.cxl_on_fail,
+            req_id: amend.req_id.as_deref(),
+        }
+    }
+}
+
+/// 下单、撤单、改单的返回
+#[derive(Deserialize)]
+pub(crate) struct InstOrderAck {
+    #[serde(rename = "ordId", default)]
+    pub ord_id: String,
+    #[serde(rename = "clOrdId", default)]
+    pub cl_ord_id: String,
+    #[serde(rename = "reqId", default)]
+    pub req_id: String,
+    #[serde(rename = "sCode")]
+    pub s_code: String,
+    #[serde(rename = "sMsg", default)]
+    pub s_msg: String,
+    #[serde(default)]
+    pub ts: String,
+}
+
+impl From<InstOrderAck> for OrderAck {
+    fn from(ack: InstOrderAck) -> Self {
+        let non_empty = |s: String| -> Option<String> { Some(s).filter(|s| !s.is_empty()) };
+        OrderAck {
+            ord_id: ack.ord_id,
+            cl_ord_id: non_empty(ack.cl_ord_id),
+            req_id: non_empty(ack.req_id),
+            s_code: ack.s_code,
+            s_msg: ack.s_msg,
+            ts: ack.ts.parse().unwrap_or(0),
+        }
+    }
+}
+
+/// 单个订单的操作，sCode 不为 "0" 时转换为错误
+fn single_ack(acks: Vec<InstOrderAck>) -> Result<OrderAck, OkxError> {
+    let Some(ack) = acks.into_iter().next() else {
+        return Err(OkxError::Api {
+            code: ApiCode::Other("1".to_string()),
+            msg: "empty order acknowledgement".to_string(),
+        });
+    };
+    let ack = OrderAck::from(ack);
+    if ack.is_success() {
+        Ok(ack)
+    } else {
+        Err(OkxError::Api {
+            code: ApiCode::from_code(&ack.s_code),
+            msg: ack.s_msg,
+        })
+    }
+}
+
+fn validate_order(order: &OrderRequest, symbol: &Symbol) -> Result<(), OkxError> {
+    order.validate(symbol).map_err(|reason| OkxError::InvalidOrder {
+        inst_id: order.inst_id.clone(),
+        reason,
+    })
+}
+
+impl OkxClient {
+    /// 下单
+    /// /api/v5/trade/order
+    /// 发送前按 symbol 的交易规则检查，订单被拒绝时返回 sCode/sMsg 对应的错误
+    pub async fn place_order(&self, symbol: &Symbol, order: &OrderRequest) -> Result<OrderAck, OkxError> {
+        self.check_trading_allowed()?;
+        validate_order(order, symbol)?;
+        let acks = self.post_private("/api/v5/trade/order", &InstOrderRequest::from(order)).await?;
+        single_ack(acks)
+    }
+
+    /// 批量下单，最多 20 个订单
+    /// /api/v5/trade/batch-orders
+    /// 任意一个订单不符合交易规则时全部不发送；发送后每个订单的结果见各自的 s_code
+    pub async fn place_batch_orders(&self, orders: &[(&Symbol, OrderRequest)]) -> Result<Vec<OrderAck>, OkxError> {
+        self.check_trading_allowed()?;
+        for (symbol, order) in orders {
+            validate_order(order, symbol)?;
+        }
+        let body: Vec<InstOrderRequest> = orders.iter().map(|(_, order)| InstOrderRequest::from(order)).collect();
+        let acks: Vec<InstOrderAck> = self.post_private("/api/v5/trade/batch-orders", &body).await?;
+        Ok(acks.into_iter().map(OrderAck::from).collect())
+    }
+
+    /// 撤单，实盘未打开下单开关时也可以撤单
+    /// /api/v5/trade/cancel-order
+    pub async fn cancel_order(&self, cancel: &CancelRequest) -> Result<OrderAck, OkxError> {
+        let acks = self.post_private("/api/v5/trade/cancel-order", &InstCancelRequest::from(cancel)).await?;
+        single_ack(acks)
+    }
+
+    /// 批量撤单，最多 20 个订单
+    /// /api/v5/trade/cancel-batch-orders
+    pub async fn cancel_batch_orders(&self, cancels: &[CancelRequest]) -> Result<Vec<OrderAck>, OkxError> {
+        let body: Vec<InstCancelRequest> = cancels.iter().map(InstCancelRequest::from).collect();
+        let acks: Vec<InstOrderAck> = self.post_private("/api/v5/trade/cancel-batch-orders", &body).await?;
+        Ok(acks.into_iter().map(OrderAck::from).collect())
+    }
+
+    /// 修改未完成订单的数量或价格
+    /// /api/v5/trade/amend-order
+    pub async fn amend_order(&self, symbol: &Symbol, amend: &AmendRequest) -> Result<OrderAck, OkxError> {
+        self.check_trading_allowed()?;
+        amend.validate(symbol).map_err(|reason| OkxError::InvalidOrder {
+            inst_id: amend.inst_id.clone(),
+            reason,
+        })?;
+        let acks = self.post_private("/api/v5/trade/amend-order", &InstAmendRequest::from(amend)).await?;
+        single_ack(acks)
+    }
+
+    /// 查询订单
+    /// /api/v5/trade/order
+    pub async fn get_order(&self, inst_id: &str, ord_id: &str) -> Result<Option<Order>, OkxError> {
+        let data: Vec<InstOrder> = self
+            .get_private("/api/v5/trade/order", &[("instId", inst_id), ("ordId", ord_id)])
+            .await?;
+        Ok(data.into_iter().next().and_then(InstOrder::into_order))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::{self, dec};
+    use crate::action::auth::Credentials;
+    use crate::action::client::{ClientConfig, Environment};
+    use crate::action::mock::MockServer;
+
+    fn swap() -> Symbol {
+        Symbol {
+            lever: Some(dec("100")),
+            ..fixture::swap("BTC-USDT-SWAP", "0.01", "0.01")
+        }
+    }
+
+    async fn client(routes: Vec<(&'static str, u16, String)>) -> (MockServer, OkxClient) {
+        let server = MockServer::start(routes).await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            credentials: Some(Credentials::new("key", "secret", "pass")),
+            environment: Environment::Demo,
+            ..Default::default()
+        })
+        .unwrap();
+        (server, client)
+    }
+
+    #[tokio::test]
+    async fn test_place_order() {
+        let body = r#"{"code":"0","msg":"","data":[{"clOrdId":"a1","ordId":"312269865356374016","tag":"","ts":"1695190491421","sCode":"0","sMsg":"Order placed"}],"inTime":"","outTime":""}"#;
+        let (server, client) = client(vec![("/api/v5/trade/order", 200, body.to_string())]).await;
+
+        let order = OrderRequest {
+            cl_ord_id: Some("a1".to_string()),
+            reduce_only: Some(true),
+            ..OrderRequest::limit("BTC-USDT-SWAP", TdMode::Cross, Side::Sell, dec("1.5"), dec("30000.1"))
+        };
+        let ack = client.place_order(&swap(), &order).await.unwrap();
+        assert_eq!(ack.ord_id, "312269865356374016");
+        assert_eq!(ack.cl_ord_id.as_deref(), Some("a1"));
+        assert_eq!(ack.ts, 1695190491421);
+
+        let request = &server.requests()[0];
+        let sent: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(
+            sent,
+            serde_json::json!({"instId": "BTC-USDT-SWAP", "tdMode": "cross", "side": "sell", "ordType": "limit",
+                "sz": "1.5", "px": "30000.1", "clOrdId": "a1", "reduceOnly": true})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_place_order_rejected() {
+        let body = r#"{"code":"1","msg":"All operations failed","data":[{"clOrdId":"","ordId":"","tag":"","ts":"1695190491421","sCode":"51008","sMsg":"Order failed. Insufficient USDT balance in account."}]}"#;
+        let (_server, client) = client(vec![("/api/v5/trade/order", 200, body.to_string())]).await;
+
+        let order = OrderRequest::market("BTC-USDT-SWAP", TdMode::Cross, Side::Buy, dec("1"));
+        match client.place_order(&swap(), &order).await {
+            Err(OkxError::Api { code, msg }) => {
+                assert_eq!(code, ApiCode::Other("51008".to_string()));
+                assert!(msg.contains("Insufficient"));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_invalid_order_is_not_sent() {
+        let (server, client) = client(vec![]).await;
+        let order = OrderRequest::limit("BTC-USDT-SWAP", TdMode::Cross, Side::Buy, dec("0.015"), dec("30000"));
+        let result = client.place_order(&swap(), &order).await;
+        assert!(matches!(result, Err(OkxError::InvalidOrder { .. })));
+        assert!(server.requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_batch_orders_partial_success() {
+        let body = r#"{"code":"2","msg":"","data":[{"clOrdId":"","ordId":"1","tag":"","ts":"1","sCode":"0","sMsg":""},{"clOrdId":"","ordId":"","tag":"","ts":"1","sCode":"51121","sMsg":"Order quantity must be a multiple of the lot size."}]}"#;
+        let (server, client) = client(vec![("/api/v5/trade/batch-orders", 200, body.to_string())]).await;
+
+        let symbol = swap();
+        let orders = vec![
+            (&symbol, OrderRequest::market("BTC-USDT-SWAP", TdMode::Cross, Side::Buy, dec("1"))),
+            (&symbol, OrderRequest::market("BTC-USDT-SWAP", TdMode::Cross, Side::Sell, dec("2"))),
+        ];
+        let acks = client.place_batch_orders(&orders).await.unwrap();
+        assert!(acks[0].is_success());
+        assert!(!acks[1].is_success());
+        assert_eq!(acks[1].s_code, "51121");
+
+        let sent: serde_json::Value = serde_json::from_str(&server.requests()[0].body).unwrap();
+        assert_eq!(sent.as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_and_amend() {
+        let cancel = r#"{"code":"0","msg":"","data":[{"clOrdId":"","ordId":"1","ts":"1","sCode":"0","sMsg":""}]}"#;
+        let amend = r#"{"code":"0","msg":"","data":[{"clOrdId":"","ordId":"1","reqId":"r1","ts":"1","sCode":"0","sMsg":""}]}"#;
+        let (server, client) = client(vec![
+            ("/api/v5/trade/cancel-order", 200, cancel.to_string()),
+            ("/api/v5/trade/amend-order", 200, amend.to_string()),
+        ])
+        .await;
+
+        client.cancel_order(&CancelRequest::new("BTC-USDT-SWAP", "1")).await.unwrap();
+        let ack = client
+            .amend_order(
+                &swap(),
+                &AmendRequest {
+                    inst_id: "BTC-USDT-SWAP".to_string(),
+                    ord_id: Some("1".to_string()),
+                    cl_ord_id: None,
+                    new_sz: Some(dec("2")),
+                    new_px: None,
+                    cxl_on_fail: Some(true),
+                    req_id: Some("r1".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(ack.req_id.as_deref(), Some("r1"));
+
+        let requests = server.requests();
+        assert_eq!(requests[0].body, r#"{"instId":"BTC-USDT-SWAP","ordId":"1"}"#);
+        assert_eq!(
+            requests[1].body,
+            r#"{"instId":"BTC-USDT-SWAP","ordId":"1","newSz":"2","cxlOnFail":true,"reqId":"r1"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_live_trading_disabled() {
+        let server = MockServer::start(vec![]).await;
+        let client = OkxClient::new(ClientConfig {
+            base_url: server.url(),
+            credentials: Some(Credentials::new("key", "secret", "pass")),
+            ..Default::default()
+        })
+        .unwrap();
+        let order = OrderRequest::market("BTC-USDT-SWAP", TdMode::Cross, Side::Buy, dec("1"));
+        assert!(matches!(client.place_order(&swap(), &order).await, Err(OkxError::LiveTradingDisabled)));
+        assert!(server.requests().is_empty());
+    }
+}
@@ -0,0 +1,74 @@
+//! 测试用的本地 WebSocket 服务，代替 OKX
+
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::Message;
+
+pub(crate) struct MockWsServer {
+    listener: TcpListener,
+    url: String,
+}
+
+/// 服务端的一个连接
+pub(crate) struct MockWsConn {
+    ws: WebSocketStream<TcpStream>,
+}
+
+impl MockWsServer {
+    pub async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        MockWsServer { listener, url }
+    }
+
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    /// 等待客户端连接
+    pub async fn accept(&self) -> MockWsConn {
+        let (stream, _) = tokio::time::timeout(Duration::from_secs(5), self.listener.accept())
+            .await
+            .expect("no websocket client connected")
+            .unwrap();
+        let ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+        MockWsConn { ws }
+    }
+}
+
+impl MockWsConn {
+    /// 接收下一条文本消息
+    pub async fn recv(&mut self) -> String {
+        loop {
+            let msg = tokio::time::timeout(Duration::from_secs(5), self.ws.next())
+                .await
+                .expect("no message from websocket client")
+                .expect("websocket client closed")
+                .unwrap();
+            if let Message::Text(text) = msg {
+                return text.as_str().to_string();
+            }
+        }
+    }
+
+    /// 接收下一条 JSON 消息，跳过心跳
+    pub async fn recv_json(&mut self) -> serde_json::Value {
+        loop {
+            let text = self.recv().await;
+            if text != "ping" {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    pub async fn send(&mut self, text: &str) {
+        self.ws.send(Message::text(text)).await.unwrap();
+    }
+
+    pub async fn close(mut self) {
+        let _ = self.ws.close(None).await;
+    }
+}
@@ -0,0 +1,457 @@
+//! OKX WebSocket 连接
+//! 公共和私有频道共用同一套连接管理：心跳、断线重连以及重连后的自动重新订阅
+
+pub mod private;
+pub mod public;
+
+#[cfg(test)]
+pub(crate) mod mock;
+
+use std::time::Duration;
+
+use chrono::Utc;
+use futures_util::stream::{SplitSink, SplitStream};
+use futures_util::{SinkExt, StreamExt};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::action::auth::Credentials;
+use crate::action::client::Environment;
+use crate::action::retry::RetryPolicy;
+
+/// 订阅参数，如 {"channel":"tickers","instId":"BTC-USDT"}
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Arg {
+    pub channel: String,
+    #[serde(rename = "instId", default, skip_serializing_if = "Option::is_none")]
+    pub inst_id: Option<String>,
+    #[serde(rename = "instType", default, skip_serializing_if = "Option::is_none")]
+    pub inst_type: Option<String>,
+    #[serde(rename = "instFamily", default, skip_serializing_if = "Option::is_none")]
+    pub inst_family: Option<String>,
+}
+
+impl Arg {
+    /// 按产品订阅的频道，如 tickers、books
+    pub fn inst(channel: &str, inst_id: &str) -> Self {
+        Arg {
+            channel: channel.to_string(),
+            inst_id: Some(inst_id.to_string()),
+            inst_type: None,
+            inst_family: None,
+        }
+    }
+
+    /// 按产品类型订阅的频道，如 orders、positions，inst_type 可以为 ANY
+    pub fn inst_type(channel: &str, inst_type: &str) -> Self {
+        Arg {
+            channel: channel.to_string(),
+            inst_id: None,
+            inst_type: Some(inst_type.to_string()),
+            inst_family: None,
+        }
+    }
+
+    /// 不需要其他参数的频道，如 account、balance_and_position
+    pub fn channel(channel: &str) -> Self {
+        Arg {
+            channel: channel.to_string(),
+            inst_id: None,
+            inst_type: None,
+            inst_family: None,
+        }
+    }
+}
+
+/// 服务端推送的消息
+/// 事件消息：{"event":"subscribe","arg":{...},"connId":"..."} 或 {"event":"error","code":"60012","msg":"..."}
+/// 数据消息：{"arg":{...},"action":"snapshot","data":[...]}
+#[derive(Debug, Deserialize)]
+pub(crate) struct WsMessage {
+    pub event: Option<String>,
+    pub arg: Option<Arg>,
+    pub action: Option<String>,
+    pub data: Option<Vec<Value>>,
+    pub code: Option<String>,
+    pub msg: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WsConfig {
+    pub url: String,
+    /// 超过该时间没有收到任何消息时发送 "ping"，OKX 30 秒内没有数据交互会断开连接
+    pub ping_interval: Duration,
+    /// 发送 "ping" 或登录请求后等待响应的时间，超时则重连
+    pub pong_timeout: Duration,
+    /// 重连的等待时间，只使用其中的退避参数，重连不会放弃
+    pub reconnect: RetryPolicy,
+    /// 连接保持超过该时间后断开，重连的等待时间从头计算
+    pub stable_after: Duration,
+    /// 每次订阅请求最多包含的频道数，避免请求过大
+    pub subscribe_batch: usize,
+    /// 私有频道登录使用的 API Key，公共频道为 None
+    pub credentials: Option<Credentials>,
+}
+
+impl WsConfig {
+    /// 公共频道，根据环境选择实盘或模拟盘地址
+    pub fn public(environment: Environment) -> Self {
+        WsConfig {
+            url: environment.ws_public_url().to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// 私有频道，连接后先登录再订阅
+    pub fn private(environment: Environment, credentials: Credentials) -> Self {
+        WsConfig {
+            url: environment.ws_private_url().to_string(),
+            credentials: Some(credentials),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for WsConfig {
+    fn default() -> Self {
+        WsConfig {
+            url: Environment::Live.ws_public_url().to_string(),
+            ping_interval: Duration::from_secs(20),
+            pong_timeout: Duration::from_secs(10),
+            reconnect: RetryPolicy {
+                max_attempts: u32::MAX,
+                base_delay: Duration::from_secs(1),
+                max_delay: Duration::from_secs(30),
+                deadline: None,
+            },
+            stable_after: Duration::from_secs(60),
+            subscribe_batch: 100,
+            credentials: None,
+        }
+    }
+}
+
+/// 调用方发给连接任务的命令
+pub(crate) enum Command {
+    Subscribe(Vec<Arg>),
+    Unsubscribe(Vec<Arg>),
+}
+
+/// Handler 处理一条消息后的输出
+pub(crate) struct Output<E> {
+    pub events: Vec<E>,
+    /// 需要重新订阅的频道，如深度校验失败时
+    pub resubscribe: Vec<Arg>,
+}
+
+impl<E> Default for Output<E> {
+    fn default() -> Self {
+        Output {
+            events: Vec::new(),
+            resubscribe: Vec::new(),
+        }
+    }
+}
+
+/// 将服务端推送转换为具体的事件类型
+pub(crate) trait Handler: Send + 'static {
+    type Event: Send + 'static;
+
+    fn on_connected(&mut self, out: &mut Output<Self::Event>);
+    fn on_disconnected(&mut self, out: &mut Output<Self::Event>);
+    fn on_error(&mut self, code: &str, msg: &str, out: &mut Output<Self::Event>);
+    /// 服务端确认订阅成功，之后的第一条推送通常是全量数据
+    fn on_subscribed(&mut self, _arg: &Arg) {}
+    fn on_data(&mut self, arg: Arg, action: Option<String>, data: Vec<Value>, out: &mut Output<Self::Event>);
+}
+
+/// 启动连接任务，返回命令发送端和事件接收端
+/// 命令发送端全部 drop 或事件接收端 drop 后任务退出
+pub(crate) fn spawn<H: Handler>(config: WsConfig, handler: H) -> (mpsc::UnboundedSender<Command>, mpsc::Receiver<H::Event>) {
+    let (cmd_tx, cmd_rx) = mpsc::unbounded_channel();
+    let (event_tx, event_rx) = mpsc::channel(1024);
+    tokio::spawn(async move {
+        let mut connection = Connection {
+            config,
+            handler,
+            cmd_rx,
+            event_tx,
+            subscriptions: Vec::new(),
+        };
+        connection.run().await;
+    });
+    (cmd_tx, event_rx)
+}
+
+enum SessionEnd {
+    Stop,
+    Reconnect,
+    /// 登录失败，如 API Key 错误，重连的等待时间逐次增加
+    LoginFailed,
+}
+
+struct Connection<H: Handler> {
+    config: WsConfig,
+    handler: H,
+    cmd_rx: mpsc::UnboundedReceiver<Command>,
+    event_tx: mpsc::Sender<H::Event>,
+    /// 当前的订阅，重连后重新订阅
+    subscriptions: Vec<Arg>,
+}
+
+type WsStream = tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+type WsWrite = SplitSink<WsStream, Message>;
+type WsRead = SplitStream<WsStream>;
+
+impl<H: Handler> Connection<H> {
+    /// 连续失败时重连的等待时间逐次增加，连接保持 stable_after 以上才重新计数
+    async fn run(&mut self) {
+        let mut attempt = 0;
+        loop {
+            let ws = match tokio_tungstenite::connect_async(self.config.url.as_str()).await {
+                Ok((ws, _)) => ws,
+                Err(e) => {
+                    attempt += 1;
+                    let delay = self.config.reconnect.backoff(attempt);
+                    warn!("websocket connect to {} failed, retry in {}ms: {}", self.config.url, delay.as_millis(), e);
+                    if !self.wait(delay).await {
+                        return;
+                    }
+                    continue;
+                }
+            };
+            info!("websocket connected: {}", self.config.url);
+
+            let connected = Instant::now();
+            let end = self.session(ws).await;
+
+            let mut out = Output::default();
+            self.handler.on_disconnected(&mut out);
+            if !self.emit(out.events).await {
+                return;
+            }
+            match end {
+                SessionEnd::Stop => return,
+                // 登录失败不算正常连接
+                SessionEnd::Reconnect if connected.elapsed() >= self.config.stable_after => attempt = 1,
+                SessionEnd::Reconnect | SessionEnd::LoginFailed => attempt += 1,
+            }
+            let delay = self.config.reconnect.backoff(attempt);
+            warn!("websocket disconnected: {}, reconnect in {}ms", self.config.url, delay.as_millis());
+            if !self.wait(delay).await {
+                return;
+            }
+        }
+    }
+
+    /// 等待重连期间继续接收命令，更新重连后要订阅的频道
+    /// 命令发送端全部 drop 或事件接收端 drop 时返回 false
+    async fn wait(&mut self, delay: Duration) -> bool {
+        let deadline = Instant::now() + delay;
+        loop {
+            if self.event_tx.is_closed() {
+                return false;
+            }
+            tokio::select! {
+                cmd = self.cmd_rx.recv() => match cmd {
+                    None => return false,
+                    Some(Command::Subscribe(args)) => {
+                        let args: Vec<Arg> = args.into_iter().filter(|a| !self.subscriptions.contains(a)).collect();
+                        self.subscriptions.extend(args);
+                    }
+                    Some(Command::Unsubscribe(args)) => self.subscriptions.retain(|a| !args.contains(a)),
+                },
+                _ = tokio::time::sleep_until(deadline) => return true,
+            }
+        }
+    }
+
+    async fn session(&mut self, ws: WsStream) -> SessionEnd {
+        let (mut write, mut read) = ws.split();
+
+        if let Some(credentials) = self.config.credentials.clone() {
+            match self.login(&credentials, &mut write, &mut read).await {
+                Some(true) => info!("websocket login succeeded: {}", self.config.url),
+                Some(false) => return SessionEnd::LoginFailed,
+                None => return SessionEnd::Reconnect,
+            }
+        }
+
+        let subscriptions = self.subscriptions.clone();
+        if !subscriptions.is_empty() && !self.send_op(&mut write, "subscribe", &subscriptions).await {
+            return SessionEnd::Reconnect;
+        }
+        let mut out = Output::default();
+        self.handler.on_connected(&mut out);
+        if !self.emit(out.events).await {
+            return SessionEnd::Stop;
+        }
+
+        let mut last_recv = Instant::now();
+        let mut ping_sent: Option<Instant> = None;
+        loop {
+            let deadline = match ping_sent {
+                Some(sent) => sent + self.config.pong_timeout,
+                None => last_recv + self.config.ping_interval,
+            };
+            tokio::select! {
+                cmd = self.cmd_rx.recv() => {
+                    let ok = match cmd {
+                        None => {
+                            let _ = write.close().await;
+                            return SessionEnd::Stop;
+                        }
+                        Some(Command::Subscribe(args)) => {
+                            let args: Vec<Arg> = args.into_iter().filter(|a| !self.subscriptions.contains(a)).collect();
+                            self.subscriptions.extend(args.iter().cloned());
+                            args.is_empty() || self.send_op(&mut write, "subscribe", &args).await
+                        }
+                        Some(Command::Unsubscribe(args)) => {
+                            self.subscriptions.retain(|a| !args.contains(a));
+                            self.send_op(&mut write, "unsubscribe", &args).await
+                        }
+                    };
+                    if !ok {
+                        return SessionEnd::Reconnect;
+                    }
+                }
+                msg = read.next() => {
+                    last_recv = Instant::now();
+                    ping_sent = None;
+                    let text = match msg {
+                        Some(Ok(Message::Text(text))) => text,
+                        Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return SessionEnd::Reconnect,
+                        Some(Ok(_)) => continue,
+                    };
+                    if text.as_str() == "pong" {
+                        continue;
+                    }
+                    let out = self.dispatch(text.as_str());
+                    if !out.resubscribe.is_empty() {
+                        warn!("websocket resubscribe: {:?}", out.resubscribe);
+                        let ok = self.send_op(&mut write, "unsubscribe", &out.resubscribe).await
+                            && self.send_op(&mut write, "subscribe", &out.resubscribe).await;
+                        if !ok {
+                            return SessionEnd::Reconnect;
+                        }
+                    }
+                    if !self.emit(out.events).await {
+                        return SessionEnd::Stop;
+                    }
+                }
+                _ = tokio::time::sleep_until(deadline) => {
+                    if ping_sent.is_some() {
+                        warn!("websocket pong timeout: {}", self.config.url);
+                        return SessionEnd::Reconnect;
+                    }
+                    if write.send(Message::text("ping")).await.is_err() {
+                        return SessionEnd::Reconnect;
+                    }
+                    ping_sent = Some(Instant::now());
+                }
+            }
+        }
+    }
+
+    fn dispatch(&mut self, text: &str) -> Output<H::Event> {
+        let mut out = Output::default();
+        let msg: WsMessage = match serde_json::from_str(text) {
+            Ok(msg) => msg,
+            Err(e) => {
+                warn!("unexpected websocket message: {}, {}", e, text);
+                return out;
+            }
+        };
+        match msg.event.as_deref() {
+            Some("error") => {
+                let code = msg.code.unwrap_or_default();
+                let message = msg.msg.unwrap_or_default();
+                warn!("websocket error: code={}, msg={}", code, message);
+                self.handler.on_error(&code, &message, &mut out);
+            }
+            Some("subscribe") => {
+                debug!("websocket event: {}", text);
+                if let Some(arg) = &msg.arg {
+                    self.handler.on_subscribed(arg);
+                }
+            }
+            Some(_) => debug!("websocket event: {}", text),
+            None => {
+                if let (Some(arg), Some(data)) = (msg.arg, msg.data) {
+                    self.handler.on_data(arg, msg.action, data, &mut out);
+                }
+            }
+        }
+        out
+    }
+
+    /// 登录私有频道
+    /// sign = Base64(HMAC_SHA256(timestamp + "GET" + "/users/self/verify", SecretKey))，timestamp 为 Unix 秒
+    /// 登录成功返回 Some(true)，被拒绝返回 Some(false)，连接异常或超时返回 None
+    async fn login(&mut self, credentials: &Credentials, write: &mut WsWrite, read: &mut WsRead) -> Option<bool> {
+        let timestamp = Utc::now().timestamp().to_string();
+        let request = json!({
+            "op": "login",
+            "args": [{
+                "apiKey": credentials.api_key,
+                "passphrase": credentials.passphrase,
+                "timestamp": timestamp,
+                "sign": credentials.sign(&timestamp, "GET", "/users/self/verify", ""),
+            }]
+        });
+        write.send(Message::text(request.to_string())).await.ok()?;
+
+        let deadline = Instant::now() + self.config.pong_timeout;
+        loop {
+            let text = match tokio::time::timeout_at(deadline, read.next()).await {
+                Ok(Some(Ok(Message::Text(text)))) => text,
+                Ok(Some(Ok(Message::Close(_)))) | Ok(Some(Err(_))) | Ok(None) => return None,
+                Ok(Some(Ok(_))) => continue,
+                Err(_) => {
+                    warn!("websocket login timeout: {}", self.config.url);
+                    return None;
+                }
+            };
+            let Ok(msg) = serde_json::from_str::<WsMessage>(text.as_str()) else {
+                continue;
+            };
+            match msg.event.as_deref() {
+                Some("login") if msg.code.as_deref() == Some("0") => return Some(true),
+                Some("login") | Some("error") => {
+                    let code = msg.code.unwrap_or_default();
+                    let message = msg.msg.unwrap_or_default();
+                    warn!("websocket login failed: code={}, msg={}", code, message);
+                    let mut out = Output::default();
+                    self.handler.on_error(&code, &message, &mut out);
+                    self.emit(out.events).await;
+                    return Some(false);
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    async fn send_op(&mut self, write: &mut WsWrite, op: &str, args: &[Arg]) -> bool {
+        for chunk in args.chunks(self.config.subscribe_batch.max(1)) {
+            let request = json!({ "op": op, "args": chunk });
+            if write.send(Message::text(request.to_string())).await.is_err() {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// 发送事件，接收端已经关闭时返回 false
+    async fn emit(&mut self, events: Vec<H::Event>) -> bool {
+        for event in events {
+            if self.event_tx.send(event).await.is_err() {
+                return false;
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,290 @@
+//! 私有频道，连接后需要登录
+//! wss://ws.okx.com:8443/ws/v5/private
+
+use std::collections::HashSet;
+
+use log::warn;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use crate::action::account::{InstAccount, InstBalanceAndPosition, InstPosition};
+use crate::action::error::OkxError;
+use crate::action::trade::InstOrder;
+use crate::action::ws::{self, Arg, Command, Handler, Output, WsConfig};
+use crate::model::account::AccountState;
+use crate::model::order::Order;
+use crate::model::symbol::Category;
+
+/// 私有频道推送的事件
+#[derive(Debug, Clone)]
+pub enum PrivateEvent {
+    /// 连接并登录成功，已有的订阅会自动重新订阅
+    Connected,
+    /// 连接断开，之后会自动重连，重连前的账户状态可能已过期
+    Disconnected,
+    /// 订单状态变化，包括新的成交
+    Order(Box<Order>),
+    /// 任意私有频道推送后合并出的完整账户状态
+    Account(Box<AccountState>),
+    /// 服务端返回的错误，如登录失败
+    Error { code: String, msg: String },
+}
+
+/// 私有频道客户端
+#[derive(Clone)]
+pub struct PrivateWs {
+    cmd_tx: mpsc::UnboundedSender<Command>,
+}
+
+impl PrivateWs {
+    /// 建立连接，config 中必须包含 API Key
+    pub fn connect(config: WsConfig) -> Result<(PrivateWs, mpsc::Receiver<PrivateEvent>), OkxError> {
+        if config.credentials.is_none() {
+            return Err(OkxError::MissingCredentials);
+        }
+        let (cmd_tx, event_rx) = ws::spawn(config, PrivateHandler::default());
+        Ok((PrivateWs { cmd_tx }, event_rx))
+    }
+
+    /// 账户频道
+    /// account
+    pub fn subscribe_account(&self) -> Result<(), OkxError> {
+        self.send(Command::Subscribe(vec![Arg::channel("account")]))
+    }
+
+    /// 持仓频道，category 为 None 时订阅所有类型
+    /// positions
+    pub fn subscribe_positions(&self, category: Option<Category>) -> Result<(), OkxError> {
+        self.send(Command::Subscribe(vec![Self::inst_type_arg("positions", category)]))
+    }
+
+    /// 订单频道，category 为 None 时订阅所有类型
+    /// orders
+    pub fn subscribe_orders(&self, category: Option<Category>) -> Result<(), OkxError> {
+        self.send(Command::Subscribe(vec![Self::inst_type_arg("orders", category)]))
+    }
+
+    /// 账户余额和持仓频道，成交、划转等事件触发
+    /// balance_and_position
+    pub fn subscribe_balance_and_position(&self) -> Result<(), OkxError> {
+        self.send(Command::Subscribe(vec![Arg::channel("balance_and_position")]))
+    }
+
+    /// 订阅以上全部频道
+    pub fn subscribe_all(&self) -> Result<(), OkxError> {
+        self.send(Command::Subscribe(vec![
+            Arg::channel("account"),
+            Self::inst_type_arg("positions", None),
+            Self::inst_type_arg("orders", None),
+            Arg::channel("balance_and_position"),
+        ]))
+    }
+
+    fn inst_type_arg(channel: &str, category: Option<Category>) -> Arg {
+        Arg::inst_type(channel, category.as_ref().map(|c| c.as_str()).unwrap_or("ANY"))
+    }
+
+    fn send(&self, command: Command) -> Result<(), OkxError> {
+        self.cmd_tx.send(command).map_err(|_| OkxError::WsClosed)
+    }
+}
+
+#[derive(Default)]
+struct PrivateHandler {
+    state: AccountState,
+    /// 刚订阅成功的频道，下一条推送为全量数据
+    snapshot_pending: HashSet<String>,
+}
+
+fn decode<T: DeserializeOwned>(channel: &str, data: Vec<Value>) -> Vec<T> {
+    data.into_iter()
+        .filter_map(|value| match serde_json::from_value(value) {
+            Ok(item) => Some(item),
+            Err(e) => {
+                warn!("failed to decode {} push: {}", channel, e);
+                None
+            }
+        })
+        .collect()
+}
+
+impl Handler for PrivateHandler {
+    type Event = PrivateEvent;
+
+    fn on_connected(&mut self, out: &mut Output<PrivateEvent>) {
+        out.events.push(PrivateEvent::Connected);
+    }
+
+    fn on_disconnected(&mut self, out: &mut Output<PrivateEvent>) {
+        out.events.push(PrivateEvent::Disconnected);
+    }
+
+    fn on_error(&mut self, code: &str, msg: &str, out: &mut Output<PrivateEvent>) {
+        out.events.push(PrivateEvent::Error {
+            code: code.to_string(),
+            msg: msg.to_string(),
+        });
+    }
+
+    fn on_subscribed(&mut self, arg: &Arg) {
+        self.snapshot_pending.insert(arg.channel.clone());
+    }
+
+    fn on_data(&mut self, arg: Arg, _action: Option<String>, data: Vec<Value>, out: &mut Output<PrivateEvent>) {
+        let snapshot = self.snapshot_pending.remove(&arg.channel);
+        match arg.channel.as_str() {
+            "account" => {
+                for account in decode::<InstAccount>(&arg.channel, data) {
+                    if let Some(account) = account.into_account() {
+                        self.state.apply_account(account, snapshot);
+                    }
+                }
+            }
+            "positions" => {
+                let positions = decode::<InstPosition>(&arg.channel, data)
+                    .into_iter()
+                    .filter_map(InstPosition::into_position)
+                    .collect();
+                self.state.apply_positions(positions, snapshot);
+            }
+            "orders" => {
+                for order in decode::<InstOrder>(&arg.channel, data) {
+                    if let Some(order) = order.into_order() {
+                        self.state.apply_order(&order);
+                        out.events.push(PrivateEvent::Order(Box::new(order)));
+                    }
+                }
+            }
+            "balance_and_position" => {
+                for update in decode::<InstBalanceAndPosition>(&arg.channel, data) {
+                    self.state.apply_balance_and_position(update.into_update());
+                }
+            }
+            _ => return,
+        }
+        out.events.push(PrivateEvent::Account(Box::new(self.state.clone())));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::auth::Credentials;
+    use crate::action::retry::RetryPolicy;
+    use crate::action::ws::mock::MockWsServer;
+    use bigdecimal::BigDecimal;
+    use serde_json::json;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    fn config(url: String) -> WsConfig {
+        WsConfig {
+            url,
+            reconnect: RetryPolicy {
+                base_delay: Duration::from_millis(10),
+                max_delay: Duration::from_millis(20),
+                ..Default::default()
+            },
+            credentials: Some(Credentials::new("key", "22582BD0CFF14C41EDBF1AB98506286D", "pass")),
+            ..Default::default()
+        }
+    }
+
+    async fn next_account(events: &mut mpsc::Receiver<PrivateEvent>) -> AccountState {
+        loop {
+            match tokio::time::timeout(Duration::from_secs(5), events.recv()).await.unwrap().unwrap() {
+                PrivateEvent::Account(state) => return *state,
+                _ => continue,
+            }
+        }
+    }
+
+    #[test]
+    fn test_requires_credentials() {
+        let config = WsConfig {
+            credentials: None,
+            ..Default::default()
+        };
+        assert!(matches!(PrivateWs::connect(config), Err(OkxError::MissingCredentials)));
+    }
+
+    #[tokio::test]
+    async fn test_login_then_subscribe() {
+        let server = MockWsServer::start().await;
+        let (client, mut events) = PrivateWs::connect(config(server.url())).unwrap();
+        client.subscribe_all().unwrap();
+
+        let mut conn = server.accept().await;
+        let login = conn.recv_json().await;
+        assert_eq!(login["op"], "login");
+        let args = &login["args"][0];
+        assert_eq!(args["apiKey"], "key");
+        assert_eq!(args["passphrase"], "pass");
+        let timestamp = args["timestamp"].as_str().unwrap();
+        assert!(timestamp.parse::<i64>().is_ok());
+        let expected = crate::action::auth::sign("22582BD0CFF14C41EDBF1AB98506286D", timestamp, "GET", "/users/self/verify", "");
+        assert_eq!(args["sign"], expected);
+
+        conn.send(r#"{"event":"login","code":"0","msg":"","connId":"a4d3ae55"}"#).await;
+        assert_eq!(
+            conn.recv_json().await,
+            json!({"op": "subscribe", "args": [
+                {"channel": "account"},
+                {"channel": "positions", "instType": "ANY"},
+                {"channel": "orders", "instType": "ANY"},
+                {"channel": "balance_and_position"}
+            ]})
+        );
+
+        conn.send(r#"{"event":"subscribe","arg":{"channel":"account"},"connId":"a4d3ae55"}"#).await;
+        conn.send(&json!({
+            "arg": {"channel": "account", "uid": "44705892343619584"},
+            "data": [{"uTime": "1614846244194", "totalEq": "1000", "adjEq": "", "imr": "", "mmr": "", "mgnRatio": "",
+                "details": [{"ccy": "USDT", "eq": "1000", "cashBal": "1000", "availBal": "900", "frozenBal": "100", "upl": "0", "eqUsd": "1000", "uTime": "1614846244194"}]}]
+        }).to_string())
+        .await;
+        let state = next_account(&mut events).await;
+        assert_eq!(state.total_eq, Some(BigDecimal::from(1000)));
+        assert_eq!(state.adj_eq, None);
+        assert_eq!(state.balance("USDT").unwrap().avail_bal, Some(BigDecimal::from(900)));
+
+        conn.send(&json!({
+            "arg": {"channel": "orders", "instType": "ANY", "uid": "44705892343619584"},
+            "data": [{"instType": "SWAP", "instId": "BTC-USDT-SWAP", "ordId": "312269865356374016", "clOrdId": "",
+                "px": "30000", "sz": "2", "ordType": "limit", "side": "buy", "posSide": "net", "tdMode": "cross",
+                "accFillSz": "1", "fillPx": "29999.9", "fillSz": "1", "tradeId": "123", "avgPx": "29999.9",
+                "state": "partially_filled", "fee": "-0.01", "feeCcy": "USDT", "cTime": "1614846244000", "uTime": "1614846244195"}]
+        }).to_string())
+        .await;
+        let order = loop {
+            if let PrivateEvent::Order(order) = events.recv().await.unwrap() {
+                break order;
+            }
+        };
+        assert_eq!(order.fill_sz, Some(BigDecimal::from(1)));
+        assert_eq!(order.fill_px, Some(BigDecimal::from_str("29999.9").unwrap()));
+        assert_eq!(order.cl_ord_id, None);
+        let state = next_account(&mut events).await;
+        assert!(state.orders.contains_key("312269865356374016"));
+    }
+
+    #[tokio::test]
+    async fn test_login_failure_reports_error_and_retries() {
+        let server = MockWsServer::start().await;
+        let (_client, mut events) = PrivateWs::connect(config(server.url())).unwrap();
+
+        let mut conn = server.accept().await;
+        conn.recv_json().await;
+        conn.send(r#"{"event":"error","code":"60009","msg":"Login failed."}"#).await;
+        loop {
+            if let PrivateEvent::Error { code, .. } = events.recv().await.unwrap() {
+                assert_eq!(code, "60009");
+                break;
+            }
+        }
+
+        let mut conn = server.accept().await;
+        assert_eq!(conn.recv_json().await["op"], "login");
+    }
+}
@@ -0,0 +1,388 @@
+//! 公共频道，不需要登录
+//! wss://ws.okx.com:8443/ws/v5/public
+
+use std::collections::HashMap;
+
+use log::warn;
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use crate::action::book::InstBook;
+use crate::action::error::OkxError;
+use crate::action::ticker::InstTicker;
+use crate::action::ws::{self, Arg, Command, Handler, Output, WsConfig};
+use crate::model::order_book::OrderBook;
+use crate::model::symbol::Ticker;
+
+/// 深度频道
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BookChannel {
+    /// 首次推送 400 档快照，之后每 100ms 推送增量，带校验和
+    Books,
+    /// 每 100ms 推送 5 档快照
+    Books5,
+    /// 每 10ms 推送 1 档快照
+    BboTbt,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl BookChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BookChannel::Books => "books",
+            BookChannel::Books5 => "books5",
+            BookChannel::BboTbt => "bbo-tbt",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<BookChannel> {
+        match s {
+            "books" => Some(BookChannel::Books),
+            "books5" => Some(BookChannel::Books5),
+            "bbo-tbt" => Some(BookChannel::BboTbt),
+            _ => None,
+        }
+    }
+}
+
+/// 公共频道推送的事件
+#[derive(Debug, Clone)]
+pub enum PublicEvent {
+    /// 连接成功，已有的订阅会自动重新订阅
+    Connected,
+    /// 连接断开，之后会自动重连
+    Disconnected,
+    Ticker(Box<Ticker>),
+    /// 深度更新后的完整本地深度
+    Book { channel: BookChannel, book: Box<OrderBook> },
+    /// 服务端返回的错误，如订阅了不存在的产品
+    Error { code: String, msg: String },
+}
+
+/// 公共频道客户端
+/// 连接在后台任务中维护，所有 PublicWs 被 drop 后连接关闭
+#[derive(Clone)]
+pub struct PublicWs {
+    cmd_tx: mpsc::UnboundedSender<Command>,
+}
+
+impl PublicWs {
+    /// 建立连接，返回客户端和事件接收端
+    pub fn connect(config: WsConfig) -> (PublicWs, mpsc::Receiver<PublicEvent>) {
+        let (cmd_tx, event_rx) = ws::spawn(config, PublicHandler::default());
+        (PublicWs { cmd_tx }, event_rx)
+    }
+
+    /// 订阅行情频道
+    /// tickers
+    pub fn subscribe_tickers(&self, inst_ids: &[&str]) -> Result<(), OkxError> {
+        self.send(Command::Subscribe(Self::ticker_args(inst_ids)))
+    }
+
+    pub fn unsubscribe_tickers(&self, inst_ids: &[&str]) -> Result<(), OkxError> {
+        self.send(Command::Unsubscribe(Self::ticker_args(inst_ids)))
+    }
+
+    /// 订阅深度频道，本地深度校验失败或序号不连续时会自动重新订阅
+    pub fn subscribe_books(&self, channel: BookChannel, inst_ids: &[&str]) -> Result<(), OkxError> {
+        self.send(Command::Subscribe(Self::args(channel.as_str(), inst_ids)))
+    }
+
+    pub fn unsubscribe_books(&self, channel: BookChannel, inst_ids: &[&str]) -> Result<(), OkxError> {
+        self.send(Command::Unsubscribe(Self::args(channel.as_str(), inst_ids)))
+    }
+
+    fn ticker_args(inst_ids: &[&str]) -> Vec<Arg> {
+        Self::args("tickers", inst_ids)
+    }
+
+    fn args(channel: &str, inst_ids: &[&str]) -> Vec<Arg> {
+        inst_ids.iter().map(|id| Arg::inst(channel, id)).collect()
+    }
+
+    fn send(&self, command: Command) -> Result<(), OkxError> {
+        self.cmd_tx.send(command).map_err(|_| OkxError::WsClosed)
+    }
+}
+
+#[derive(Default)]
+struct PublicHandler {
+    /// 本地深度，按频道和产品区分
+    books: HashMap<(BookChannel, String), OrderBook>,
+}
+
+impl PublicHandler {
+    fn on_tickers(&mut self, data: Vec<Value>, out: &mut Output<PublicEvent>) {
+        for value in data {
+            let inst_ticker: InstTicker = match serde_json::from_value(value) {
+                Ok(t) => t,
+                Err(e) => {
+                    warn!("failed to decode ticker push: {}", e);
+                    continue;
+                }
+            };
+            match inst_ticker.to_ticker() {
+                Ok(ticker) => out.events.push(PublicEvent::Ticker(Box::new(ticker))),
+                Err(e) => warn!("{}", e),
+            }
+        }
+    }
+
+    /// books 先推送快照再推送增量，books5 和 bbo-tbt 每次都是快照，没有 action 字段
+    fn on_books(&mut self, channel: BookChannel, arg: Arg, action: Option<String>, data: Vec<Value>, out: &mut Output<PublicEvent>) {
+        let inst_id = match &arg.inst_id {
+            Some(inst_id) => inst_id.clone(),
+            None => return,
+        };
+        let key = (channel, inst_id.clone());
+        for value in data {
+            let update = match serde_json::from_value::<InstBook>(value).map_err(|e| e.to_string()).and_then(|b| b.to_update()) {
+                Ok(update) => update,
+                Err(e) => {
+                    warn!("failed to decode {} push of {}: {}", channel.as_str(), inst_id, e);
+                    continue;
+                }
+            };
+            let result = if action.as_deref() == Some("update") {
+                match self.books.get_mut(&key) {
+                    Some(book) => book.apply_update(update),
+                    // 重新订阅后快照到达之前的增量，忽略
+                    None => continue,
+                }
+            } else {
+                self.books.entry(key.clone()).or_insert_with(|| OrderBook::new(&inst_id)).apply_snapshot(update)
+            };
+            match result {
+                Ok(()) => out.events.push(PublicEvent::Book {
+                    channel,
+                    book: Box::new(self.books[&key].clone()),
+                }),
+                Err(e) => {
+                    warn!("{} of {}: {}, resubscribing", channel.as_str(), inst_id, e);
+                    self.books.remove(&key);
+                    out.resubscribe.push(arg);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+impl Handler for PublicHandler {
+    type Event = PublicEvent;
+
+    fn on_connected(&mut self, out: &mut Output<PublicEvent>) {
+        out.events.push(PublicEvent::Connected);
+    }
+
+    fn on_disconnected(&mut self, out: &mut Output<PublicEvent>) {
+        // 重连后会重新推送快照
+        self.books.clear();
+        out.events.push(PublicEvent::Disconnected);
+    }
+
+    fn on_error(&mut self, code: &str, msg: &str, out: &mut Output<PublicEvent>) {
+        out.events.push(PublicEvent::Error {
+            code: code.to_string(),
+            msg: msg.to_string(),
+        });
+    }
+
+    fn on_data(&mut self, arg: Arg, action: Option<String>, data: Vec<Value>, out: &mut Output<PublicEvent>) {
+        if arg.channel == "tickers" {
+            self.on_tickers(data, out);
+        } else if let Some(channel) = BookChannel::from_str(&arg.channel) {
+            self.on_books(channel, arg, action, data, out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::retry::RetryPolicy;
+    use crate::action::ws::mock::MockWsServer;
+    use bigdecimal::BigDecimal;
+    use serde_json::json;
+    use std::time::Duration;
+
+    fn config(url: String) -> WsConfig {
+        WsConfig {
+            url,
+            reconnect: RetryPolicy {
+                base_delay: Duration::from_millis(10),
+                max_delay: Duration::from_millis(20),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn ticker_push(inst_id: &str, last: &str) -> String {
+        json!({
+            "arg": {"channel": "tickers", "instId": inst_id},
+            "data": [{
+                "instType": "SWAP", "instId": inst_id, "last": last, "lastSz": "1",
+                "askPx": "", "askSz": "", "bidPx": "9999", "bidSz": "5",
+                "open24h": "9000", "high24h": "10000", "low24h": "8888", "volCcy24h": "2222", "vol24h": "2222",
+                "sodUtc0": "0.1", "sodUtc8": "0.1", "ts": "1597026383085"
+            }]
+        })
+        .to_string()
+    }
+
+    async fn next_ticker(events: &mut mpsc::Receiver<PublicEvent>) -> Ticker {
+        loop {
+            match tokio::time::timeout(Duration::from_secs(5), events.recv()).await.unwrap().unwrap() {
+                PublicEvent::Ticker(ticker) => return *ticker,
+                _ => continue,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_and_receive_tickers() {
+        let server = MockWsServer::start().await;
+        let (client, mut events) = PublicWs::connect(config(server.url()));
+        client.subscribe_tickers(&["BTC-USDT-SWAP", "ETH-USDT-SWAP"]).unwrap();
+
+        let mut conn = server.accept().await;
+        let request = conn.recv_json().await;
+        assert_eq!(
+            request,
+            json!({"op": "subscribe", "args": [
+                {"channel": "tickers", "instId": "BTC-USDT-SWAP"},
+                {"channel": "tickers", "instId": "ETH-USDT-SWAP"}
+            ]})
+        );
+
+        conn.send(r#"{"event":"subscribe","arg":{"channel":"tickers","instId":"BTC-USDT-SWAP"},"connId":"a4d3ae55"}"#).await;
+        conn.send(&ticker_push("BTC-USDT-SWAP", "10000.5")).await;
+        let ticker = next_ticker(&mut events).await;
+        assert_eq!(ticker.inst_id, "BTC-USDT-SWAP");
+        assert_eq!(ticker.base, "BTC");
+        assert_eq!(ticker.last.to_string(), "10000.5");
+        assert_eq!(ticker.ask_px, None);
+
+        client.unsubscribe_tickers(&["ETH-USDT-SWAP"]).unwrap();
+        assert_eq!(
+            conn.recv_json().await,
+            json!({"op": "unsubscribe", "args": [{"channel": "tickers", "instId": "ETH-USDT-SWAP"}]})
+        );
+
+        conn.send(r#"{"event":"error","code":"60018","msg":"Wrong URL or channel:tickers,instId:FOO-BAR doesn't exist."}"#).await;
+        loop {
+            if let PublicEvent::Error { code, .. } = events.recv().await.unwrap() {
+                assert_eq!(code, "60018");
+                break;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_resubscribes() {
+        let server = MockWsServer::start().await;
+        let (client, mut events) = PublicWs::connect(config(server.url()));
+        client.subscribe_tickers(&["BTC-USDT"]).unwrap();
+
+        let mut conn = server.accept().await;
+        conn.recv_json().await;
+        conn.close().await;
+
+        // 重连后自动订阅之前的频道
+        let mut conn = server.accept().await;
+        assert_eq!(
+            conn.recv_json().await,
+            json!({"op": "subscribe", "args": [{"channel": "tickers", "instId": "BTC-USDT"}]})
+        );
+        conn.send(&ticker_push("BTC-USDT", "1")).await;
+
+        let mut seen = Vec::new();
+        loop {
+            let event = events.recv().await.unwrap();
+            let done = matches!(event, PublicEvent::Ticker(_));
+            seen.push(event);
+            if done {
+                break;
+            }
+        }
+        assert!(matches!(
+            seen.as_slice(),
+            [PublicEvent::Connected, PublicEvent::Disconnected, PublicEvent::Connected, PublicEvent::Ticker(_)]
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stop_while_unable_to_connect() {
+        // 没有服务监听的端口，连接一直失败
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let (client, mut events) = PublicWs::connect(config(url));
+        client.subscribe_tickers(&["BTC-USDT"]).unwrap();
+        drop(client);
+        // 命令发送端全部 drop 后任务退出，事件接收端随之关闭
+        let closed = tokio::time::timeout(Duration::from_secs(5), events.recv()).await.unwrap();
+        assert!(closed.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_books_resubscribe_on_checksum_mismatch() {
+        let server = MockWsServer::start().await;
+        let (client, mut events) = PublicWs::connect(config(server.url()));
+        client.subscribe_books(BookChannel::Books, &["BTC-USDT"]).unwrap();
+
+        let mut conn = server.accept().await;
+        assert_eq!(
+            conn.recv_json().await,
+            json!({"op": "subscribe", "args": [{"channel": "books", "instId": "BTC-USDT"}]})
+        );
+
+        let checksum = crc32fast::hash(b"100:1:101:2") as i32;
+        conn.send(&json!({
+            "arg": {"channel": "books", "instId": "BTC-USDT"},
+            "action": "snapshot",
+            "data": [{"asks": [["101", "2", "0", "1"]], "bids": [["100", "1", "0", "1"]], "ts": "1", "checksum": checksum, "prevSeqId": -1, "seqId": 10}]
+        }).to_string())
+        .await;
+        let book = loop {
+            if let PublicEvent::Book { channel, book } = events.recv().await.unwrap() {
+                assert_eq!(channel, BookChannel::Books);
+                break book;
+            }
+        };
+        assert_eq!(book.best_bid().unwrap().px, BigDecimal::from(100));
+        assert_eq!(book.best_ask().unwrap().sz, BigDecimal::from(2));
+
+        conn.send(&json!({
+            "arg": {"channel": "books", "instId": "BTC-USDT"},
+            "action": "update",
+            "data": [{"asks": [], "bids": [["100", "3", "0", "2"]], "ts": "2", "checksum": 1, "prevSeqId": 10, "seqId": 11}]
+        }).to_string())
+        .await;
+        let arg = json!([{"channel": "books", "instId": "BTC-USDT"}]);
+        assert_eq!(conn.recv_json().await, json!({"op": "unsubscribe", "args": arg}));
+        assert_eq!(conn.recv_json().await, json!({"op": "subscribe", "args": arg}));
+    }
+
+    #[tokio::test]
+    async fn test_ping_pong_heartbeat() {
+        let server = MockWsServer::start().await;
+        let (_client, mut events) = PublicWs::connect(WsConfig {
+            ping_interval: Duration::from_millis(50),
+            pong_timeout: Duration::from_millis(100),
+            ..config(server.url())
+        });
+
+        // 空闲时发送 ping，收到 pong 后连接保持
+        let mut conn = server.accept().await;
+        assert_eq!(conn.recv().await, "ping");
+        conn.send("pong").await;
+        assert_eq!(conn.recv().await, "ping");
+
+        // 不回复 pong，超时后重连
+        let _conn = server.accept().await;
+        assert!(matches!(events.recv().await, Some(PublicEvent::Connected)));
+        assert!(matches!(events.recv().await, Some(PublicEvent::Disconnected)));
+        assert!(matches!(events.recv().await, Some(PublicEvent::Connected)));
+    }
+}
@@ -1 +1,10 @@
-pub static  REST_API_HOST : &str ="https://www.okx.com";
\ No newline at end of file
+pub static  REST_API_HOST : &str ="https://www.okx.com";
+
+pub static WS_PUBLIC_HOST: &str = "wss://ws.okx.com:8443/ws/v5/public";
+pub static WS_PRIVATE_HOST: &str = "wss://ws.okx.com:8443/ws/v5/private";
+pub static WS_BUSINESS_HOST: &str = "wss://ws.okx.com:8443/ws/v5/business";
+
+/// 模拟盘 WebSocket 地址，REST 与实盘共用域名，通过 x-simulated-trading 请求头区分
+pub static WS_DEMO_PUBLIC_HOST: &str = "wss://wspap.okx.com:8443/ws/v5/public";
+pub static WS_DEMO_PRIVATE_HOST: &str = "wss://wspap.okx.com:8443/ws/v5/private";
+pub static WS_DEMO_BUSINESS_HOST: &str = "wss://wspap.okx.com:8443/ws/v5/business";
@@ -0,0 +1,278 @@
+use std::collections::{HashMap, HashSet};
+
+use bigdecimal::{BigDecimal, FromPrimitive, Signed};
+use chrono::{Local, Utc};
+use log::{info, warn};
+use serde::Deserialize;
+
+use crate::action::{client::OkxClient, telegram::broadcast};
+use crate::logic::spot_swap::is_stale;
+use crate::model::spread::{CalendarSpread, RollingBand};
+use crate::model::symbol::{Category, FutureAlias, State, Symbol, Ticker};
+use crate::util::log::log_okx_error;
+
+/// 跨期价差监控的配置，对应 conf/okx.yml 中的 calendar
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    /// 只监控这些交易品种，如 BTC-USDT，为空时监控全部
+    pub families: Vec<String>,
+    /// 滚动窗口的样本数，每 30 秒采样一次
+    pub window: usize,
+    /// 样本数达到该值后才判断是否偏离
+    pub min_samples: usize,
+    /// 价差偏离均值超过该倍数的标准差时提醒
+    pub z_threshold: BigDecimal,
+    /// 近月距交割不足该时间时换到下一组合约，临近交割的价差会快速收敛
+    pub min_expiry_hours: u64,
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        CalendarConfig {
+            families: Vec::new(),
+            window: 240,
+            min_samples: 60,
+            z_threshold: BigDecimal::from_f64(2.5).unwrap(),
+            min_expiry_hours: 24,
+        }
+    }
+}
+
+/// 监控同一交易品种相邻交割合约之间的价差
+/// 价差超出滚动的 z-score 区间时提醒；合约交割或上新后按新的合约组合重新采样
+pub async fn calendar_spread(client: OkxClient, config: CalendarConfig) {
+    let mut monitor = CalendarMonitor::new(client, config);
+    info!("calendar spread monitor started");
+    loop {
+        monitor.run().await;
+        tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
+    }
+}
+
+/// 一组合约的价差样本，key 为 (近月, 远月)
+type PairKey = (String, String);
+
+struct Track {
+    band: RollingBand,
+    /// 上一次是否在区间外，只在刚离开区间时提醒
+    outside: bool,
+}
+
+/// 离开区间的价差
+struct Alert {
+    inst_family: String,
+    spread: CalendarSpread,
+    /// 采样前的均值和 z-score
+    mean: BigDecimal,
+    z_score: BigDecimal,
+}
+
+struct CalendarMonitor {
+    client: OkxClient,
+    config: CalendarConfig,
+    tracks: HashMap<PairKey, Track>,
+}
+
+impl CalendarMonitor {
+    fn new(client: OkxClient, config: CalendarConfig) -> Self {
+        CalendarMonitor {
+            client,
+            config,
+            tracks: HashMap::new(),
+        }
+    }
+
+    async fn run(&mut self) {
+        let symbols = match self.client.get_symbols(Category::Future).await {
+            Ok(symbols) => symbols,
+            Err(e) => {
+                log_okx_error("fetch future symbols", &e);
+                return;
+            }
+        };
+        let tickers = match self.client.get_ticker(Category::Future).await {
+            Ok(tickers) => tickers,
+            Err(e) => {
+                log_okx_error("fetch future tickers", &e);
+                return;
+            }
+        };
+        let now = Utc::now().timestamp_millis() as u64;
+        let tickers: HashMap<&str, &Ticker> =
+            tickers.iter().filter(|t| !is_stale(t, now)).map(|t| (t.inst_id.as_str(), t)).collect();
+
+        let pairs = self.pairs(&symbols, now);
+        self.retain(&pairs.iter().map(|(_, near, far)| (near.inst_id.clone(), far.inst_id.clone())).collect());
+        let mut alerts = Vec::new();
+        for (family, near, far) in pairs {
+            let (Some(near_ticker), Some(far_ticker)) = (tickers.get(near.inst_id.as_str()), tickers.get(far.inst_id.as_str())) else {
+                continue;
+            };
+            let Some(spread) = CalendarSpread::new(near, far, near_ticker, far_ticker) else {
+                continue;
+            };
+            if let Some(alert) = self.observe(family, spread) {
+                alerts.push(alert);
+            }
+        }
+        if !alerts.is_empty() {
+            let msg = alert_message(&alerts);
+            if !broadcast(&msg).await {
+                warn!("sent telegram message failed:\n{}", msg);
+            }
+        }
+    }
+
+    /// 每个交易品种按交割时间排序后的相邻合约
+    /// 近月距交割不足 min_expiry_hours 的合约不参与，价差自动换到下一组
+    fn pairs<'a>(&self, symbols: &'a [Symbol], now: u64) -> Vec<(&'a str, &'a Symbol, &'a Symbol)> {
+        let min_expiry = now + self.config.min_expiry_hours * 3600 * 1000;
+        let mut families: HashMap<&str, Vec<&Symbol>> = HashMap::new();
+        for s in symbols.iter().filter(|s| s.state == State::Live && s.exp_time >= min_expiry) {
+            if self.config.families.is_empty() || self.config.families.contains(&s.inst_family) {
+                families.entry(s.inst_family.as_str()).or_default().push(s);
+            }
+        }
+        let mut pairs = Vec::new();
+        for (family, mut contracts) in families {
+            contracts.sort_by_key(|s| s.exp_time);
+            pairs.extend(contracts.windows(2).map(|w| (family, w[0], w[1])));
+        }
+        pairs
+    }
+
+    /// 丢弃已经交割或不再相邻的合约组合的样本
+    fn retain(&mut self, live: &HashSet<PairKey>) {
+        self.tracks.retain(|(near, far), _| {
+            let keep = live.contains(&(near.clone(), far.clone()));
+            if !keep {
+                info!("calendar spread {}/{} rolled off", near, far);
+            }
+            keep
+        });
+    }
+
+    /// 用采样前的区间判断价差是否偏离，再加入样本
+    fn observe(&mut self, inst_family: &str, spread: CalendarSpread) -> Option<Alert> {
+        let key = (spread.near_inst_id.clone(), spread.far_inst_id.clone());
+        let track = self.tracks.entry(key).or_insert_with(|| {
+            info!("track calendar spread {}/{}", spread.near_inst_id, spread.far_inst_id);
+            Track {
+                band: RollingBand::new(self.config.window),
+                outside: false,
+            }
+        });
+        let z_score = if track.band.len() >= self.config.min_samples {
+            track.band.z_score(&spread.spread_rate)
+        } else {
+            None
+        };
+        let mean = track.band.mean();
+        track.band.push(spread.spread_rate.clone());
+
+        let outside = z_score.as_ref().is_some_and(|z| z.abs() > self.config.z_threshold);
+        let entered = outside && !track.outside;
+        track.outside = outside;
+        if !entered {
+            return None;
+        }
+        Some(Alert {
+            inst_family: inst_family.to_string(),
+            spread,
+            mean: mean?,
+            z_score: z_score?,
+        })
+    }
+}
+
+fn alert_message(alerts: &[Alert]) -> String {
+    let percent = |v: &BigDecimal| v * BigDecimal::from(100);
+    let mut msg = format!("📏 **跨期价差偏离** 📏\n⏰ {}\n", Local::now().format("%m-%d %H:%M:%S"));
+    for (i, a) in alerts.iter().enumerate() {
+        let s = &a.spread;
+        let alias = |alias: Option<FutureAlias>| alias.map(|a| a.as_str()).unwrap_or("-");
+        let action = if a.z_score.is_positive() {
+            format!("📉 卖远月/买近月 `{:.4}`", s.sell_px)
+        } else {
+            format!("📈 买远月/卖近月 `{:.4}`", s.buy_px)
+        };
+        msg += &format!(
+            "📊 **{}** `{}` → `{}`\n🔄 {} / {}\n💱 价差: `{:.4}` ({:.3}%) 均值 {:.3}% z={:.2}\n🎯 年化: {:.2}%\n{}\n",
+            a.inst_family,
+            alias(s.near_alias),
+            alias(s.far_alias),
+            s.near_inst_id,
+            s.far_inst_id,
+            s.spread,
+            percent(&s.spread_rate),
+            percent(&a.mean),
+            a.z_score,
+            percent(&s.annualized),
+            action
+        );
+        if i < alerts.len() - 1 {
+            msg += "➖➖➖➖➖➖➖➖➖➖\n";
+        }
+    }
+    msg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::dec;
+    use crate::action::client::ClientConfig;
+
+    fn spread(near: &str, far: &str, rate: &str) -> CalendarSpread {
+        CalendarSpread {
+            near_inst_id: near.to_string(),
+            far_inst_id: far.to_string(),
+            near_alias: None,
+            far_alias: None,
+            near_exp_time: 0,
+            far_exp_time: 1,
+            spread: dec(rate) * dec("100"),
+            spread_rate: dec(rate),
+            annualized: dec(rate),
+            buy_px: dec("1"),
+            sell_px: dec("-1"),
+        }
+    }
+
+    fn monitor() -> CalendarMonitor {
+        let config = CalendarConfig {
+            window: 10,
+            min_samples: 4,
+            z_threshold: dec("2"),
+            ..Default::default()
+        };
+        CalendarMonitor::new(OkxClient::new(ClientConfig::default()).unwrap(), config)
+    }
+
+    #[test]
+    fn test_alert_when_leaving_band() {
+        let mut monitor = monitor();
+        for rate in ["0.010", "0.011", "0.009", "0.010"] {
+            assert!(monitor.observe("BTC-USDT", spread("Q", "NQ", rate)).is_none());
+        }
+        let alert = monitor.observe("BTC-USDT", spread("Q", "NQ", "0.02")).unwrap();
+        assert!(alert.z_score > dec("2"));
+        assert_eq!(alert.mean, dec("0.01"));
+        // 仍在区间外时不重复提醒
+        assert!(monitor.observe("BTC-USDT", spread("Q", "NQ", "0.03")).is_none());
+    }
+
+    #[test]
+    fn test_roll_resets_samples() {
+        let mut monitor = monitor();
+        for rate in ["0.010", "0.011", "0.009", "0.010"] {
+            monitor.observe("BTC-USDT", spread("Q", "NQ", rate));
+        }
+        // 季度合约交割后，次季度与新上的第三季度组成新的一组，需要重新采样
+        monitor.retain(&HashSet::from([("NQ".to_string(), "TQ".to_string())]));
+        assert!(monitor.tracks.is_empty());
+        assert!(monitor.observe("BTC-USDT", spread("NQ", "TQ", "0.05")).is_none());
+        assert_eq!(monitor.tracks[&("NQ".to_string(), "TQ".to_string())].band.len(), 1);
+    }
+}
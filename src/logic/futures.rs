@@ -0,0 +1,340 @@
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, FromPrimitive};
+use chrono::{DateTime, Local, Utc};
+use log::{info, warn};
+use serde::Deserialize;
+
+use crate::action::{client::OkxClient, telegram::broadcast};
+use crate::logic::spot_swap::is_stale;
+use crate::model::inst_id::InstId;
+use crate::model::quote::{QuoteConfig, QuoteConverter, QuotePath};
+use crate::model::symbol::{Category, State, Symbol, Ticker};
+use crate::model::term::{TermPoint, TermStructure};
+use crate::util::log::log_okx_error;
+
+/// 交割合约期限结构扫描的配置，对应 conf/okx.yml 中的 futures
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct FuturesConfig {
+    /// 可成交的年化基差超过该值时提醒
+    pub max_yield: BigDecimal,
+    /// 距交割不足该时间的合约年化后波动太大，不按收益提醒
+    pub min_expiry_hours: u64,
+}
+
+impl Default for FuturesConfig {
+    fn default() -> Self {
+        FuturesConfig {
+            max_yield: BigDecimal::from_f64(0.2).unwrap(),
+            min_expiry_hours: 24,
+        }
+    }
+}
+
+/// 比较交割合约与现货、永续的基差，按交易品种构建期限结构
+/// 出现倒挂或年化收益异常时发送提醒
+pub async fn futures_basis(client: OkxClient, config: FuturesConfig, quotes: Option<QuoteConfig>) {
+    let mut scanner = FuturesBasis {
+        client,
+        config,
+        quotes: QuoteConverter::new(quotes.unwrap_or_default()),
+        alerts: HashSet::new(),
+    };
+    info!("futures basis scanner started");
+    loop {
+        scanner.run().await;
+        tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
+    }
+}
+
+struct FuturesBasis {
+    client: OkxClient,
+    config: FuturesConfig,
+    quotes: QuoteConverter,
+    /// 上一次提醒的内容，没有变化时不重复提醒
+    alerts: HashSet<String>,
+}
+
+/// 一个交易品种的期限结构和需要提醒的内容
+struct Alert {
+    term: TermStructure,
+    /// 倒挂的相邻合约，现货记为 SPOT
+    inversions: Vec<(String, String)>,
+    /// 年化收益异常的合约
+    unusual: HashSet<String>,
+}
+
+impl Alert {
+    fn keys(&self) -> impl Iterator<Item = String> + '_ {
+        let inversions = self.inversions.iter().map(|(near, far)| format!("{}>{}", near, far));
+        inversions.chain(self.unusual.iter().cloned())
+    }
+}
+
+impl FuturesBasis {
+    async fn run(&mut self) {
+        let symbols = match self.client.get_symbols(Category::Future).await {
+            Ok(symbols) => symbols,
+            Err(e) => {
+                log_okx_error("fetch future symbols", &e);
+                return;
+            }
+        };
+        let Some(spot) = self.fetch_tickers(Category::Spot).await else {
+            return;
+        };
+        let Some(swap) = self.fetch_tickers(Category::Swap).await else {
+            return;
+        };
+        let Some(future) = self.fetch_tickers(Category::Future).await else {
+            return;
+        };
+        if self.quotes.config().use_index {
+            match self.client.get_index_tickers("USD").await {
+                Ok(index) => self.quotes.update_index(&index),
+                Err(e) => log_okx_error("fetch index tickers", &e),
+            }
+            self.quotes.update_spot(&spot);
+        }
+
+        let now = Utc::now().timestamp_millis() as u64;
+        let fresh = |tickers: Vec<Ticker>| -> HashMap<String, Ticker> {
+            tickers.into_iter().filter(|t| !is_stale(t, now)).map(|t| (t.inst_id.clone(), t)).collect()
+        };
+        let (spot, swap, future) = (fresh(spot), fresh(swap), fresh(future));
+
+        let mut families: HashMap<&str, Vec<&Symbol>> = HashMap::new();
+        for s in symbols.iter().filter(|s| s.state == State::Live) {
+            families.entry(s.inst_family.as_str()).or_default().push(s);
+        }
+        let mut alerts = Vec::new();
+        for (family, contracts) in families {
+            let Some((spot_ticker, path)) = self.find_spot(family, &spot) else {
+                continue;
+            };
+            let swap_ticker = swap.get(&format!("{}-SWAP", family));
+            let points = contracts
+                .iter()
+                .filter_map(|s| TermPoint::new(s, future.get(&s.inst_id)?, spot_ticker, swap_ticker, &path, now))
+                .collect::<Vec<_>>();
+            if points.is_empty() {
+                continue;
+            }
+            let term = TermStructure::new(family.to_string(), spot_ticker.inst_id.clone(), path, points);
+            let alert = self.check(term, now);
+            if !alert.inversions.is_empty() || !alert.unusual.is_empty() {
+                alerts.push(alert);
+            }
+        }
+
+        if self.update_alerts(&alerts) {
+            alerts.sort_by(|a, b| a.term.inst_family.cmp(&b.term.inst_family));
+            let msg = alert_message(&alerts);
+            if !broadcast(&msg).await {
+                warn!("sent telegram message failed:\n{}", msg);
+            }
+        }
+    }
+
+    /// 记录本次提醒的内容，与上一次不同时返回 true
+    fn update_alerts(&mut self, alerts: &[Alert]) -> bool {
+        let keys: HashSet<String> = alerts.iter().flat_map(|a| a.keys()).collect();
+        if keys == self.alerts {
+            return false;
+        }
+        self.alerts = keys;
+        true
+    }
+
+    async fn fetch_tickers(&self, category: Category) -> Option<Vec<Ticker>> {
+        match self.client.get_ticker(category.clone()).await {
+            Ok(tickers) if !tickers.is_empty() => Some(tickers),
+            Ok(_) => None,
+            Err(e) => {
+                log_okx_error(&format!("fetch {} tickers", category.as_str()), &e);
+                None
+            }
+        }
+    }
+
+    /// 交易品种对应的现货，优先计价币相同的现货，否则按配置的顺序选择可以换算的计价币
+    fn find_spot<'a>(&self, family: &str, spot: &'a HashMap<String, Ticker>) -> Option<(&'a Ticker, QuotePath)> {
+        let future = InstId::from_str(family).ok()?;
+        let quotes = std::iter::once(future.quote.as_str()).chain(self.quotes.config().currencies.iter().map(String::as_str));
+        for quote in quotes {
+            let pair = InstId::spot(&future.base, quote);
+            let Some(ticker) = spot.get(&pair.to_string()) else {
+                continue;
+            };
+            // 用永续的 ID 判断是否为币本位，交割和永续的计价方式相同
+            if let Some(path) = self.quotes.path(&pair, &InstId::swap(&future.base, &future.quote)) {
+                return Some((ticker, path));
+            }
+        }
+        None
+    }
+
+    fn check(&self, term: TermStructure, now: u64) -> Alert {
+        let inversions = term
+            .inversions()
+            .iter()
+            .map(|i| (i.near.map(|n| n.inst_id.clone()).unwrap_or_else(|| "SPOT".to_string()), i.far.inst_id.clone()))
+            .collect();
+        let min_expiry = now + self.config.min_expiry_hours * 3600 * 1000;
+        let max_yield = &self.config.max_yield;
+        let unusual = term
+            .points
+            .iter()
+            .filter(|p| p.exp_time >= min_expiry)
+            .filter(|p| p.carry_annualized > *max_yield || p.reverse_annualized < -max_yield.clone())
+            .map(|p| p.inst_id.clone())
+            .collect();
+        Alert {
+            term,
+            inversions,
+            unusual,
+        }
+    }
+}
+
+fn alert_message(alerts: &[Alert]) -> String {
+    let percent = |v: &BigDecimal| v * BigDecimal::from(100);
+    let mut msg = format!("📐 **交割合约期限结构** 📐\n⏰ {}\n", Local::now().format("%m-%d %H:%M:%S"));
+    for (i, alert) in alerts.iter().enumerate() {
+        let term = &alert.term;
+        msg += &format!("📊 **{}** 现货 `{}` 计价 `{}`\n", term.inst_family, term.spot_inst_id, term.path);
+        for p in term.points.iter() {
+            let alias = p.alias.map(|a| a.as_str()).unwrap_or("-");
+            let expiry = DateTime::from_timestamp_millis(p.exp_time as i64)
+                .map(|t| t.with_timezone(&Local).format("%m-%d").to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let flag = if alert.unusual.contains(&p.inst_id) { "🔥" } else { "▫️" };
+            msg += &format!(
+                "{} `{}` {} 基差 {:.2}% 年化 {:.2}% (买现货 {:.2}% / 卖现货 {:.2}%)",
+                flag,
+                alias,
+                expiry,
+                percent(&p.mid_rate),
+                percent(&p.annualized),
+                percent(&p.carry_annualized),
+                percent(&p.reverse_annualized)
+            );
+            if let Some(swap) = &p.swap_annualized {
+                msg += &format!(" 对永续 {:.2}%", percent(swap));
+            }
+            msg += "\n";
+        }
+        for (near, far) in alert.inversions.iter() {
+            msg += &format!("⚠️ 倒挂: `{}` 低于 `{}`\n", far, near);
+        }
+        if i < alerts.len() - 1 {
+            msg += "➖➖➖➖➖➖➖➖➖➖\n";
+        }
+    }
+    msg += &format!("\n📝 共 {} 个交易品种需要关注", alerts.len());
+    msg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::client::ClientConfig;
+    use crate::model::fixture::{dec, future, index, ticker};
+    use crate::model::symbol::FutureAlias;
+
+    const NOW: u64 = 1_700_000_000_000;
+    const HOUR_MS: u64 = 3600 * 1000;
+
+    fn scanner() -> FuturesBasis {
+        let mut quotes = QuoteConverter::new(QuoteConfig::default());
+        quotes.update_index(&[index("USDT-USD", "1.25"), index("USDC-USD", "1.25")]);
+        FuturesBasis {
+            client: OkxClient::new(ClientConfig::default()).unwrap(),
+            config: FuturesConfig::default(),
+            quotes,
+            alerts: HashSet::new(),
+        }
+    }
+
+    fn spot_map(tickers: &[Ticker]) -> HashMap<String, Ticker> {
+        tickers.iter().map(|t| (t.inst_id.clone(), t.clone())).collect()
+    }
+
+    #[test]
+    fn test_find_spot() {
+        let scanner = scanner();
+        let spot = spot_map(&[ticker("BTC-USDT", "99.9", "100.1"), ticker("ETH-USDC", "9.9", "10.1")]);
+
+        let (ticker, path) = scanner.find_spot("BTC-USDT", &spot).unwrap();
+        assert_eq!(ticker.inst_id, "BTC-USDT");
+        assert!(path.is_direct());
+        assert!(!path.inverse);
+
+        // 币本位没有 BTC-USD 现货，按配置的顺序换算到 USDT
+        let (ticker, path) = scanner.find_spot("BTC-USD", &spot).unwrap();
+        assert_eq!(ticker.inst_id, "BTC-USDT");
+        assert!(path.inverse);
+        assert_eq!(path.rate, dec("0.8"));
+
+        let (ticker, path) = scanner.find_spot("ETH-USDT", &spot).unwrap();
+        assert_eq!(ticker.inst_id, "ETH-USDC");
+        assert_eq!((path.spot_quote.as_str(), path.swap_quote.as_str()), ("USDC", "USDT"));
+
+        assert!(scanner.find_spot("SOL-USDT", &spot).is_none());
+    }
+
+    fn term_structure(contracts: &[(&str, FutureAlias, u64, &str, &str)]) -> TermStructure {
+        let spots = spot_map(&[ticker("BTC-USDT", "99.9", "100.1")]);
+        let scanner = scanner();
+        let (spot, path) = scanner.find_spot("BTC-USDT", &spots).unwrap();
+        let points = contracts
+            .iter()
+            .map(|(inst_id, alias, hours, bid, ask)| {
+                let symbol = future(inst_id, *alias, NOW + hours * HOUR_MS);
+                TermPoint::new(&symbol, &ticker(inst_id, bid, ask), spot, None, &path, NOW).unwrap()
+            })
+            .collect();
+        TermStructure::new("BTC-USDT".to_string(), spot.inst_id.clone(), path, points)
+    }
+
+    #[test]
+    fn test_check_inversions_and_yield() {
+        let scanner = scanner();
+        let term = term_structure(&[
+            // 不足 24 小时交割，年化收益再高也不提醒
+            ("BTC-USDT-W", FutureAlias::ThisWeek, 10, "101", "101.2"),
+            // 30 天 2.9% 的基差，年化超过 20%
+            ("BTC-USDT-Q", FutureAlias::Quarter, 30 * 24, "103", "103.2"),
+            // 低于季度合约
+            ("BTC-USDT-NQ", FutureAlias::NextQuarter, 120 * 24, "102", "102.2"),
+        ]);
+        let alert = scanner.check(term, NOW);
+        assert_eq!(alert.inversions, vec![("BTC-USDT-Q".to_string(), "BTC-USDT-NQ".to_string())]);
+        assert_eq!(alert.unusual, HashSet::from(["BTC-USDT-Q".to_string()]));
+        let keys: HashSet<String> = alert.keys().collect();
+        assert_eq!(keys, HashSet::from(["BTC-USDT-Q>BTC-USDT-NQ".to_string(), "BTC-USDT-Q".to_string()]));
+
+        // 最近的合约低于现货
+        let term = term_structure(&[("BTC-USDT-Q", FutureAlias::Quarter, 30 * 24, "99", "99.2")]);
+        let alert = scanner.check(term, NOW);
+        assert_eq!(alert.inversions, vec![("SPOT".to_string(), "BTC-USDT-Q".to_string())]);
+        assert!(alert.unusual.is_empty());
+    }
+
+    #[test]
+    fn test_alert_dedup() {
+        let inverted = || {
+            let term = term_structure(&[("BTC-USDT-Q", FutureAlias::Quarter, 30 * 24, "99", "99.2")]);
+            scanner().check(term, NOW)
+        };
+        let mut scanner = scanner();
+        assert!(scanner.update_alerts(&[inverted()]));
+        // 内容没有变化时不重复提醒
+        assert!(!scanner.update_alerts(&[inverted()]));
+        // 倒挂消失后提醒一次
+        assert!(scanner.update_alerts(&[]));
+        assert!(!scanner.update_alerts(&[]));
+    }
+}
@@ -0,0 +1,730 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use bigdecimal::rounding::RoundingMode;
+use bigdecimal::{BigDecimal, FromPrimitive, Zero};
+use log::{error, info, warn};
+use serde::Deserialize;
+use tokio::time::Instant;
+
+use crate::action::error::OkxError;
+use crate::action::exchange::Exchange;
+use crate::model::basis::{Basis, Direction};
+use crate::model::order::{CancelRequest, Order, OrderRequest, Side, TdMode, TgtCcy};
+use crate::model::symbol::{ContractType, Symbol};
+use crate::util::log::log_okx_error;
+
+/// 对冲执行的配置，对应 conf/okx.yml 中的 hedge
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HedgeConfig {
+    /// 基差率超过该值时开仓
+    pub open_threshold: BigDecimal,
+    /// 基差率回落到该值以内时平仓
+    pub close_threshold: BigDecimal,
+    /// 每组对冲的名义价值，以计价货币计
+    pub notional: BigDecimal,
+    /// 同时持有的最多组数
+    pub max_pairs: usize,
+    /// 是否做反向套利，需要账户支持借币卖出现货
+    pub allow_reverse: bool,
+    /// 等待订单结束的最长时间，超时后撤单，撤单后再等待同样的时间确认成交数量
+    pub fill_timeout_ms: u64,
+    /// 查询订单状态的间隔
+    pub poll_interval_ms: u64,
+    /// 合约腿未完全成交时的补单次数
+    pub hedge_retries: u32,
+}
+
+impl Default for HedgeConfig {
+    fn default() -> Self {
+        HedgeConfig {
+            open_threshold: BigDecimal::from_f64(0.01).unwrap(),
+            close_threshold: BigDecimal::from_f64(0.002).unwrap(),
+            notional: BigDecimal::from(100),
+            max_pairs: 1,
+            allow_reverse: false,
+            fill_timeout_ms: 5000,
+            poll_interval_ms: 200,
+            hedge_retries: 2,
+        }
+    }
+}
+
+/// 现货的交易模式，卖出现货需要借币
+pub(crate) fn spot_td_mode(direction: Direction) -> TdMode {
+    match direction {
+        Direction::CashAndCarry => TdMode::Cash,
+        Direction::ReverseCarry => TdMode::Cross,
+    }
+}
+
+/// 已开仓的一组对冲
+#[derive(Debug, Clone)]
+pub struct HedgedPair {
+    pub spot_inst_id: String,
+    pub swap_inst_id: String,
+    pub direction: Direction,
+    /// 现货数量，单位为币
+    pub spot_sz: BigDecimal,
+    /// 合约数量，单位为张
+    pub swap_sz: BigDecimal,
+    /// 开仓时的基差率
+    pub open_basis: BigDecimal,
+}
+
+/// 执行结果，用于通知
+#[derive(Debug, Clone)]
+pub enum HedgeEvent {
+    Opened(HedgedPair),
+    /// 平仓完成，close_basis 为触发平仓时的基差率
+    Closed { pair: HedgedPair, close_basis: BigDecimal },
+    /// 合约腿没有成交，已经成交的现货被平掉
+    Unwound { spot_inst_id: String, reason: String },
+    /// 订单成交数量无法确认，该交易对不再开平仓，需要人工检查仓位
+    Suspended { spot_inst_id: String, reason: String },
+}
+
+/// 现货-永续对冲执行
+/// 只支持正向合约：按 ctVal 把币的数量换算成张数，两条腿的币数量相同
+/// 先下现货腿，按实际成交数量对冲合约腿；合约腿补单后仍有多余的现货则平掉多余部分
+pub struct HedgeExecutor<E: Exchange> {
+    exchange: E,
+    config: HedgeConfig,
+    symbols: HashMap<String, Symbol>,
+    /// 按现货 instId
+    pairs: HashMap<String, HedgedPair>,
+    /// 订单状态未知而暂停的现货 instId
+    suspended: HashSet<String>,
+}
+
+impl<E: Exchange> HedgeExecutor<E> {
+    pub fn new(exchange: E, config: HedgeConfig) -> Self {
+        HedgeExecutor {
+            exchange,
+            config,
+            symbols: HashMap::new(),
+            pairs: HashMap::new(),
+            suspended: HashSet::new(),
+        }
+    }
+
+    /// 更新交易规则，下单数量按 lotSz/minSz 取整
+    pub fn set_symbols(&mut self, symbols: impl IntoIterator<Item = Symbol>) {
+        self.symbols.extend(symbols.into_iter().map(|s| (s.inst_id.clone(), s)));
+    }
+
+    pub fn pairs(&self) -> impl Iterator<Item = &HedgedPair> {
+        self.pairs.values()
+    }
+
+    /// 根据最新的盘口基差决定开仓或平仓，开仓和平仓都按要吃掉的一侧计算
+    pub async fn on_basis(&mut self, spot_inst_id: &str, swap_inst_id: &str, basis: &Basis) -> Option<HedgeEvent> {
+        if self.suspended.contains(spot_inst_id) {
+            return None;
+        }
+        if let Some(pair) = self.pairs.get(spot_inst_id) {
+            let rate = basis.close_rate(pair.direction);
+            let converged = match pair.direction {
+                Direction::CashAndCarry => *rate <= self.config.close_threshold,
+                Direction::ReverseCarry => *rate >= -self.config.close_threshold.clone(),
+            };
+            if !converged {
+                return None;
+            }
+            let pair = pair.clone();
+            return self.close(pair, &rate.clone()).await;
+        }
+
+        if self.pairs.len() >= self.config.max_pairs {
+            return None;
+        }
+        let (direction, rate) = basis.opportunity(&self.config.open_threshold)?;
+        if direction == Direction::ReverseCarry && !self.config.allow_reverse {
+            return None;
+        }
+        let rate = rate.clone();
+        self.open(spot_inst_id, swap_inst_id, basis.spot_px(direction), &rate, direction).await
+    }
+
+    async fn open(
+        &mut self,
+        spot_inst_id: &str,
+        swap_inst_id: &str,
+        spot_px: &BigDecimal,
+        diff_rate: &BigDecimal,
+        direction: Direction,
+    ) -> Option<HedgeEvent> {
+        let (Some(spot), Some(swap)) = (self.symbols.get(spot_inst_id), self.symbols.get(swap_inst_id)) else {
+            warn!("skip hedge of {}: trading rules of {} or {} not loaded", spot_inst_id, spot_inst_id, swap_inst_id);
+            return None;
+        };
+        let Some(ct_val) = linear_ct_val(spot, swap) else {
+            warn!("skip hedge of {}: {} is not a linear contract of {}", spot_inst_id, swap_inst_id, spot.base);
+            return None;
+        };
+        let (spot, swap) = (spot.clone(), swap.clone());
+        if spot_px.is_zero() {
+            return None;
+        }
+
+        // 先按合约取整，保证现货数量能被完整对冲
+        let contracts = round_down(&(&self.config.notional / spot_px / &ct_val), &swap.lot_sz);
+        let spot_sz = round_down(&(&contracts * &ct_val), &spot.lot_sz);
+        if contracts < swap.min_sz || spot_sz < spot.min_sz {
+            info!(
+                "skip hedge of {}: notional {} is too small, contracts={}, spot size={}",
+                spot_inst_id, self.config.notional, contracts, spot_sz
+            );
+            return None;
+        }
+
+        info!(
+            "open {:?} {}/{}: basis={}, spot size={}, contracts={}",
+            direction, spot_inst_id, swap_inst_id, diff_rate, spot_sz, contracts
+        );
+        let spot_side = direction.spot_side();
+        let open = spot_order(&spot, spot_side, spot_td_mode(direction), spot_sz);
+        let spot_filled = match self.execute(&spot, &open).await {
+            Ok(order) => net_base_filled(&order, &spot.base),
+            Err(e @ OkxError::OrderUnknown { .. }) => return Some(self.suspend(spot_inst_id, e.to_string())),
+            Err(e) => {
+                log_okx_error(&format!("open spot leg of {}", spot_inst_id), &e);
+                return None;
+            }
+        };
+        if spot_filled.is_zero() {
+            warn!("spot leg of {} was not filled", spot_inst_id);
+            return None;
+        }
+
+        // 按现货实际成交数量对冲，部分成交时补单
+        let target = round_down(&(&spot_filled / &ct_val), &swap.lot_sz);
+        let swap_filled = match self.fill(&swap, spot_side.opposite(), &target, false).await {
+            Ok(filled) => filled,
+            // 合约成交数量未知时不能按未对冲平掉现货
+            Err(e) => return Some(self.suspend(spot_inst_id, format!("spot filled {}, {}", spot_filled, e))),
+        };
+        let hedged = &swap_filled * &ct_val;
+
+        // 多余的现货平掉，剩余不足最小下单数量时保留
+        let excess = round_down(&(&spot_filled - &hedged), &spot.lot_sz);
+        let mut spot_left = spot_filled.clone();
+        if excess >= spot.min_sz {
+            let unwind = spot_order(&spot, spot_side.opposite(), spot_td_mode(direction), excess);
+            match self.execute(&spot, &unwind).await {
+                Ok(order) => spot_left -= order.acc_fill_sz,
+                Err(e @ OkxError::OrderUnknown { .. }) => return Some(self.suspend(spot_inst_id, e.to_string())),
+                Err(e) => log_okx_error(&format!("unwind spot leg of {}", spot_inst_id), &e),
+            }
+        }
+
+        if swap_filled.is_zero() {
+            error!("hedge of {} failed, spot left after unwind: {}", spot_inst_id, spot_left);
+            return Some(HedgeEvent::Unwound {
+                spot_inst_id: spot_inst_id.to_string(),
+                reason: format!("swap leg {} was not filled", swap_inst_id),
+            });
+        }
+        let pair = HedgedPair {
+            spot_inst_id: spot_inst_id.to_string(),
+            swap_inst_id: swap_inst_id.to_string(),
+            direction,
+            spot_sz: spot_left,
+            swap_sz: swap_filled,
+            open_basis: diff_rate.clone(),
+        };
+        self.pairs.insert(spot_inst_id.to_string(), pair.clone());
+        Some(HedgeEvent::Opened(pair))
+    }
+
+    /// 平仓，先平合约再平现货，现货只卖出已平合约对应的数量
+    /// 未能全部平掉时保留剩余数量，下一次基差收敛时继续平仓；合约补单后仍未平完时暂停交易对
+    async fn close(&mut self, mut pair: HedgedPair, diff_rate: &BigDecimal) -> Option<HedgeEvent> {
+        let (Some(spot), Some(swap)) = (
+            self.symbols.get(&pair.spot_inst_id).cloned(),
+            self.symbols.get(&pair.swap_inst_id).cloned(),
+        ) else {
+            return None;
+        };
+        let ct_val = linear_ct_val(&spot, &swap)?;
+        info!("close {:?} {}/{}: basis={}", pair.direction, pair.spot_inst_id, pair.swap_inst_id, diff_rate);
+
+        let spot_side = pair.direction.spot_side();
+        let requested = pair.swap_sz.clone();
+        let mut closed = BigDecimal::zero();
+        if !requested.is_zero() {
+            match self.fill(&swap, spot_side, &requested, true).await {
+                Ok(filled) => closed = filled,
+                Err(e) => return Some(self.suspend(&pair.spot_inst_id, e.to_string())),
+            }
+            pair.swap_sz -= &closed;
+        }
+        // 合约已经平完时卖出全部现货，否则只卖出已平合约对应的现货，避免合约失去对冲
+        let spot_sz = if pair.swap_sz.is_zero() {
+            pair.spot_sz.clone()
+        } else {
+            (&closed * &ct_val).min(pair.spot_sz.clone())
+        };
+        let spot_sz = round_down(&spot_sz, &spot.lot_sz);
+        if spot_sz >= spot.min_sz {
+            let order = spot_order(&spot, spot_side.opposite(), spot_td_mode(pair.direction), spot_sz);
+            match self.execute(&spot, &order).await {
+                Ok(order) => pair.spot_sz -= order.acc_fill_sz,
+                Err(e @ OkxError::OrderUnknown { .. }) => return Some(self.suspend(&pair.spot_inst_id, e.to_string())),
+                Err(e) => log_okx_error(&format!("close spot leg of {}", pair.spot_inst_id), &e),
+            }
+        }
+
+        if closed < requested {
+            let reason = format!(
+                "closed {} of {} contracts, spot left={}, contracts left={}",
+                closed, requested, pair.spot_sz, pair.swap_sz
+            );
+            self.pairs.insert(pair.spot_inst_id.clone(), pair.clone());
+            return Some(self.suspend(&pair.spot_inst_id, reason));
+        }
+        // 不足最小下单数量的现货无法卖出，视为已平仓
+        if pair.swap_sz.is_zero() && round_down(&pair.spot_sz, &spot.lot_sz) < spot.min_sz {
+            // 返回平仓前的数量
+            let pair = self.pairs.remove(&pair.spot_inst_id).unwrap_or(pair);
+            return Some(HedgeEvent::Closed {
+                pair,
+                close_basis: diff_rate.clone(),
+            });
+        }
+        warn!(
+            "close of {} incomplete, spot left={}, contracts left={}",
+            pair.spot_inst_id, pair.spot_sz, pair.swap_sz
+        );
+        self.pairs.insert(pair.spot_inst_id.clone(), pair);
+        None
+    }
+
+    /// 以市价单成交 sz 张合约，部分成交时按配置补单，返回累计成交数量
+    /// 订单状态未知时不再补单，直接返回错误，避免重复对冲
+    async fn fill(&self, swap: &Symbol, side: Side, sz: &BigDecimal, reduce_only: bool) -> Result<BigDecimal, OkxError> {
+        let mut filled = BigDecimal::zero();
+        for attempt in 0..=self.config.hedge_retries {
+            let left = sz - &filled;
+            if left < swap.min_sz {
+                break;
+            }
+            let order = OrderRequest {
+                reduce_only: reduce_only.then_some(true),
+                ..OrderRequest::market(&swap.inst_id, TdMode::Cross, side, left)
+            };
+            match self.execute(swap, &order).await {
+                Ok(order) => filled += order.acc_fill_sz,
+                Err(e @ OkxError::OrderUnknown { .. }) => return Err(e),
+                Err(e) => log_okx_error(&format!("swap leg of {} (attempt {})", swap.inst_id, attempt + 1), &e),
+            }
+        }
+        Ok(filled)
+    }
+
+    /// 暂停交易对，之后的行情不再触发开平仓
+    fn suspend(&mut self, spot_inst_id: &str, reason: String) -> HedgeEvent {
+        error!("hedge of {} suspended, check the position manually: {}", spot_inst_id, reason);
+        self.suspended.insert(spot_inst_id.to_string());
+        HedgeEvent::Suspended {
+            spot_inst_id: spot_inst_id.to_string(),
+            reason,
+        }
+    }
+
+    /// 下单并等待订单结束，超时后撤单并继续查询
+    /// 下单被拒绝时返回错误，下单结果不确定或撤单后仍查不到结束状态时返回 OrderUnknown
+    async fn execute(&self, symbol: &Symbol, order: &OrderRequest) -> Result<Order, OkxError> {
+        let ack = match self.exchange.place_order(symbol, order).await {
+            Ok(ack) => ack,
+            // 请求可能已经被交易所接受，不能当作没有下单
+            Err(e) if e.is_ambiguous() => {
+                log_okx_error(&format!("place order of {}", order.inst_id), &e);
+                return Err(OkxError::OrderUnknown {
+                    inst_id: order.inst_id.clone(),
+                    ord_id: String::new(),
+                });
+            }
+            Err(e) => return Err(e),
+        };
+        let timeout = Duration::from_millis(self.config.fill_timeout_ms);
+        let interval = Duration::from_millis(self.config.poll_interval_ms);
+        let start = Instant::now();
+        let mut canceled: Option<Instant> = None;
+        loop {
+            match self.exchange.get_order(&order.inst_id, &ack.ord_id).await {
+                Ok(Some(o)) if o.state.is_final() => return Ok(o),
+                Ok(_) => {}
+                Err(e) => log_okx_error(&format!("query order {} of {}", ack.ord_id, order.inst_id), &e),
+            }
+            match canceled {
+                Some(at) if at.elapsed() >= timeout => break,
+                Some(_) => {}
+                None if start.elapsed() >= timeout => {
+                    let cancel = CancelRequest::new(&order.inst_id, &ack.ord_id);
+                    if let Err(e) = self.exchange.cancel_order(&cancel).await {
+                        log_okx_error(&format!("cancel order {} of {}", ack.ord_id, order.inst_id), &e);
+                    }
+                    canceled = Some(Instant::now());
+                }
+                None => {}
+            }
+            tokio::time::sleep(interval).await;
+        }
+        warn!("order {} of {} did not finish in time", ack.ord_id, order.inst_id);
+        // 撤单后仍未结束的订单还可能继续成交，不能按已查到的成交数量处理
+        Err(OkxError::OrderUnknown {
+            inst_id: order.inst_id.clone(),
+            ord_id: ack.ord_id,
+        })
+    }
+}
+
+/// 合约为同一币种的正向合约时返回每张的币数量
+fn linear_ct_val(spot: &Symbol, swap: &Symbol) -> Option<BigDecimal> {
+    if swap.ct_type == Some(ContractType::Inverse) || swap.base != spot.base {
+        return None;
+    }
+    swap.contract_value().filter(|v| !v.is_zero())
+}
+
+/// 按步长向下取整
+fn round_down(v: &BigDecimal, step: &BigDecimal) -> BigDecimal {
+    if step.is_zero() {
+        return v.clone();
+    }
+    (v / step).with_scale_round(0, RoundingMode::Down) * step
+}
+
+/// 现货市价单，数量以交易货币计
+fn spot_order(spot: &Symbol, side: Side, td_mode: TdMode, sz: BigDecimal) -> OrderRequest {
+    OrderRequest {
+        tgt_ccy: Some(TgtCcy::BaseCcy),
+        ..OrderRequest::market(&spot.inst_id, td_mode, side, sz)
+    }
+}
+
+/// 实际得到的交易货币数量，买入时手续费以交易货币扣除
+fn net_base_filled(order: &Order, base: &str) -> BigDecimal {
+    match (&order.fee, &order.fee_ccy) {
+        (Some(fee), Some(ccy)) if ccy == base && order.side == Side::Buy => &order.acc_fill_sz + fee,
+        _ => order.acc_fill_sz.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::{self, dec};
+    use crate::action::error::ApiCode;
+    use crate::model::order::{OrderAck, OrderState};
+    use crate::model::symbol::Category;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    /// 市价单立即结束，按脚本中的比例成交，None 表示下单被拒绝
+    #[derive(Default)]
+    struct MockExchange {
+        fills: Mutex<HashMap<String, VecDeque<Option<BigDecimal>>>>,
+        /// 现货买单以 BTC 扣除的手续费率
+        spot_fee_rate: BigDecimal,
+        orders: Mutex<Vec<Order>>,
+        requests: Mutex<Vec<OrderRequest>>,
+        /// 这些产品的订单成交后查询不到
+        hidden: HashSet<String>,
+        /// 这些产品下单超时，订单是否被接受未知
+        timed_out: HashSet<String>,
+    }
+
+    impl MockExchange {
+        fn script(self, inst_id: &str, fills: &[Option<&str>]) -> Self {
+            let fills = fills.iter().map(|f| f.map(dec)).collect();
+            self.fills.lock().unwrap().insert(inst_id.to_string(), fills);
+            self
+        }
+
+        fn hide(mut self, inst_id: &str) -> Self {
+            self.hidden.insert(inst_id.to_string());
+            self
+        }
+
+        fn time_out(mut self, inst_id: &str) -> Self {
+            self.timed_out.insert(inst_id.to_string());
+            self
+        }
+
+        fn orders(&self) -> Vec<Order> {
+            self.orders.lock().unwrap().clone()
+        }
+    }
+
+    impl Exchange for MockExchange {
+        async fn place_order(&self, symbol: &Symbol, order: &OrderRequest) -> Result<OrderAck, OkxError> {
+            self.requests.lock().unwrap().push(order.clone());
+            if self.timed_out.contains(&order.inst_id) {
+                return Err(OkxError::Api {
+                    code: ApiCode::Timeout,
+                    msg: "API endpoint request timeout".to_string(),
+                });
+            }
+            let ratio = match self.fills.lock().unwrap().get_mut(&order.inst_id).and_then(|f| f.pop_front()) {
+                Some(Some(ratio)) => ratio,
+                Some(None) => {
+                    return Err(OkxError::Api {
+                        code: ApiCode::Other("51008".to_string()),
+                        msg: "Insufficient balance".to_string(),
+                    });
+                }
+                None => dec("1"),
+            };
+            let filled = &order.sz * ratio;
+            let fee = (symbol.category == Category::Spot && order.side == Side::Buy)
+                .then(|| -(&filled * &self.spot_fee_rate));
+            let mut orders = self.orders.lock().unwrap();
+            let ord_id = orders.len().to_string();
+            orders.push(Order {
+                ord_id: ord_id.clone(),
+                cl_ord_id: order.cl_ord_id.clone(),
+                inst_id: order.inst_id.clone(),
+                category: symbol.category.clone(),
+                side: order.side,
+                pos_side: order.pos_side,
+                td_mode: order.td_mode,
+                ord_type: order.ord_type,
+                px: order.px.clone(),
+                sz: order.sz.clone(),
+                state: if filled == order.sz { OrderState::Filled } else { OrderState::Canceled },
+                acc_fill_sz: filled,
+                avg_px: None,
+                fill_px: None,
+                fill_sz: None,
+                trade_id: None,
+                fee_ccy: fee.as_ref().map(|_| "BTC".to_string()),
+                fee,
+                c_time: 0,
+                u_time: 0,
+            });
+            Ok(OrderAck {
+                ord_id,
+                cl_ord_id: None,
+                req_id: None,
+                s_code: "0".to_string(),
+                s_msg: String::new(),
+                ts: 0,
+            })
+        }
+
+        async fn cancel_order(&self, cancel: &CancelRequest) -> Result<OrderAck, OkxError> {
+            Ok(OrderAck {
+                ord_id: cancel.ord_id.clone().unwrap_or_default(),
+                cl_ord_id: None,
+                req_id: None,
+                s_code: "0".to_string(),
+                s_msg: String::new(),
+                ts: 0,
+            })
+        }
+
+        async fn get_order(&self, inst_id: &str, ord_id: &str) -> Result<Option<Order>, OkxError> {
+            if self.hidden.contains(inst_id) {
+                return Ok(None);
+            }
+            Ok(self.orders.lock().unwrap().iter().find(|o| o.ord_id == ord_id).cloned())
+        }
+    }
+
+    fn new_executor(exchange: MockExchange, config: HedgeConfig) -> HedgeExecutor<MockExchange> {
+        let mut executor = HedgeExecutor::new(
+            exchange,
+            HedgeConfig {
+                notional: dec("1000"),
+                poll_interval_ms: 1,
+                ..config
+            },
+        );
+        executor.set_symbols([
+            fixture::spot("BTC-USDT", "0.0001"),
+            fixture::swap("BTC-USDT-SWAP", "1", "0.1"),
+        ]);
+        executor
+    }
+
+    /// 买卖价相同的盘口
+    fn basis(spot: &str, swap: &str) -> Basis {
+        Basis::new(dec(spot), dec(spot), dec(swap), dec(swap)).unwrap()
+    }
+
+    fn summary(orders: &[Order]) -> Vec<(String, Side, BigDecimal)> {
+        orders.iter().map(|o| (o.inst_id.clone(), o.side, o.sz.normalized())).collect()
+    }
+
+    #[tokio::test]
+    async fn test_open_and_close_cash_and_carry() {
+        let exchange = MockExchange {
+            spot_fee_rate: dec("0.001"),
+            ..Default::default()
+        };
+        let mut executor = new_executor(exchange, HedgeConfig::default());
+
+        // 1000 USDT / 100 = 10 BTC = 100 张，手续费扣除 0.01 BTC 后只能对冲 99 张，多余的 0.09 BTC 卖出
+        let event = executor.on_basis("BTC-USDT", "BTC-USDT-SWAP", &basis("100", "102")).await;
+        let Some(HedgeEvent::Opened(pair)) = event else {
+            panic!("unexpected event: {:?}", event);
+        };
+        assert_eq!(pair.direction, Direction::CashAndCarry);
+        assert_eq!(pair.swap_sz, dec("99"));
+        assert_eq!(pair.spot_sz, dec("9.9"));
+        assert_eq!(
+            summary(&executor.exchange.orders()),
+            vec![
+                ("BTC-USDT".to_string(), Side::Buy, dec("10")),
+                ("BTC-USDT-SWAP".to_string(), Side::Sell, dec("99")),
+                ("BTC-USDT".to_string(), Side::Sell, dec("0.09")),
+            ]
+        );
+        assert_eq!(executor.exchange.orders()[0].td_mode, TdMode::Cash);
+
+        // 基差还没有收敛
+        assert!(executor.on_basis("BTC-USDT", "BTC-USDT-SWAP", &basis("100", "100.5")).await.is_none());
+        assert_eq!(executor.exchange.orders().len(), 3);
+
+        let event = executor.on_basis("BTC-USDT", "BTC-USDT-SWAP", &basis("100", "100.1")).await;
+        assert!(matches!(event, Some(HedgeEvent::Closed { .. })), "unexpected event: {:?}", event);
+        let orders = executor.exchange.orders();
+        assert_eq!(
+            summary(&orders[3..]),
+            vec![
+                ("BTC-USDT-SWAP".to_string(), Side::Buy, dec("99")),
+                ("BTC-USDT".to_string(), Side::Sell, dec("9.9")),
+            ]
+        );
+        let requests = executor.exchange.requests.lock().unwrap().clone();
+        assert_eq!(requests[3].reduce_only, Some(true));
+        assert_eq!(executor.pairs().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_partial_swap_fill_is_topped_up() {
+        let exchange = MockExchange::default().script("BTC-USDT-SWAP", &[Some("0.5"), None, Some("1")]);
+        let mut executor = new_executor(exchange, HedgeConfig::default());
+
+        let event = executor.on_basis("BTC-USDT", "BTC-USDT-SWAP", &basis("100", "102")).await;
+        let Some(HedgeEvent::Opened(pair)) = event else {
+            panic!("unexpected event: {:?}", event);
+        };
+        assert_eq!(pair.swap_sz, dec("100"));
+        assert_eq!(pair.spot_sz, dec("10"));
+        assert_eq!(
+            summary(&executor.exchange.orders()),
+            vec![
+                ("BTC-USDT".to_string(), Side::Buy, dec("10")),
+                ("BTC-USDT-SWAP".to_string(), Side::Sell, dec("100")),
+                ("BTC-USDT-SWAP".to_string(), Side::Sell, dec("50")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_failed_swap_leg_unwinds_spot() {
+        let exchange = MockExchange::default().script("BTC-USDT-SWAP", &[None, None, None]);
+        let mut executor = new_executor(exchange, HedgeConfig::default());
+
+        let event = executor.on_basis("BTC-USDT", "BTC-USDT-SWAP", &basis("100", "102")).await;
+        assert!(matches!(event, Some(HedgeEvent::Unwound { .. })), "unexpected event: {:?}", event);
+        assert_eq!(
+            summary(&executor.exchange.orders()),
+            vec![
+                ("BTC-USDT".to_string(), Side::Buy, dec("10")),
+                ("BTC-USDT".to_string(), Side::Sell, dec("10")),
+            ]
+        );
+        assert_eq!(executor.pairs().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_reverse_carry_requires_config() {
+        let mut executor = new_executor(MockExchange::default(), HedgeConfig::default());
+        assert!(executor.on_basis("BTC-USDT", "BTC-USDT-SWAP", &basis("100", "98")).await.is_none());
+        assert!(executor.exchange.orders().is_empty());
+
+        let config = HedgeConfig {
+            allow_reverse: true,
+            ..Default::default()
+        };
+        let mut executor = new_executor(MockExchange::default(), config);
+        let event = executor.on_basis("BTC-USDT", "BTC-USDT-SWAP", &basis("100", "98")).await;
+        assert!(matches!(event, Some(HedgeEvent::Opened(_))), "unexpected event: {:?}", event);
+        let orders = executor.exchange.orders();
+        assert_eq!(
+            summary(&orders),
+            vec![
+                ("BTC-USDT".to_string(), Side::Sell, dec("10")),
+                ("BTC-USDT-SWAP".to_string(), Side::Buy, dec("100")),
+            ]
+        );
+        assert_eq!(orders[0].td_mode, TdMode::Cross);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_fill_suspends_pair() {
+        let config = HedgeConfig {
+            fill_timeout_ms: 5,
+            ..Default::default()
+        };
+        // 现货成交后查询不到，不能当作没有成交
+        let mut executor = new_executor(MockExchange::default().hide("BTC-USDT"), config.clone());
+        let event = executor.on_basis("BTC-USDT", "BTC-USDT-SWAP", &basis("100", "102")).await;
+        assert!(matches!(event, Some(HedgeEvent::Suspended { .. })), "unexpected event: {:?}", event);
+        assert_eq!(summary(&executor.exchange.orders()), vec![("BTC-USDT".to_string(), Side::Buy, dec("10"))]);
+        assert!(executor.on_basis("BTC-USDT", "BTC-USDT-SWAP", &basis("100", "102")).await.is_none());
+        assert_eq!(executor.exchange.orders().len(), 1);
+
+        // 合约成交数量未知时不补单，也不平掉现货
+        let mut executor = new_executor(MockExchange::default().hide("BTC-USDT-SWAP"), config);
+        let event = executor.on_basis("BTC-USDT", "BTC-USDT-SWAP", &basis("100", "102")).await;
+        assert!(matches!(event, Some(HedgeEvent::Suspended { .. })), "unexpected event: {:?}", event);
+        assert_eq!(
+            summary(&executor.exchange.orders()),
+            vec![
+                ("BTC-USDT".to_string(), Side::Buy, dec("10")),
+                ("BTC-USDT-SWAP".to_string(), Side::Sell, dec("100")),
+            ]
+        );
+        assert_eq!(executor.pairs().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_ambiguous_place_suspends_pair() {
+        // 合约下单超时，不能重新下单，也不能平掉现货
+        let mut executor = new_executor(MockExchange::default().time_out("BTC-USDT-SWAP"), HedgeConfig::default());
+        let event = executor.on_basis("BTC-USDT", "BTC-USDT-SWAP", &basis("100", "102")).await;
+        assert!(matches!(event, Some(HedgeEvent::Suspended { .. })), "unexpected event: {:?}", event);
+        assert_eq!(summary(&executor.exchange.orders()), vec![("BTC-USDT".to_string(), Side::Buy, dec("10"))]);
+        assert_eq!(executor.exchange.requests.lock().unwrap().len(), 2);
+        assert_eq!(executor.pairs().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_partial_close_keeps_hedged_spot() {
+        let exchange = MockExchange::default().script("BTC-USDT-SWAP", &[Some("1"), Some("0.5"), None, None]);
+        let mut executor = new_executor(exchange, HedgeConfig::default());
+        let event = executor.on_basis("BTC-USDT", "BTC-USDT-SWAP", &basis("100", "102")).await;
+        assert!(matches!(event, Some(HedgeEvent::Opened(_))), "unexpected event: {:?}", event);
+
+        // 100 张只平掉一半，补单都被拒绝，现货只卖出对应的一半
+        let event = executor.on_basis("BTC-USDT", "BTC-USDT-SWAP", &basis("100", "100.1")).await;
+        assert!(matches!(event, Some(HedgeEvent::Suspended { .. })), "unexpected event: {:?}", event);
+        assert_eq!(
+            summary(&executor.exchange.orders()[2..]),
+            vec![
+                ("BTC-USDT-SWAP".to_string(), Side::Buy, dec("100")),
+                ("BTC-USDT".to_string(), Side::Sell, dec("5")),
+            ]
+        );
+        let pair = executor.pairs().next().unwrap();
+        assert_eq!(pair.swap_sz, dec("50"));
+        assert_eq!(pair.spot_sz, dec("5"));
+        assert!(executor.on_basis("BTC-USDT", "BTC-USDT-SWAP", &basis("100", "100.1")).await.is_none());
+    }
+}
@@ -1,11 +1,14 @@
 use crate::action;
+use crate::action::client::OkxClient;
+use crate::action::retry::wait_for_data;
 use crate::model::symbol::{Category, Symbol};
+use crate::util::log::log_okx_error;
 
 use chrono::Local;
 use log::{warn, info};
 
-pub async fn list() {
-    let mut spot_swap_list = List::new().await;
+pub async fn list(client: OkxClient) {
+    let mut spot_swap_list = List::new(client).await;
     info!("listing symbols started");
     loop {
         spot_swap_list.run().await;
@@ -14,25 +17,17 @@ pub async fn list() {
 }
 
 struct List {
+    client: OkxClient,
     pub spot: Vec<Symbol>,
     pub swap: Vec<Symbol>,
 }
 
 impl List {
-    async fn new() -> Self {
-        let spot = match action::instrument::get_symbols(Category::Spot).await {
-            Some(symbols) => symbols,
-            None => vec![],        
-        };
-        // 初始化阶段使用assert确保获取到数据
-        assert!(!spot.is_empty(), "Failed to fetch spot symbols");
-        let swap = match action::instrument::get_symbols(Category::Swap).await {
-            Some(symbols) => symbols,
-            None => vec![],        
-        };
-        // 初始化阶段使用assert确保获取到数据
-        assert!(!swap.is_empty(), "Failed to fetch swap symbols");
-        List { spot, swap }
+    async fn new(client: OkxClient) -> Self {
+        // 初始化阶段必须获取到数据，失败时等待后重试
+        let spot = wait_for_data("fetch spot symbols", || client.get_symbols(Category::Spot)).await;
+        let swap = wait_for_data("fetch swap symbols", || client.get_symbols(Category::Swap)).await;
+        List { client, spot, swap }
     }
 
 
@@ -41,16 +36,19 @@ impl List {
     // 所以这里先只看现货和期货 价差
     async fn run(&mut self) {
         // 获取现货交易对列表
-        let spot_symbols = match action::instrument::get_symbols(Category::Spot).await{
-            Some(symbols) => symbols,
-            None => vec![],
+        let spot_symbols = match self.client.get_symbols(Category::Spot).await {
+            Ok(symbols) => symbols,
+            Err(e) => {
+                log_okx_error("read spot symbols", &e);
+                return;
+            }
         };
         if spot_symbols.is_empty() {
             warn!("read spot symbols failed");
             return;
         }
         let new_spot_symbols = new_symbols(&self.spot, &spot_symbols);
-        if new_spot_symbols.len() > 0 {
+        if !new_spot_symbols.is_empty() {
             let mut msg = format!("🚀 **OKX新增现货交易对** 🚀\n⏰ {}\n\n", Local::now().format("%m-%d %H:%M:%S"));
             
             for (i, sym) in new_spot_symbols.iter().enumerate() {
@@ -75,16 +73,19 @@ impl List {
             }
         }
         self.spot = spot_symbols;
-        let swap_symbols = match action::instrument::get_symbols(Category::Swap).await{
-            Some(symbols) => symbols,
-            None => vec![],
+        let swap_symbols = match self.client.get_symbols(Category::Swap).await {
+            Ok(symbols) => symbols,
+            Err(e) => {
+                log_okx_error("read swap symbols", &e);
+                return;
+            }
         };
         if swap_symbols.is_empty() {
             warn!("read swap symbols failed");
             return;
         }
         let new_swap_symbols = new_symbols(&self.swap, &swap_symbols);
-        if new_swap_symbols.len() > 0 {
+        if !new_swap_symbols.is_empty() {
             let mut msg = format!("⚡ **OKX新增永续交易对** ⚡\n⏰ {}\n\n", Local::now().format("%m-%d %H:%M:%S"));
             
             for (i, sym) in new_swap_symbols.iter().enumerate() {
@@ -113,33 +114,31 @@ impl List {
 }
 
 /// 找出secound中新增的交易对
-pub fn new_symbols(first: &Vec<Symbol>, second: &Vec<Symbol>) -> Vec<Symbol> {
+pub fn new_symbols(first: &[Symbol], second: &[Symbol]) -> Vec<Symbol> {
     let mut removed: Vec<Symbol> = Vec::new();
     let mut symbol_set = std::collections::HashSet::new();
     for sym in first {
-        symbol_set.insert(format!("{}-{}", sym.base, sym.quote));
+        symbol_set.insert(sym.pair_id());
     }
     for sym in second {
-        let key = format!("{}-{}", sym.base, sym.quote);
-        if !symbol_set.contains(&key) {
+        if !symbol_set.contains(&sym.pair_id()) {
             removed.push(sym.clone());
         }
     }
-    return removed;
+    removed
 }
 
 // 找出 first 和 second 中共有的交易对
-pub fn common_symbols(first: &Vec<Symbol>, second: &Vec<Symbol>) -> Vec<Symbol> {
+pub fn common_symbols(first: &[Symbol], second: &[Symbol]) -> Vec<Symbol> {
     let mut common = Vec::new();
     let mut symbol_set = std::collections::HashSet::new();
     for sym in first {
-        symbol_set.insert(format!("{}-{}", sym.base, sym.quote));
+        symbol_set.insert(sym.pair_id());
     }
     for sym in second {
-        let key = format!("{}-{}", sym.base, sym.quote);
-        if symbol_set.contains(&key) {
+        if symbol_set.contains(&sym.pair_id()) {
             common.push(sym.clone());
         }
     }
-    return common;
+    common
 }
\ No newline at end of file
@@ -1,2 +1,6 @@
+pub mod calendar;
+pub mod futures;
+pub mod hedge;
 pub mod list;
-pub mod spot_swap;
\ No newline at end of file
+pub mod spot_swap;
+pub mod triangle;
\ No newline at end of file
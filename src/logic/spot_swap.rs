@@ -1,13 +1,58 @@
 use std::collections::HashMap;
-use log::{info, warn};
-use bigdecimal::{BigDecimal, FromPrimitive};
-use chrono::Local;
+use log::{debug, info, warn};
+use bigdecimal::{BigDecimal, FromPrimitive, Zero};
+use chrono::{Local, Utc};
+use futures_util::future::join_all;
 
-use crate::model::symbol::{Category, Ticker};
-use crate::action::{ticker::get_ticker, telegram::broadcast};
+use crate::model::basis::{depth_basis, Basis, DepthBasis, Direction};
+use crate::model::funding::{annualized_carry, FundingRate};
+use crate::model::inst_id::InstId;
+use crate::model::order_book::OrderBook;
+use crate::model::order::TdMode;
+use crate::model::quote::{QuoteConfig, QuoteConverter, QuotePath};
+use crate::model::symbol::{Category, Symbol, Ticker};
+use crate::action::{client::OkxClient, exchange::Exchange, retry::wait_for_data, sim::{SimConfig, SimExchange}, telegram::broadcast};
+use crate::logic::hedge::{spot_td_mode, HedgeConfig, HedgeEvent, HedgeExecutor};
+use crate::util::log::log_okx_error;
 
-pub async fn spot_swap_arbitrage() {
-    let mut spot_swap = SpotSwap::new().await;
+/// hedge 不为 None 时自动开平对冲仓位，否则只发送提醒
+/// paper 不为 None 时在模拟盘上执行，用最新的 ticker 撮合；否则在 OKX 上执行，需要配置 API Key
+/// quotes 为 None 时按默认配置在 USD、USDT、USDC 之间换算比较
+pub async fn spot_swap_arbitrage(
+    client: OkxClient,
+    hedge: Option<HedgeConfig>,
+    paper: Option<SimConfig>,
+    quotes: Option<QuoteConfig>,
+) {
+    let quotes = QuoteConverter::new(quotes.unwrap_or_default());
+    match (hedge, paper) {
+        (Some(hedge), Some(paper)) => {
+            let sim = SimExchange::new(paper);
+            let executor = new_executor(&client, sim.clone(), hedge).await;
+            info!("hedge execution enabled on paper trading");
+            run_forever(SpotSwap::new(client, Some(executor), Some(sim), quotes).await).await;
+        }
+        (Some(_), None) if client.config().credentials.is_none() => {
+            warn!("hedge execution disabled: api key is not configured");
+            run_forever(SpotSwap::<OkxClient>::new(client, None, None, quotes).await).await;
+        }
+        (Some(hedge), None) => {
+            let executor = new_executor(&client, client.clone(), hedge).await;
+            info!("hedge execution enabled");
+            run_forever(SpotSwap::new(client, Some(executor), None, quotes).await).await;
+        }
+        (None, _) => run_forever(SpotSwap::<OkxClient>::new(client, None, None, quotes).await).await,
+    }
+}
+
+async fn new_executor<E: Exchange>(client: &OkxClient, exchange: E, config: HedgeConfig) -> HedgeExecutor<E> {
+    let mut executor = HedgeExecutor::new(exchange, config);
+    executor.set_symbols(wait_for_data("fetch spot symbols", || client.get_symbols(Category::Spot)).await);
+    executor.set_symbols(wait_for_data("fetch swap symbols", || client.get_symbols(Category::Swap)).await);
+    executor
+}
+
+async fn run_forever<E: Exchange>(mut spot_swap: SpotSwap<E>) {
     info!("spot-swap arbitrage started");
     loop {
         spot_swap.run().await;
@@ -15,102 +60,273 @@ pub async fn spot_swap_arbitrage() {
     }
 }
 
-struct SpotSwap {
+struct SpotSwap<E: Exchange> {
+    client: OkxClient,
     pub spot:Vec<Ticker>,
     pub swap:Vec<Ticker>,
     pub diffs:Vec<Diff>,
+    hedge: Option<HedgeExecutor<E>>,
+    /// 模拟盘，每次获取行情后更新
+    paper: Option<SimExchange>,
+    /// 不同计价币之间的换算
+    quotes: QuoteConverter,
+    /// 永续合约的交易规则，按深度计算数量时把张数换算为币
+    swap_symbols: HashMap<String, Symbol>,
 }
 
+/// 超过该时间没有更新的 ticker 不参与计算
+pub(crate) const MAX_TICKER_AGE_MS: u64 = 30_000;
+/// 计算年化收益时假设基差收敛需要的天数
+const HOLD_DAYS: u32 = 7;
+/// 获取深度的档数
+const BOOK_DEPTH: u32 = 50;
+/// 按深度计算数量时，成交均价扣除两条腿的吃单手续费后至少要有的基差率
+const MIN_NET_BASIS: f64 = 0.005;
+/// 单条腿的吃单手续费率
+const TAKER_FEE: f64 = 0.001;
+
 struct Diff {
     pub base: String,
+    /// 现货的计价币，合约价格已换算为该币种
     pub quote: String,
+    pub spot_inst_id: String,
+    pub swap_inst_id: String,
+    pub path: QuotePath,
+    /// 现货最新成交价
     pub spot_px: BigDecimal,
+    /// 合约最新成交价
     pub swap_px: BigDecimal,
+    /// 按最新成交价计算的价差，仅供参考
     pub diff: BigDecimal,
     pub diff_rate: BigDecimal,
+    pub direction: Direction,
+    /// 按方向吃单的现货价格和合约价格
+    pub spot_exec_px: BigDecimal,
+    pub swap_exec_px: BigDecimal,
+    /// 可成交的基差率
+    pub exec_rate: BigDecimal,
+    /// 本期和下一期预测的资金费率，查询失败时为 None
+    pub funding_rate: Option<BigDecimal>,
+    pub next_funding_rate: Option<BigDecimal>,
+    /// 下一次收取资金费的时间
+    pub funding_time: Option<u64>,
+    /// 基差收敛和资金费合计的年化收益，见 annualized_carry
+    pub carry: BigDecimal,
+    /// 配置了 API Key 时查询的可开仓容量
+    pub capacity: Option<Capacity>,
+    /// 按两边深度计算的可成交数量，获取深度失败时为 None
+    pub depth: Option<DepthBasis>,
+    /// depth 的预期收益换算为 USD
+    pub profit_usd: Option<BigDecimal>,
+}
+
+/// 按套利方向的可开仓容量
+/// 价差为正时买现货、卖合约，为负时卖现货、买合约
+struct Capacity {
+    /// 现货腿付出的币种，买现货时为计价币，卖现货时为交易货币
+    pub ccy: String,
+    /// ccy 的可用余额
+    pub free: BigDecimal,
+    /// 现货最大可下单数量，单位为币；卖现货时按全仓查询，已包含可借的数量
+    pub spot_max: BigDecimal,
+    /// 合约最大可下单数量，单位为张
+    pub swap_max: BigDecimal,
+}
+
+impl Capacity {
+    /// 卖现货可以借币，不要求持有交易货币
+    fn is_empty(&self, direction: Direction) -> bool {
+        let no_funds = direction == Direction::CashAndCarry && self.free.is_zero();
+        no_funds || self.spot_max.is_zero() || self.swap_max.is_zero()
+    }
 }
 
-impl SpotSwap {
-    pub async fn new() -> Self {
-        let spot = match get_ticker(Category::Spot).await{
-            Some(tickers) => tickers,
-            None => vec![],
+impl Diff {
+    /// 可成交的基差率超过 1% 时返回，最新成交价的价差只用于展示
+    fn new(spot: &Ticker, swap: &Ticker, path: QuotePath, basis: &Basis) -> Option<Diff> {
+        let threshold = BigDecimal::from_f64(0.01).unwrap(); // 1% threshold
+        let (direction, exec_rate) = basis.opportunity(&threshold)?;
+        let swap_px = path.convert(&swap.last);
+        let diff = &swap_px - &spot.last;
+        let diff_rate = if spot.last.is_zero() {
+            BigDecimal::zero()
+        } else {
+            &diff / &spot.last
         };
-        assert!(spot.len() > 0, "fetch spot tickers failed");
-        let swap = match get_ticker(Category::Swap).await {
-            Some(tickers) => tickers,
-            None => vec![],
+        Some(Diff {
+            base: spot.base.clone(),
+            quote: spot.quote.clone(),
+            spot_inst_id: spot.inst_id.clone(),
+            swap_inst_id: swap.inst_id.clone(),
+            spot_px: spot.last.clone(),
+            swap_px,
+            diff,
+            diff_rate,
+            direction,
+            spot_exec_px: basis.spot_px(direction).clone(),
+            swap_exec_px: basis.swap_px(direction).clone(),
+            exec_rate: exec_rate.clone(),
+            funding_rate: None,
+            next_funding_rate: None,
+            funding_time: None,
+            carry: BigDecimal::zero(),
+            capacity: None,
+            depth: None,
+            profit_usd: None,
+            path,
+        })
+    }
+
+    /// 记录资金费率并计算年化收益，funding 为 None 时只计算基差收敛的收益
+    fn set_funding(&mut self, funding: Option<&FundingRate>) {
+        self.carry = annualized_carry(self.direction, &self.exec_rate, funding, HOLD_DAYS);
+        if let Some(funding) = funding {
+            self.funding_rate = Some(funding.funding_rate.clone());
+            self.next_funding_rate = funding.next_funding_rate.clone();
+            self.funding_time = Some(funding.funding_time);
+        }
+    }
+
+    /// 按两边深度计算扣除两条腿手续费后基差率仍不低于 MIN_NET_BASIS 的数量，预期收益换算为 USD
+    /// 第一档就达不到时返回 false
+    fn set_depth(&mut self, spot: &OrderBook, swap: &OrderBook, swap_symbol: &Symbol, quotes: &QuoteConverter) -> bool {
+        let fee = BigDecimal::from_f64(TAKER_FEE * 2.0).unwrap();
+        let threshold = BigDecimal::from_f64(MIN_NET_BASIS).unwrap();
+        let Some(depth) = depth_basis(spot, swap, swap_symbol, self.direction, &self.path.rate, &fee, &threshold) else {
+            return false;
         };
-        assert!(swap.len() > 0, "fetch swap tickers failed");
+        self.profit_usd = quotes.rate(&self.quote, "USD").map(|r| &depth.profit * r);
+        self.depth = Some(depth);
+        true
+    }
+}
+
+impl<E: Exchange> SpotSwap<E> {
+    pub async fn new(
+        client: OkxClient,
+        hedge: Option<HedgeExecutor<E>>,
+        paper: Option<SimExchange>,
+        quotes: QuoteConverter,
+    ) -> Self {
+        // 初始化阶段必须获取到数据，失败时等待后重试
+        let spot = wait_for_data("fetch spot tickers", || client.get_ticker(Category::Spot)).await;
+        let swap = wait_for_data("fetch swap tickers", || client.get_ticker(Category::Swap)).await;
+        let swap_symbols = wait_for_data("fetch swap symbols", || client.get_symbols(Category::Swap)).await;
         SpotSwap {
+            client,
             spot,
             swap,
             diffs: Vec::new(),
+            hedge,
+            paper,
+            quotes,
+            swap_symbols: swap_symbols.into_iter().map(|s| (s.inst_id.clone(), s)).collect(),
         }
     }
 
     pub async fn run(&mut self) {
-        let spot = match get_ticker(Category::Spot).await{
-            Some(tickers) => tickers,
-            None => vec![],
+        let spot = match self.client.get_ticker(Category::Spot).await {
+            Ok(tickers) => tickers,
+            Err(e) => {
+                log_okx_error("fetch spot tickers", &e);
+                return;
+            }
         };
-        if spot.len() == 0 {
+        if spot.is_empty() {
             return;
         }
-        let swap = match get_ticker(Category::Swap).await {
-            Some(tickers) => tickers,
-            None => vec![],
+        let swap = match self.client.get_ticker(Category::Swap).await {
+            Ok(tickers) => tickers,
+            Err(e) => {
+                log_okx_error("fetch swap tickers", &e);
+                return;
+            }
         };
-        if swap.len() == 0 {
+        if swap.is_empty() {
             return;
         }
+        if let Some(paper) = &self.paper {
+            paper.update_tickers(spot.iter().chain(swap.iter()).cloned());
+        }
+
+        self.update_quotes(&spot).await;
 
-        let spot_map: HashMap<String, Ticker> = HashMap::from_iter(spot.iter().map(|t| (format!("{}-{}", t.base, t.quote), t.clone())));
+        let now = Utc::now().timestamp_millis() as u64;
         let mut diffs = Vec::new();
-        for s in swap.iter() {
-            let key = format!("{}-{}", s.base, s.quote);
-            if spot_map.contains_key(&key) {
-                let spot_ticker = spot_map.get(&key).unwrap();
-                let diff = s.last.clone() - spot_ticker.last.clone();
-                let diff_rate = &diff / spot_ticker.last.clone();
-                let threshold = BigDecimal::from_f64(0.01).unwrap(); // 1% threshold
-                if diff_rate.abs() > threshold {
-                    let item = Diff {
-                        base: s.base.clone(),
-                        quote: s.quote.clone(),
-                        spot_px: spot_ticker.last.clone(),
-                        swap_px: s.last.clone(),
-                        diff: diff.clone(),
-                        diff_rate: diff_rate.clone(),
-                    };
-                    diffs.push(item);
-                }
+        for (spot_ticker, swap_ticker, path) in match_tickers(&spot, &swap, &self.quotes, now) {
+            if let Some(item) = self.compare(spot_ticker, swap_ticker, path).await {
+                diffs.push(item);
             }
         }
+        self.add_funding(&mut diffs).await;
+        let diffs = self.add_depth(diffs).await;
+        let mut diffs = self.check_capacity(diffs).await;
 
         if new_diff(&self.diffs, &diffs) {
-            diffs.sort_by(|a, b| b.diff_rate.cmp(&a.diff_rate));
+            rank(&mut diffs);
             let mut msg = format!("📊 **现货-合约套利机会** 📊\n⏰ {}\n", Local::now().format("%m-%d %H:%M:%S"));
             
             for (i, d) in diffs.iter().enumerate() {
+                let exec_percent = d.exec_rate.clone() * BigDecimal::from_f64(100.0).unwrap();
                 let rate_percent = d.diff_rate.clone() * BigDecimal::from_f64(100.0).unwrap();
-                let emoji = if d.diff_rate > BigDecimal::from_f64(0.0).unwrap() { "📈" } else { "📉" };
+                let (emoji, action) = match d.direction {
+                    Direction::CashAndCarry => ("📈", "买现货/空合约"),
+                    Direction::ReverseCarry => ("📉", "卖现货/多合约"),
+                };
                 
                 // 格式化价格显示，保留4位小数
-                let spot_px_formatted = format!("{:.4}", d.spot_px);
-                let swap_px_formatted = format!("{:.4}", d.swap_px);
-                let diff_formatted = format!("{:.4}", d.diff);
-                
                 msg += &format!(
-                    "{} **{}{}**\n💰 现货: `{}`\n🔄 合约: `{}`\n📊 差价: `{}` ({:.2}%)\n", 
+                    "{} **{}/{}** {}\n🔀 计价: `{}`\n💰 现货: `{:.4}` (最新 `{:.4}`)\n🔄 合约: `{:.4}` (最新 `{:.4}`)\n📊 可成交基差: {:.2}% (最新价差 `{:.4}` {:.2}%)\n", 
                     emoji,
-                    d.base, 
-                    d.quote,
-                    spot_px_formatted,
-                    swap_px_formatted, 
-                    diff_formatted,
+                    d.spot_inst_id,
+                    d.swap_inst_id,
+                    action,
+                    d.path,
+                    d.spot_exec_px,
+                    d.spot_px,
+                    d.swap_exec_px,
+                    d.swap_px,
+                    exec_percent,
+                    d.diff,
                     rate_percent
                 );
+                let funding = match (&d.funding_rate, &d.next_funding_rate) {
+                    (Some(rate), Some(next)) => format!("{:.4}% / 预测 {:.4}%", rate * BigDecimal::from(100), next * BigDecimal::from(100)),
+                    (Some(rate), None) => format!("{:.4}%", rate * BigDecimal::from(100)),
+                    _ => "-".to_string(),
+                };
+                let funding_time = d
+                    .funding_time
+                    .and_then(|t| chrono::DateTime::from_timestamp_millis(t as i64))
+                    .map(|t| t.with_timezone(&Local).format("%H:%M").to_string())
+                    .unwrap_or_else(|| "-".to_string());
+                msg += &format!(
+                    "💸 资金费率: `{}` 下次: `{}`\n🎯 年化收益: {:.2}%\n",
+                    funding,
+                    funding_time,
+                    &d.carry * BigDecimal::from(100)
+                );
+                if let Some(depth) = &d.depth {
+                    let profit = match &d.profit_usd {
+                        Some(usd) => format!("${:.2}", usd),
+                        None => format!("{:.2} {}", depth.profit, d.quote),
+                    };
+                    msg += &format!(
+                        "📚 深度可成交: `{:.4}` {} 均价 `{:.4}`/`{:.4}` 扣费基差 {:.2}% 预期收益: `{}`\n",
+                        depth.size,
+                        d.base,
+                        depth.spot_vwap,
+                        depth.swap_vwap,
+                        &depth.net_rate * BigDecimal::from(100),
+                        profit
+                    );
+                }
+                if let Some(c) = &d.capacity {
+                    msg += &format!(
+                        "💵 可用{}: `{:.2}` 现货可下: `{}` 合约可下: `{}`张\n",
+                        c.ccy, c.free, c.spot_max, c.swap_max
+                    );
+                }
                 
                 // 在每个条目之间添加分隔线，除了最后一个
                 if i < diffs.len() - 1 {
@@ -131,12 +347,238 @@ impl SpotSwap {
     }
 }
 
-fn new_diff(old:&Vec<Diff>, new:&Vec<Diff>) -> bool {
+impl<E: Exchange> SpotSwap<E> {
+    /// 更新计价币之间的换算价格，指数查询失败时沿用上一次的价格
+    async fn update_quotes(&mut self, spot: &[Ticker]) {
+        if !self.quotes.config().use_index {
+            return;
+        }
+        match self.client.get_index_tickers("USD").await {
+            Ok(index) => self.quotes.update_index(&index),
+            Err(e) => log_okx_error("fetch index tickers", &e),
+        }
+        self.quotes.update_spot(spot);
+    }
+
+    /// 按 path 把合约价格换算为现货的计价币后计算基差，基差率超过阈值时返回
+    async fn compare(&mut self, spot: &Ticker, swap: &Ticker, path: QuotePath) -> Option<Diff> {
+        let basis = quote_basis(spot, swap, &path)?;
+        // 跨计价币的对冲还有汇率敞口，只提醒不自动开仓
+        if path.is_direct()
+            && let Some(hedge) = &mut self.hedge
+            && let Some(event) = hedge.on_basis(&spot.inst_id, &swap.inst_id, &basis).await
+        {
+            notify_hedge(&event, self.paper.is_some()).await;
+        }
+        Diff::new(spot, swap, path, &basis)
+    }
+
+    /// 获取两条腿的深度，计算扣除手续费后基差率仍不低于 MIN_NET_BASIS 的最大数量
+    /// 第一档就达不到的机会不提醒；获取深度失败时保留机会，不附带数量
+    async fn add_depth(&mut self, diffs: Vec<Diff>) -> Vec<Diff> {
+        // 有新上线的永续时重新获取交易规则，每轮最多一次
+        if diffs.iter().any(|d| !self.swap_symbols.contains_key(&d.swap_inst_id)) {
+            match self.client.get_symbols(Category::Swap).await {
+                Ok(symbols) => self.swap_symbols = symbols.into_iter().map(|s| (s.inst_id.clone(), s)).collect(),
+                Err(e) => log_okx_error("fetch swap symbols", &e),
+            }
+        }
+        let mut checked = Vec::with_capacity(diffs.len());
+        for mut diff in diffs {
+            let Some(swap_symbol) = self.swap_symbols.get(&diff.swap_inst_id) else {
+                checked.push(diff);
+                continue;
+            };
+            let (spot_book, swap_book) = match tokio::join!(
+                self.client.get_order_book(&diff.spot_inst_id, Some(BOOK_DEPTH)),
+                self.client.get_order_book(&diff.swap_inst_id, Some(BOOK_DEPTH)),
+            ) {
+                (Ok(Some(spot)), Ok(Some(swap))) => (spot, swap),
+                (Err(e), _) | (_, Err(e)) => {
+                    log_okx_error(&format!("fetch order book of {}/{}", diff.spot_inst_id, diff.swap_inst_id), &e);
+                    checked.push(diff);
+                    continue;
+                }
+                _ => {
+                    checked.push(diff);
+                    continue;
+                }
+            };
+            let sized = diff.set_depth(&spot_book, &swap_book, swap_symbol, &self.quotes);
+            if let Some(paper) = &self.paper {
+                paper.update_book(spot_book);
+                paper.update_book(swap_book);
+            }
+            if !sized {
+                info!("skip {}/{}: not enough depth after fees", diff.spot_inst_id, diff.swap_inst_id);
+                continue;
+            }
+            checked.push(diff);
+        }
+        checked
+    }
+
+    /// 并发查询资金费率并计算年化收益，查询失败时只计算基差收敛的收益
+    /// 同一个永续可能与多个计价币的现货比较，每轮每个合约只查询一次
+    async fn add_funding(&self, diffs: &mut [Diff]) {
+        let mut swap_ids: Vec<String> = diffs.iter().map(|d| d.swap_inst_id.clone()).collect();
+        swap_ids.sort();
+        swap_ids.dedup();
+        let results = join_all(swap_ids.iter().map(|id| self.client.get_funding_rate(id))).await;
+        let mut funding: HashMap<String, FundingRate> = HashMap::new();
+        for (swap_id, result) in swap_ids.into_iter().zip(results) {
+            match result {
+                Ok(Some(rate)) => {
+                    funding.insert(swap_id, rate);
+                }
+                Ok(None) => {}
+                Err(e) => log_okx_error(&format!("fetch funding rate of {}", swap_id), &e),
+            }
+        }
+        for diff in diffs.iter_mut() {
+            diff.set_funding(funding.get(&diff.swap_inst_id));
+        }
+    }
+
+    /// 配置了 API Key 时查询可用余额和最大可下单数量，没有容量的机会不提醒
+    /// 查询失败时保留机会，不附带容量
+    async fn check_capacity(&self, diffs: Vec<Diff>) -> Vec<Diff> {
+        if self.client.config().credentials.is_none() || diffs.is_empty() {
+            return diffs;
+        }
+        let free: HashMap<String, BigDecimal> = match self.client.get_balance(&[]).await {
+            Ok(Some(balance)) => balance
+                .details
+                .into_iter()
+                .map(|b| (b.ccy, b.avail_bal.unwrap_or(b.cash_bal)))
+                .collect(),
+            Ok(None) => HashMap::new(),
+            Err(e) => {
+                log_okx_error("fetch account balance", &e);
+                return diffs;
+            }
+        };
+        let mut checked = Vec::with_capacity(diffs.len());
+        for mut diff in diffs {
+            let (spot_id, swap_id) = (diff.spot_inst_id.clone(), diff.swap_inst_id.clone());
+            let buy_spot = diff.direction == Direction::CashAndCarry;
+            let spot_max = match self.client.get_max_size(&spot_id, spot_td_mode(diff.direction), None).await {
+                Ok(max) => max.map(|m| if buy_spot { m.max_buy } else { m.max_sell }).unwrap_or_default(),
+                Err(e) => {
+                    log_okx_error(&format!("fetch max size of {}", spot_id), &e);
+                    checked.push(diff);
+                    continue;
+                }
+            };
+            let swap_max = match self.client.get_max_size(&swap_id, TdMode::Cross, None).await {
+                Ok(max) => max.map(|m| if buy_spot { m.max_sell } else { m.max_buy }).unwrap_or_default(),
+                Err(e) => {
+                    log_okx_error(&format!("fetch max size of {}", swap_id), &e);
+                    checked.push(diff);
+                    continue;
+                }
+            };
+            let ccy = if buy_spot { diff.quote.clone() } else { diff.base.clone() };
+            let capacity = Capacity {
+                free: free.get(&ccy).cloned().unwrap_or_default(),
+                ccy,
+                spot_max,
+                swap_max,
+            };
+            if capacity.is_empty(diff.direction) {
+                info!(
+                    "skip {}/{}: free {}={}, spot max={}, swap max={}",
+                    spot_id, swap_id, capacity.ccy, capacity.free, capacity.spot_max, capacity.swap_max
+                );
+                continue;
+            }
+            diff.capacity = Some(capacity);
+            checked.push(diff);
+        }
+        checked
+    }
+}
+
+async fn notify_hedge(event: &HedgeEvent, paper: bool) {
+    let mut msg = match event {
+        HedgeEvent::Opened(pair) => format!(
+            "✅ **对冲开仓** {:?}\n💰 {}: `{}`\n🔄 {}: `{}`张\n📊 基差: `{:.4}`",
+            pair.direction, pair.spot_inst_id, pair.spot_sz, pair.swap_inst_id, pair.swap_sz, pair.open_basis
+        ),
+        HedgeEvent::Closed { pair, close_basis } => format!(
+            "🏁 **对冲平仓** {}/{}\n📊 开仓基差: `{:.4}` 平仓基差: `{:.4}`",
+            pair.spot_inst_id, pair.swap_inst_id, pair.open_basis, close_basis
+        ),
+        HedgeEvent::Unwound { spot_inst_id, reason } => format!("⚠️ **对冲失败** {}\n{}", spot_inst_id, reason),
+        HedgeEvent::Suspended { spot_inst_id, reason } => {
+            format!("🚨 **对冲暂停** {}\n{}\n请人工检查仓位", spot_inst_id, reason)
+        }
+    };
+    if paper {
+        msg = format!("🧪 模拟盘\n{}", msg);
+    }
+    if !broadcast(&msg).await {
+        warn!("sent telegram message failed:\n{}", msg);
+    }
+}
+
+/// 按交易货币匹配现货和合约，计价币可以换算时返回换算路径，跳过任意一侧过期的 ticker
+/// 同一个币的现货可能有多个计价币，都与合约比较
+fn match_tickers<'a>(
+    spot: &'a [Ticker],
+    swap: &'a [Ticker],
+    quotes: &QuoteConverter,
+    now: u64,
+) -> Vec<(&'a Ticker, &'a Ticker, QuotePath)> {
+    let mut spot_map: HashMap<&str, Vec<&Ticker>> = HashMap::new();
+    for t in spot.iter() {
+        spot_map.entry(t.base.as_str()).or_default().push(t);
+    }
+    let mut matched = Vec::new();
+    for s in swap.iter() {
+        let Some(candidates) = spot_map.get(s.base.as_str()) else {
+            continue;
+        };
+        for spot_ticker in candidates.iter().copied() {
+            let Some(path) = quotes.path(&spot_ticker.pair_id(), &InstId::swap(&s.base, &s.quote)) else {
+                continue;
+            };
+            if is_stale(spot_ticker, now) || is_stale(s, now) {
+                debug!("skip {}: ticker is stale, spot ts={}, swap ts={}", s.inst_id, spot_ticker.ts, s.ts);
+                continue;
+            }
+            matched.push((spot_ticker, s, path));
+        }
+    }
+    matched
+}
+
+/// 合约价格按 path 换算为现货的计价币后的盘口基差，任意一侧盘口为空时无法成交
+fn quote_basis(spot: &Ticker, swap: &Ticker, path: &QuotePath) -> Option<Basis> {
+    Basis::new(
+        spot.bid_px.clone()?,
+        spot.ask_px.clone()?,
+        path.convert(swap.bid_px.as_ref()?),
+        path.convert(swap.ask_px.as_ref()?),
+    )
+}
+
+/// 按年化收益从高到低排序
+fn rank(diffs: &mut [Diff]) {
+    diffs.sort_by(|a, b| b.carry.cmp(&a.carry));
+}
+
+/// ticker 的时间比当前时间早 MAX_TICKER_AGE_MS 以上
+pub(crate) fn is_stale(ticker: &Ticker, now: u64) -> bool {
+    now.saturating_sub(ticker.ts) > MAX_TICKER_AGE_MS
+}
+
+fn new_diff(old: &[Diff], new: &[Diff]) -> bool {
     if old.len() != new.len() {
         return true;
     }
-    let new_set: std::collections::HashSet<String> = new.iter().map(|d| format!("{}-{}", d.base, d.quote)).collect();
-    let old_set: std::collections::HashSet<String> = old.iter().map(|d| format!("{}-{}", d.base, d.quote)).collect();
+    let new_set: std::collections::HashSet<(&str, &str)> = new.iter().map(|d| (d.spot_inst_id.as_str(), d.swap_inst_id.as_str())).collect();
+    let old_set: std::collections::HashSet<(&str, &str)> = old.iter().map(|d| (d.spot_inst_id.as_str(), d.swap_inst_id.as_str())).collect();
     for item in new_set.iter() {
         if !old_set.contains(item) {
             return true;
@@ -147,5 +589,187 @@ fn new_diff(old:&Vec<Diff>, new:&Vec<Diff>) -> bool {
             return true;
         }
     }
-    return false;
-}
\ No newline at end of file
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::{self, dec, index, ticker};
+    use crate::model::order_book::{BookUpdate, Level};
+
+    const NOW: u64 = 1_700_000_000_000;
+
+    fn direct_path() -> QuotePath {
+        QuoteConverter::new(QuoteConfig::default())
+            .path(&InstId::spot("BTC", "USDT"), &InstId::swap("BTC", "USDT"))
+            .unwrap()
+    }
+
+    fn diff(spot: &Ticker, swap: &Ticker, path: QuotePath) -> Option<Diff> {
+        Diff::new(spot, swap, path.clone(), &quote_basis(spot, swap, &path)?)
+    }
+
+    #[test]
+    fn test_executable_basis() {
+        // 买现货吃卖一、卖合约吃买一，最新价的价差只用于展示
+        let spot = ticker("BTC-USDT", "100", "100.5");
+        let swap = ticker("BTC-USDT-SWAP", "102", "102.5");
+        let d = diff(&spot, &swap, direct_path()).unwrap();
+        assert_eq!(d.direction, Direction::CashAndCarry);
+        assert_eq!((d.spot_exec_px.clone(), d.swap_exec_px.clone()), (dec("100.5"), dec("102")));
+        assert_eq!(d.exec_rate, dec("1.5") / dec("100.5"));
+        assert_eq!((d.diff.clone(), d.diff_rate.clone()), (dec("2"), dec("0.02")));
+
+        let d = diff(&ticker("BTC-USDT", "100", "100.1"), &ticker("BTC-USDT-SWAP", "98", "98.5"), direct_path()).unwrap();
+        assert_eq!(d.direction, Direction::ReverseCarry);
+        assert_eq!((d.spot_exec_px, d.swap_exec_px), (dec("100"), dec("98.5")));
+
+        // 最新价相差 2%，但盘口价差不到 1%
+        let spot = Ticker {
+            last: dec("100"),
+            ..ticker("BTC-USDT", "100.9", "101.5")
+        };
+        assert!(diff(&spot, &swap, direct_path()).is_none());
+
+        // 盘口为空时无法成交
+        let empty = Ticker {
+            ask_px: None,
+            ..ticker("BTC-USDT", "100", "100.5")
+        };
+        assert!(diff(&empty, &swap, direct_path()).is_none());
+    }
+
+    #[test]
+    fn test_match_across_quotes() {
+        let mut quotes = QuoteConverter::new(QuoteConfig::default());
+        quotes.update_index(&[index("USDT-USD", "1.25"), index("USDC-USD", "1.25")]);
+        let fresh = |inst_id: &str| Ticker {
+            ts: NOW,
+            ..ticker(inst_id, "100", "100.5")
+        };
+        let spot = [fresh("BTC-USDT"), fresh("BTC-USDC"), fresh("BTC-EUR"), fresh("ETH-USDT")];
+        let swap = [
+            fresh("BTC-USDT-SWAP"),
+            fresh("BTC-USD-SWAP"),
+            Ticker { ts: 0, ..fresh("ETH-USDT-SWAP") },
+        ];
+        let mut matched: Vec<(String, String, String)> = match_tickers(&spot, &swap, &quotes, NOW)
+            .into_iter()
+            .map(|(spot, swap, path)| (spot.inst_id.clone(), swap.inst_id.clone(), path.to_string()))
+            .collect();
+        matched.sort();
+        // 不在等价集合中的 EUR 不比较，过期的 ETH 永续跳过
+        assert_eq!(
+            matched,
+            vec![
+                ("BTC-USDC".to_string(), "BTC-USD-SWAP".to_string(), "USD→USDC @0.800000 (币本位)".to_string()),
+                ("BTC-USDC".to_string(), "BTC-USDT-SWAP".to_string(), "USDT→USDC @1.000000".to_string()),
+                ("BTC-USDT".to_string(), "BTC-USD-SWAP".to_string(), "USD→USDT @0.800000 (币本位)".to_string()),
+                ("BTC-USDT".to_string(), "BTC-USDT-SWAP".to_string(), "USDT".to_string()),
+            ]
+        );
+
+        // 换算后比较，USD 合约的 100 相当于 80 USDT
+        let (spot, swap, path) = match_tickers(&spot[..1], &swap[1..2], &quotes, NOW).remove(0);
+        let d = diff(spot, swap, path).unwrap();
+        assert_eq!(d.direction, Direction::ReverseCarry);
+        assert_eq!(d.swap_exec_px, dec("80.4"));
+    }
+
+    fn book(inst_id: &str, bids: &[(&str, &str)], asks: &[(&str, &str)]) -> OrderBook {
+        let levels = |levels: &[(&str, &str)]| levels.iter().map(|(px, sz)| Level::parse(px, sz, 1).unwrap()).collect();
+        let mut book = OrderBook::new(inst_id);
+        book.apply_snapshot(BookUpdate {
+            bids: levels(bids),
+            asks: levels(asks),
+            ..Default::default()
+        })
+        .unwrap();
+        book
+    }
+
+    #[test]
+    fn test_depth_sizing() {
+        let mut quotes = QuoteConverter::new(QuoteConfig::default());
+        let spot = ticker("BTC-USDT", "99.9", "100");
+        let swap = ticker("BTC-USDT-SWAP", "102", "102.1");
+        let swap_symbol = fixture::swap("BTC-USDT-SWAP", "1", "0.1");
+        let spot_book = book("BTC-USDT", &[("99.9", "2")], &[("100", "2")]);
+        // 每张 0.1 BTC，第二档只能吃到扣费基差率正好为 0.5% 的 0.5 BTC
+        let swap_book = book("BTC-USDT-SWAP", &[("102", "10"), ("98.1", "10")], &[("102.1", "20")]);
+
+        // 没有 USD 指数时不换算收益
+        let mut d = diff(&spot, &swap, direct_path()).unwrap();
+        assert!(d.set_depth(&spot_book, &swap_book, &swap_symbol, &quotes));
+        let depth = d.depth.clone().unwrap();
+        assert_eq!(depth.size.round(8), dec("1.5"));
+        assert_eq!(depth.net_rate.round(10), dec("0.005"));
+        assert!(d.profit_usd.is_none());
+
+        quotes.update_index(&[index("USDT-USD", "1.25")]);
+        let mut d = diff(&spot, &swap, direct_path()).unwrap();
+        assert!(d.set_depth(&spot_book, &swap_book, &swap_symbol, &quotes));
+        assert_eq!(d.profit_usd, Some(&depth.profit * dec("1.25")));
+
+        // 第一档扣费后就不到 0.5%
+        let swap_book = book("BTC-USDT-SWAP", &[("100.6", "10")], &[("102.1", "20")]);
+        let mut d = diff(&spot, &swap, direct_path()).unwrap();
+        assert!(!d.set_depth(&spot_book, &swap_book, &swap_symbol, &quotes));
+        assert!(d.depth.is_none());
+    }
+
+    #[test]
+    fn test_rank_by_carry() {
+        // 基差 2%，没有资金费率
+        let mut btc = diff(&ticker("BTC-USDT", "99", "100"), &ticker("BTC-USDT-SWAP", "102", "102.5"), direct_path()).unwrap();
+        btc.set_funding(None);
+        assert_eq!(btc.carry, dec("0.02") * dec("365") / dec("7"));
+        assert!(btc.funding_rate.is_none());
+
+        // 基差只有 1.5%，但每 8 小时收取 0.1% 的资金费
+        let mut eth = diff(&ticker("ETH-USDT", "99", "100"), &ticker("ETH-USDT-SWAP", "101.5", "102"), direct_path()).unwrap();
+        let funding = FundingRate {
+            inst_id: "ETH-USDT-SWAP".to_string(),
+            funding_rate: dec("0.001"),
+            next_funding_rate: None,
+            funding_time: NOW,
+            next_funding_time: NOW + 8 * 3600 * 1000,
+            min_funding_rate: None,
+            max_funding_rate: None,
+            ts: NOW,
+        };
+        eth.set_funding(Some(&funding));
+        assert_eq!(eth.funding_rate, Some(dec("0.001")));
+        assert_eq!(eth.funding_time, Some(NOW));
+        assert!(eth.exec_rate < btc.exec_rate && eth.carry > btc.carry);
+
+        let mut diffs = vec![btc, eth];
+        rank(&mut diffs);
+        let order: Vec<&str> = diffs.iter().map(|d| d.swap_inst_id.as_str()).collect();
+        assert_eq!(order, vec!["ETH-USDT-SWAP", "BTC-USDT-SWAP"]);
+    }
+
+    #[test]
+    fn test_never_traded_spot() {
+        let spot = Ticker {
+            last: BigDecimal::zero(),
+            ..ticker("BTC-USDT", "100", "100.5")
+        };
+        let d = diff(&spot, &ticker("BTC-USDT-SWAP", "102", "102.5"), direct_path()).unwrap();
+        assert_eq!(d.diff_rate, BigDecimal::zero());
+        assert_eq!(d.exec_rate, dec("1.5") / dec("100.5"));
+    }
+
+    #[test]
+    fn test_stale_ticker() {
+        let t = Ticker {
+            ts: 1_000,
+            ..ticker("BTC-USDT", "100", "100.5")
+        };
+        assert!(!is_stale(&t, 1_000 + MAX_TICKER_AGE_MS));
+        assert!(is_stale(&t, 1_001 + MAX_TICKER_AGE_MS));
+        // 本地时间落后于交易所时不算过期
+        assert!(!is_stale(&t, 0));
+    }
+}
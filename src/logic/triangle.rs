@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+
+use bigdecimal::BigDecimal;
+use chrono::{Local, Utc};
+use log::{info, warn};
+use serde::Deserialize;
+
+use crate::action::{client::OkxClient, telegram::broadcast};
+use crate::logic::spot_swap::is_stale;
+use crate::model::order::Side;
+use crate::model::symbol::Category;
+use crate::model::triangle::{Cycle, CurrencyGraph};
+use crate::util::log::log_okx_error;
+
+/// 现货三角套利扫描的配置，对应 conf/okx.yml 中的 triangle
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct TriangleConfig {
+    /// 吃单手续费率，每一腿都按吃单计算
+    pub taker_fee: BigDecimal,
+    /// 环路的起始币种
+    pub start: Vec<String>,
+    /// 扣除手续费后的收益率超过该值时提醒
+    pub min_profit: BigDecimal,
+    /// 每次最多提醒的环路数
+    pub top: usize,
+}
+
+impl Default for TriangleConfig {
+    fn default() -> Self {
+        TriangleConfig {
+            taker_fee: BigDecimal::new(1.into(), 3),
+            start: vec!["USDT".to_string(), "USDC".to_string()],
+            min_profit: BigDecimal::new(5.into(), 4),
+            top: 5,
+        }
+    }
+}
+
+/// 用一次获取的全部现货行情构建币种图，查找扣除手续费后有收益的三角环路
+pub async fn triangular_arbitrage(client: OkxClient, config: TriangleConfig) {
+    let mut last: HashSet<String> = HashSet::new();
+    info!("triangular arbitrage scanner started");
+    loop {
+        if let Some(cycles) = scan(&client, &config).await {
+            let keys: HashSet<String> = cycles.iter().map(|c| c.to_string()).collect();
+            if keys != last {
+                let msg = cycle_message(&cycles);
+                if !broadcast(&msg).await {
+                    warn!("sent telegram message failed:\n{}", msg);
+                }
+                last = keys;
+            }
+        }
+        tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
+    }
+}
+
+/// 按收益率从高到低返回最多 top 个环路，获取行情失败时返回 None
+async fn scan(client: &OkxClient, config: &TriangleConfig) -> Option<Vec<Cycle>> {
+    let tickers = match client.get_ticker(Category::Spot).await {
+        Ok(tickers) => tickers,
+        Err(e) => {
+            log_okx_error("fetch spot tickers", &e);
+            return None;
+        }
+    };
+    if tickers.is_empty() {
+        return None;
+    }
+    let now = Utc::now().timestamp_millis() as u64;
+    let graph = CurrencyGraph::from_tickers(tickers.iter().filter(|t| !is_stale(t, now)), &config.taker_fee);
+    let cycles = config.start.iter().flat_map(|s| graph.triangles(s, &config.min_profit)).collect();
+    Some(rank(cycles, config.top))
+}
+
+/// 去重后按收益率从高到低保留 top 个
+/// 同时经过多个起始币种的环路会重复出现，按使用的交易对和方向去重，保留先出现的
+fn rank(mut cycles: Vec<Cycle>, top: usize) -> Vec<Cycle> {
+    let mut seen = HashSet::new();
+    cycles.retain(|c| {
+        let mut legs: Vec<_> = c.legs.iter().map(|l| (l.inst_id.clone(), l.side)).collect();
+        legs.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.as_str().cmp(b.1.as_str())));
+        seen.insert(legs)
+    });
+    cycles.sort_by(|a, b| b.profit_rate.cmp(&a.profit_rate));
+    cycles.truncate(top);
+    cycles
+}
+
+fn cycle_message(cycles: &[Cycle]) -> String {
+    let mut msg = format!("🔺 **现货三角套利** 🔺\n⏰ {}\n", Local::now().format("%m-%d %H:%M:%S"));
+    for (i, c) in cycles.iter().enumerate() {
+        msg += &format!(
+            "💰 **{}**\n📊 扣费收益: {:.3}%\n💵 最大数量: `{:.4}` {} 预期收益: `{:.4}` {}\n",
+            c,
+            &c.profit_rate * BigDecimal::from(100),
+            c.max_size,
+            c.start(),
+            c.profit(),
+            c.start()
+        );
+        for leg in c.legs.iter() {
+            let action = match leg.side {
+                Side::Buy => "买入",
+                Side::Sell => "卖出",
+            };
+            msg += &format!("▫️ {} `{}` @ `{}`\n", action, leg.inst_id, leg.px);
+        }
+        if i < cycles.len() - 1 {
+            msg += "➖➖➖➖➖➖➖➖➖➖\n";
+        }
+    }
+    msg += &format!("\n📝 共发现 {} 个三角套利机会", cycles.len());
+    msg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::dec;
+    use crate::model::triangle::Edge;
+
+    /// 每一腿为 (instId, 方向, 兑换率)，币种按方向从 instId 推出
+    fn cycle(start: &str, legs: &[(&str, Side, &str)]) -> Cycle {
+        let mut from = start.to_string();
+        let legs = legs
+            .iter()
+            .map(|(inst_id, side, rate)| {
+                let (base, quote) = inst_id.split_once('-').unwrap();
+                let to = if *side == Side::Buy { base } else { quote }.to_string();
+                Edge {
+                    inst_id: inst_id.to_string(),
+                    from: std::mem::replace(&mut from, to.clone()),
+                    to,
+                    side: *side,
+                    px: dec("1"),
+                    rate: dec(rate),
+                    capacity: dec("1"),
+                }
+            })
+            .collect();
+        Cycle::new(legs)
+    }
+
+    #[test]
+    fn test_rank_dedup_and_top() {
+        let usdt = cycle("USDT", &[("BTC-USDT", Side::Buy, "1"), ("ETH-BTC", Side::Buy, "1"), ("ETH-USDT", Side::Sell, "1.02")]);
+        // 同一个环路从 BTC 出发
+        let btc = cycle("BTC", &[("ETH-BTC", Side::Buy, "1"), ("ETH-USDT", Side::Sell, "1.02"), ("BTC-USDT", Side::Buy, "1")]);
+        // 同样的交易对，方向相反
+        let reverse = cycle("USDT", &[("ETH-USDT", Side::Buy, "1"), ("ETH-BTC", Side::Sell, "1"), ("BTC-USDT", Side::Sell, "1.03")]);
+        let usdc = cycle("USDC", &[("BTC-USDC", Side::Buy, "1"), ("ETH-BTC", Side::Buy, "1"), ("ETH-USDC", Side::Sell, "1.01")]);
+
+        let ranked = rank(vec![usdc.clone(), usdt.clone(), btc, reverse.clone()], 5);
+        assert_eq!(ranked, vec![reverse.clone(), usdt.clone(), usdc]);
+        assert_eq!(ranked[1].to_string(), "USDT→BTC→ETH→USDT");
+
+        assert_eq!(rank(vec![usdt.clone(), reverse.clone()], 1), vec![reverse]);
+    }
+}
@@ -1,14 +1,63 @@
+use okx::action::client::OkxClient;
 use okx::logic;
+use okx::util::config::AppConfig;
 use okx::util::log::init_log;
 
+use log::info;
+
 
 #[tokio::main]
 async fn main() {
     init_log();
 
+    let config = AppConfig::load("conf/okx.yml");
+    let client = OkxClient::new(config.client_config()).expect("failed to build okx client");
+    info!(
+        "okx client started, environment: {}, live trading: {}",
+        client.environment().as_str(),
+        client.config().live_trading
+    );
+
+    // 定期输出各接口的限速统计
+    let metrics_client = client.clone();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(tokio::time::Duration::from_secs(600)).await;
+            for (endpoint, m) in metrics_client.rate_limit_metrics() {
+                info!(
+                    "rate limit {}: allowed={}, delayed={}, rejected={}, wait={}ms",
+                    endpoint, m.allowed, m.delayed, m.rejected, m.total_wait.as_millis()
+                );
+            }
+        }
+    });
+
+    let list_client = client.clone();
     tokio::spawn(async move {
-        logic::list::list().await;
+        logic::list::list(list_client).await;
     });
 
-    logic::spot_swap::spot_swap_arbitrage().await;
+    if let Some(futures) = config.futures {
+        let futures_client = client.clone();
+        let quotes = config.quotes.clone();
+        tokio::spawn(async move {
+            logic::futures::futures_basis(futures_client, futures, quotes).await;
+        });
+    }
+
+    if let Some(calendar) = config.calendar {
+        let calendar_client = client.clone();
+        tokio::spawn(async move {
+            logic::calendar::calendar_spread(calendar_client, calendar).await;
+        });
+    }
+
+    if let Some(triangle) = config.triangle {
+        let triangle_client = client.clone();
+        tokio::spawn(async move {
+            logic::triangle::triangular_arbitrage(triangle_client, triangle).await;
+        });
+    }
+
+    logic::spot_swap::spot_swap_arbitrage(client, config.hedge, config.paper, config.quotes).await;
 }
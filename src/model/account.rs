@@ -0,0 +1,395 @@
+use std::collections::HashMap;
+
+use bigdecimal::{BigDecimal, Zero};
+use serde::{Deserialize, Serialize};
+
+use crate::model::order::{Order, PosSide, TdMode};
+use crate::model::symbol::Category;
+
+/// 单个币种的资产
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Balance {
+    pub ccy: String,
+    /// 币种总权益
+    pub eq: BigDecimal,
+    /// 币种余额
+    pub cash_bal: BigDecimal,
+    /// 可用余额
+    pub avail_bal: Option<BigDecimal>,
+    /// 冻结余额
+    pub frozen_bal: Option<BigDecimal>,
+    /// 未实现盈亏
+    pub upl: Option<BigDecimal>,
+    /// 币种权益的美元价值
+    pub eq_usd: Option<BigDecimal>,
+    pub u_time: u64,
+}
+
+/// 账户资产，account 频道和 /api/v5/account/balance 返回的数据
+/// 保证金相关字段只在保证金模式下有值
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AccountBalance {
+    /// 美元层面的总权益
+    pub total_eq: BigDecimal,
+    /// 美元层面的有效保证金
+    pub adj_eq: Option<BigDecimal>,
+    /// 占用保证金
+    pub imr: Option<BigDecimal>,
+    /// 维持保证金
+    pub mmr: Option<BigDecimal>,
+    /// 保证金率
+    pub mgn_ratio: Option<BigDecimal>,
+    pub u_time: u64,
+    pub details: Vec<Balance>,
+}
+
+/// 持仓
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Position {
+    pub pos_id: String,
+    pub inst_id: String,
+    pub category: Category,
+    pub mgn_mode: TdMode,
+    pub pos_side: PosSide,
+    /// 持仓数量，买卖模式下正数为多、负数为空，合约单位为张
+    pub pos: BigDecimal,
+    /// 保证金币种
+    pub ccy: String,
+    pub avg_px: Option<BigDecimal>,
+    pub upl: Option<BigDecimal>,
+    pub lever: Option<BigDecimal>,
+    pub liq_px: Option<BigDecimal>,
+    pub mark_px: Option<BigDecimal>,
+    /// 占用保证金，逐仓为 margin，全仓为 imr
+    pub margin: Option<BigDecimal>,
+    pub mgn_ratio: Option<BigDecimal>,
+    pub u_time: u64,
+}
+
+/// balance_and_position 频道中的余额变化
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CashBalance {
+    pub ccy: String,
+    pub cash_bal: BigDecimal,
+    pub u_time: u64,
+}
+
+/// balance_and_position 频道的推送，成交、划转等事件导致的余额和持仓变化
+/// 持仓只有数量和均价，其他字段为 None
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BalanceAndPosition {
+    pub p_time: u64,
+    /// 事件类型，如 filled、transferred、delivered
+    pub event_type: String,
+    pub balances: Vec<CashBalance>,
+    pub positions: Vec<Position>,
+}
+
+/// 持仓模式
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PositionMode {
+    /// 开平仓模式，多空分开持仓
+    LongShort,
+    /// 买卖模式，只有一个净持仓
+    Net,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl PositionMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PositionMode::LongShort => "long_short_mode",
+            PositionMode::Net => "net_mode",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<PositionMode> {
+        match s {
+            "long_short_mode" => Some(PositionMode::LongShort),
+            "net_mode" => Some(PositionMode::Net),
+            _ => None,
+        }
+    }
+}
+
+/// 账户模式
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AccountMode {
+    /// 现货模式，不能交易合约
+    Spot,
+    /// 现货和合约模式
+    SingleCurrencyMargin,
+    /// 跨币种保证金模式
+    MultiCurrencyMargin,
+    /// 组合保证金模式
+    PortfolioMargin,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl AccountMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountMode::Spot => "1",
+            AccountMode::SingleCurrencyMargin => "2",
+            AccountMode::MultiCurrencyMargin => "3",
+            AccountMode::PortfolioMargin => "4",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<AccountMode> {
+        match s {
+            "1" => Some(AccountMode::Spot),
+            "2" => Some(AccountMode::SingleCurrencyMargin),
+            "3" => Some(AccountMode::MultiCurrencyMargin),
+            "4" => Some(AccountMode::PortfolioMargin),
+            _ => None,
+        }
+    }
+
+    /// 是否可以交易合约
+    pub fn supports_derivatives(&self) -> bool {
+        *self != AccountMode::Spot
+    }
+}
+
+/// 账户配置
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AccountConfig {
+    pub uid: String,
+    pub acct_lv: AccountMode,
+    pub pos_mode: PositionMode,
+    /// 是否自动借币
+    pub auto_loan: bool,
+    /// 用户等级，如 Lv1
+    pub level: String,
+}
+
+/// 设置杠杆倍数
+/// 全仓按 inst_id 或 ccy 设置，逐仓按 inst_id 设置，开平仓模式下逐仓需要 pos_side
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SetLeverage {
+    pub inst_id: Option<String>,
+    pub ccy: Option<String>,
+    pub lever: BigDecimal,
+    pub mgn_mode: TdMode,
+    pub pos_side: Option<PosSide>,
+}
+
+/// 设置后的杠杆倍数
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Leverage {
+    pub inst_id: String,
+    pub lever: BigDecimal,
+    pub mgn_mode: TdMode,
+    pub pos_side: Option<PosSide>,
+}
+
+/// 最大可下单数量，合约为张数，币币为币的数量
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MaxSize {
+    pub inst_id: String,
+    pub ccy: String,
+    pub max_buy: BigDecimal,
+    pub max_sell: BigDecimal,
+}
+
+/// 最大可用余额或保证金，币币和杠杆为币的数量
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MaxAvailSize {
+    pub inst_id: String,
+    pub avail_buy: BigDecimal,
+    pub avail_sell: BigDecimal,
+}
+
+/// 由私有频道推送合并出的账户状态
+/// 每条数据带有更新时间，较旧的推送不会覆盖较新的数据
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AccountState {
+    pub total_eq: Option<BigDecimal>,
+    pub adj_eq: Option<BigDecimal>,
+    pub imr: Option<BigDecimal>,
+    pub mmr: Option<BigDecimal>,
+    pub mgn_ratio: Option<BigDecimal>,
+    /// 按币种
+    pub balances: HashMap<String, Balance>,
+    /// 按 posId，不包含已平仓的持仓
+    pub positions: HashMap<String, Position>,
+    /// 未完成的订单，按 ordId
+    pub orders: HashMap<String, Order>,
+    /// 最后一次更新的时间
+    pub u_time: u64,
+}
+
+impl AccountState {
+    /// 应用 account 频道的推送
+    /// 首次推送为全量，之后只推送有变化的币种
+    pub fn apply_account(&mut self, account: AccountBalance, snapshot: bool) {
+        if snapshot {
+            self.balances.clear();
+        }
+        self.total_eq = Some(account.total_eq);
+        self.adj_eq = account.adj_eq;
+        self.imr = account.imr;
+        self.mmr = account.mmr;
+        self.mgn_ratio = account.mgn_ratio;
+        for balance in account.details {
+            match self.balances.get(&balance.ccy) {
+                Some(old) if old.u_time > balance.u_time => {}
+                _ => {
+                    self.balances.insert(balance.ccy.clone(), balance);
+                }
+            }
+        }
+        self.touch(account.u_time);
+    }
+
+    /// 应用 positions 频道的推送
+    /// 首次推送为全量，之后只推送有变化的持仓，数量为 0 表示已平仓
+    pub fn apply_positions(&mut self, positions: Vec<Position>, snapshot: bool) {
+        if snapshot {
+            self.positions.clear();
+        }
+        for position in positions {
+            let u_time = position.u_time;
+            match self.positions.get(&position.pos_id) {
+                Some(old) if old.u_time > position.u_time => {}
+                _ if position.pos.is_zero() => {
+                    self.positions.remove(&position.pos_id);
+                }
+                _ => {
+                    self.positions.insert(position.pos_id.clone(), position);
+                }
+            }
+            self.touch(u_time);
+        }
+    }
+
+    /// 应用 orders 频道的推送，结束的订单从未完成订单中移除
+    pub fn apply_order(&mut self, order: &Order) {
+        match self.orders.get(&order.ord_id) {
+            Some(old) if old.u_time > order.u_time => return,
+            _ => {}
+        }
+        if order.state.is_final() {
+            self.orders.remove(&order.ord_id);
+        } else {
+            self.orders.insert(order.ord_id.clone(), order.clone());
+        }
+        self.touch(order.u_time);
+    }
+
+    /// 应用 balance_and_position 频道的推送
+    /// 只更新余额、持仓数量和均价，其他字段保留 account、positions 频道的值
+    pub fn apply_balance_and_position(&mut self, update: BalanceAndPosition) {
+        for cash in update.balances {
+            match self.balances.get_mut(&cash.ccy) {
+                Some(old) if old.u_time > cash.u_time => {}
+                Some(old) => {
+                    old.cash_bal = cash.cash_bal;
+                    old.u_time = cash.u_time;
+                }
+                None => {
+                    let balance = Balance {
+                        ccy: cash.ccy.clone(),
+                        eq: cash.cash_bal.clone(),
+                        cash_bal: cash.cash_bal,
+                        avail_bal: None,
+                        frozen_bal: None,
+                        upl: None,
+                        eq_usd: None,
+                        u_time: cash.u_time,
+                    };
+                    self.balances.insert(cash.ccy, balance);
+                }
+            }
+        }
+        for position in update.positions {
+            match self.positions.get_mut(&position.pos_id) {
+                Some(old) if old.u_time > position.u_time => {}
+                _ if position.pos.is_zero() => {
+                    self.positions.remove(&position.pos_id);
+                }
+                Some(old) => {
+                    old.pos = position.pos;
+                    old.avg_px = position.avg_px;
+                    old.u_time = position.u_time;
+                }
+                None => {
+                    self.positions.insert(position.pos_id.clone(), position);
+                }
+            }
+        }
+        self.touch(update.p_time);
+    }
+
+    pub fn balance(&self, ccy: &str) -> Option<&Balance> {
+        self.balances.get(ccy)
+    }
+
+    /// 某个产品的持仓，开平仓模式下可能同时有多空两个持仓
+    pub fn positions_of(&self, inst_id: &str) -> Vec<&Position> {
+        self.positions.values().filter(|p| p.inst_id == inst_id).collect()
+    }
+
+    fn touch(&mut self, u_time: u64) {
+        self.u_time = self.u_time.max(u_time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::dec;
+
+    fn position(pos: &str, avg_px: &str, u_time: u64) -> Position {
+        Position {
+            pos_id: "1".to_string(),
+            inst_id: "BTC-USDT-SWAP".to_string(),
+            category: Category::Swap,
+            mgn_mode: TdMode::Cross,
+            pos_side: PosSide::Net,
+            pos: dec(pos),
+            ccy: "USDT".to_string(),
+            avg_px: Some(dec(avg_px)),
+            upl: None,
+            lever: Some(dec("5")),
+            liq_px: None,
+            mark_px: None,
+            margin: None,
+            mgn_ratio: None,
+            u_time,
+        }
+    }
+
+    #[test]
+    fn test_positions_ignore_stale_updates() {
+        let mut state = AccountState::default();
+        state.apply_positions(vec![position("2", "100", 10)], true);
+
+        // balance_and_position 的推送比 positions 更新
+        state.apply_balance_and_position(BalanceAndPosition {
+            p_time: 12,
+            event_type: "filled".to_string(),
+            balances: vec![CashBalance {
+                ccy: "USDT".to_string(),
+                cash_bal: dec("900"),
+                u_time: 12,
+            }],
+            positions: vec![Position {
+                lever: None,
+                ..position("3", "101", 12)
+            }],
+        });
+        // 较旧的推送不会覆盖
+        state.apply_positions(vec![position("2", "100", 11)], false);
+
+        let p = &state.positions["1"];
+        assert_eq!(p.pos, dec("3"));
+        assert_eq!(p.avg_px, Some(dec("101")));
+        // 只更新数量和均价
+        assert_eq!(p.lever, Some(dec("5")));
+        assert_eq!(state.balance("USDT").unwrap().cash_bal, dec("900"));
+        assert_eq!(state.u_time, 12);
+
+        state.apply_positions(vec![position("0", "0", 13)], false);
+        assert!(state.positions_of("BTC-USDT-SWAP").is_empty());
+    }
+}
@@ -0,0 +1,371 @@
+use bigdecimal::{BigDecimal, Zero};
+use serde::{Deserialize, Serialize};
+
+use crate::model::order::{PosSide, Side, TdMode, check_price, check_size};
+use crate::model::symbol::{Category, State, Symbol};
+
+/// 策略委托类型
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlgoOrderType {
+    /// 单向止盈止损
+    Conditional,
+    /// 双向止盈止损
+    Oco,
+    /// 计划委托
+    Trigger,
+    /// 移动止盈止损
+    MoveOrderStop,
+    /// 时间加权
+    Twap,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl AlgoOrderType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlgoOrderType::Conditional => "conditional",
+            AlgoOrderType::Oco => "oco",
+            AlgoOrderType::Trigger => "trigger",
+            AlgoOrderType::MoveOrderStop => "move_order_stop",
+            AlgoOrderType::Twap => "twap",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<AlgoOrderType> {
+        match s {
+            "conditional" => Some(AlgoOrderType::Conditional),
+            "oco" => Some(AlgoOrderType::Oco),
+            "trigger" => Some(AlgoOrderType::Trigger),
+            "move_order_stop" => Some(AlgoOrderType::MoveOrderStop),
+            "twap" => Some(AlgoOrderType::Twap),
+            _ => None,
+        }
+    }
+}
+
+/// 策略委托状态
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlgoState {
+    /// 待生效
+    Live,
+    /// 暂停生效
+    Pause,
+    /// 部分生效
+    PartiallyEffective,
+    /// 已生效
+    Effective,
+    Canceled,
+    /// 委托失败
+    OrderFailed,
+    /// 部分委托失败
+    PartiallyFailed,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl AlgoState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlgoState::Live => "live",
+            AlgoState::Pause => "pause",
+            AlgoState::PartiallyEffective => "partially_effective",
+            AlgoState::Effective => "effective",
+            AlgoState::Canceled => "canceled",
+            AlgoState::OrderFailed => "order_failed",
+            AlgoState::PartiallyFailed => "partially_failed",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<AlgoState> {
+        match s {
+            "live" => Some(AlgoState::Live),
+            "pause" => Some(AlgoState::Pause),
+            "partially_effective" => Some(AlgoState::PartiallyEffective),
+            "effective" => Some(AlgoState::Effective),
+            "canceled" => Some(AlgoState::Canceled),
+            "order_failed" => Some(AlgoState::OrderFailed),
+            "partially_failed" => Some(AlgoState::PartiallyFailed),
+            _ => None,
+        }
+    }
+}
+
+/// 触发价格类型
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TriggerPxType {
+    /// 最新价格，默认
+    Last,
+    /// 指数价格
+    Index,
+    /// 标记价格
+    Mark,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl TriggerPxType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TriggerPxType::Last => "last",
+            TriggerPxType::Index => "index",
+            TriggerPxType::Mark => "mark",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<TriggerPxType> {
+        match s {
+            "last" => Some(TriggerPxType::Last),
+            "index" => Some(TriggerPxType::Index),
+            "mark" => Some(TriggerPxType::Mark),
+            _ => None,
+        }
+    }
+}
+
+/// 触发后的委托价格
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum OrderPx {
+    /// 市价，OKX 中为 "-1"
+    Market,
+    Limit(BigDecimal),
+}
+
+impl OrderPx {
+    pub fn to_param(&self) -> String {
+        match self {
+            OrderPx::Market => "-1".to_string(),
+            OrderPx::Limit(px) => px.to_plain_string(),
+        }
+    }
+}
+
+/// 止盈或止损
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TpSl {
+    pub trigger_px: BigDecimal,
+    pub order_px: OrderPx,
+    pub trigger_px_type: Option<TriggerPxType>,
+}
+
+impl TpSl {
+    /// 触发后以市价委托
+    pub fn market(trigger_px: BigDecimal) -> Self {
+        TpSl {
+            trigger_px,
+            order_px: OrderPx::Market,
+            trigger_px_type: None,
+        }
+    }
+}
+
+/// 价格距离，按比例或按价差
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum PxDistance {
+    /// 比例，如 0.01 表示 1%
+    Ratio(BigDecimal),
+    /// 价差
+    Spread(BigDecimal),
+}
+
+/// 各类策略委托的参数
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum AlgoParams {
+    /// 单向止盈止损，tp 和 sl 至少一个
+    Conditional { tp: Option<TpSl>, sl: Option<TpSl> },
+    /// 双向止盈止损，一个触发后另一个自动撤销
+    Oco { tp: TpSl, sl: TpSl },
+    /// 计划委托，价格达到 trigger_px 后按 order_px 下单
+    Trigger(TpSl),
+    /// 移动止盈止损，价格从最高/最低点回调 callback 后以市价委托
+    /// active_px 为激活价格，不传则立即激活
+    MoveOrderStop { callback: PxDistance, active_px: Option<BigDecimal> },
+    /// 时间加权，每隔 time_interval 秒下一笔不超过 sz_limit 的单
+    /// 价格距离盘口 px_variance，买单不高于 px_limit，卖单不低于 px_limit
+    Twap {
+        px_variance: PxDistance,
+        sz_limit: BigDecimal,
+        px_limit: BigDecimal,
+        time_interval: u64,
+    },
+}
+
+impl AlgoParams {
+    pub fn ord_type(&self) -> AlgoOrderType {
+        match self {
+            AlgoParams::Conditional { .. } => AlgoOrderType::Conditional,
+            AlgoParams::Oco { .. } => AlgoOrderType::Oco,
+            AlgoParams::Trigger(_) => AlgoOrderType::Trigger,
+            AlgoParams::MoveOrderStop { .. } => AlgoOrderType::MoveOrderStop,
+            AlgoParams::Twap { .. } => AlgoOrderType::Twap,
+        }
+    }
+}
+
+/// 策略委托下单请求
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AlgoOrderRequest {
+    pub inst_id: String,
+    pub td_mode: TdMode,
+    pub side: Side,
+    pub pos_side: Option<PosSide>,
+    pub sz: BigDecimal,
+    pub reduce_only: Option<bool>,
+    /// 自定义策略订单 ID
+    pub algo_cl_ord_id: Option<String>,
+    pub params: AlgoParams,
+}
+
+impl AlgoOrderRequest {
+    pub fn new(inst_id: &str, td_mode: TdMode, side: Side, sz: BigDecimal, params: AlgoParams) -> Self {
+        AlgoOrderRequest {
+            inst_id: inst_id.to_string(),
+            td_mode,
+            side,
+            pos_side: None,
+            sz,
+            reduce_only: None,
+            algo_cl_ord_id: None,
+            params,
+        }
+    }
+
+    /// 按产品的交易规则检查数量和各个价格
+    pub fn validate(&self, symbol: &Symbol) -> Result<(), String> {
+        if self.inst_id != symbol.inst_id {
+            return Err(format!("order is for {} but rules are for {}", self.inst_id, symbol.inst_id));
+        }
+        if symbol.state != State::Live {
+            return Err(format!("instrument state is {}", symbol.state.as_str()));
+        }
+        check_size(&self.sz, symbol)?;
+        let check_tp_sl = |tp_sl: &TpSl| -> Result<(), String> {
+            check_price(&tp_sl.trigger_px, symbol)?;
+            match &tp_sl.order_px {
+                OrderPx::Limit(px) => check_price(px, symbol),
+                OrderPx::Market => Ok(()),
+            }
+        };
+        let check_distance = |distance: &PxDistance| -> Result<(), String> {
+            match distance {
+                PxDistance::Ratio(ratio) if *ratio <= BigDecimal::zero() => Err(format!("ratio {} must be positive", ratio)),
+                PxDistance::Ratio(_) => Ok(()),
+                PxDistance::Spread(spread) => check_price(spread, symbol),
+            }
+        };
+        match &self.params {
+            AlgoParams::Conditional { tp: None, sl: None } => Err("either tp or sl is required".to_string()),
+            AlgoParams::Conditional { tp, sl } => tp.iter().chain(sl.iter()).try_for_each(check_tp_sl),
+            AlgoParams::Oco { tp, sl } => check_tp_sl(tp).and_then(|_| check_tp_sl(sl)),
+            AlgoParams::Trigger(trigger) => check_tp_sl(trigger),
+            AlgoParams::MoveOrderStop { callback, active_px } => {
+                check_distance(callback)?;
+                active_px.as_ref().map_or(Ok(()), |px| check_price(px, symbol))
+            }
+            AlgoParams::Twap {
+                px_variance,
+                sz_limit,
+                px_limit,
+                time_interval,
+            } => {
+                check_distance(px_variance)?;
+                check_size(sz_limit, symbol)?;
+                check_price(px_limit, symbol)?;
+                if *time_interval == 0 {
+                    return Err("time_interval must be positive".to_string());
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// 撤销策略委托
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CancelAlgoRequest {
+    pub inst_id: String,
+    pub algo_id: String,
+}
+
+/// 策略委托下单和撤单的回执
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AlgoAck {
+    pub algo_id: String,
+    pub algo_cl_ord_id: Option<String>,
+    pub s_code: String,
+    pub s_msg: String,
+}
+
+impl AlgoAck {
+    pub fn is_success(&self) -> bool {
+        self.s_code == "0"
+    }
+}
+
+/// 查询到的策略委托
+/// 只有对应类型的价格字段有值
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AlgoOrder {
+    pub algo_id: String,
+    pub algo_cl_ord_id: Option<String>,
+    pub inst_id: String,
+    pub category: Category,
+    pub ord_type: AlgoOrderType,
+    pub side: Side,
+    pub pos_side: Option<PosSide>,
+    pub td_mode: TdMode,
+    pub sz: BigDecimal,
+    pub state: AlgoState,
+    pub tp_trigger_px: Option<BigDecimal>,
+    /// 止盈委托价，市价为 -1
+    pub tp_ord_px: Option<BigDecimal>,
+    pub sl_trigger_px: Option<BigDecimal>,
+    pub sl_ord_px: Option<BigDecimal>,
+    pub trigger_px: Option<BigDecimal>,
+    pub ord_px: Option<BigDecimal>,
+    pub callback_ratio: Option<BigDecimal>,
+    pub callback_spread: Option<BigDecimal>,
+    pub active_px: Option<BigDecimal>,
+    /// 触发后生成的订单 ID
+    pub ord_id: Option<String>,
+    pub c_time: u64,
+    pub u_time: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::{self, dec};
+
+    fn swap() -> Symbol {
+        fixture::swap("BTC-USDT-SWAP", "0.01", "0.01")
+    }
+
+    #[test]
+    fn test_validate_algo_order() {
+        let symbol = swap();
+        let request = |params| AlgoOrderRequest::new("BTC-USDT-SWAP", TdMode::Cross, Side::Sell, dec("1"), params);
+
+        let valid = [
+            AlgoParams::Conditional { tp: None, sl: Some(TpSl::market(dec("25000"))) },
+            AlgoParams::Oco { tp: TpSl::market(dec("35000")), sl: TpSl::market(dec("25000")) },
+            AlgoParams::MoveOrderStop { callback: PxDistance::Ratio(dec("0.01")), active_px: None },
+        ];
+        for params in valid {
+            assert_eq!(request(params).validate(&symbol), Ok(()));
+        }
+
+        let invalid = [
+            AlgoParams::Conditional { tp: None, sl: None },
+            AlgoParams::Trigger(TpSl {
+                trigger_px: dec("30000"),
+                order_px: OrderPx::Limit(dec("30000.05")),
+                trigger_px_type: None,
+            }),
+            AlgoParams::MoveOrderStop { callback: PxDistance::Ratio(dec("0")), active_px: None },
+            AlgoParams::Twap {
+                px_variance: PxDistance::Spread(dec("1")),
+                sz_limit: dec("0.001"),
+                px_limit: dec("30000"),
+                time_interval: 10,
+            },
+        ];
+        for params in invalid {
+            assert!(request(params.clone()).validate(&symbol).is_err(), "{:?} should be rejected", params);
+        }
+    }
+}
@@ -0,0 +1,262 @@
+use bigdecimal::{BigDecimal, Zero};
+
+use crate::model::order::Side;
+use crate::model::order_book::{Level, OrderBook};
+use crate::model::symbol::{Symbol, Ticker};
+
+/// 现货-合约套利方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// 合约高于现货：买入现货，做空合约
+    CashAndCarry,
+    /// 合约低于现货：借币卖出现货，做多合约
+    ReverseCarry,
+}
+
+impl Direction {
+    /// 开仓时现货的方向，合约相反
+    pub fn spot_side(&self) -> Side {
+        match self {
+            Direction::CashAndCarry => Side::Buy,
+            Direction::ReverseCarry => Side::Sell,
+        }
+    }
+}
+
+/// 按盘口计算的现货-合约基差
+/// 最新成交价可能是很久以前的成交，只有按要吃掉的一侧计算的基差才能成交
+#[derive(Debug, Clone, PartialEq)]
+pub struct Basis {
+    pub spot_bid: BigDecimal,
+    pub spot_ask: BigDecimal,
+    pub swap_bid: BigDecimal,
+    pub swap_ask: BigDecimal,
+    /// 买现货、卖合约的基差率，(合约买一 - 现货卖一) / 现货卖一
+    pub carry: BigDecimal,
+    /// 卖现货、买合约的基差率，(合约卖一 - 现货买一) / 现货买一
+    pub reverse: BigDecimal,
+}
+
+impl Basis {
+    /// 任意一个价格不为正时返回 None
+    pub fn new(spot_bid: BigDecimal, spot_ask: BigDecimal, swap_bid: BigDecimal, swap_ask: BigDecimal) -> Option<Basis> {
+        if [&spot_bid, &spot_ask, &swap_bid, &swap_ask].iter().any(|px| **px <= BigDecimal::zero()) {
+            return None;
+        }
+        let carry = (&swap_bid - &spot_ask) / &spot_ask;
+        let reverse = (&swap_ask - &spot_bid) / &spot_bid;
+        Some(Basis {
+            spot_bid,
+            spot_ask,
+            swap_bid,
+            swap_ask,
+            carry,
+            reverse,
+        })
+    }
+
+    /// 盘口为空时返回 None
+    pub fn from_tickers(spot: &Ticker, swap: &Ticker) -> Option<Basis> {
+        Basis::new(
+            spot.bid_px.clone()?,
+            spot.ask_px.clone()?,
+            swap.bid_px.clone()?,
+            swap.ask_px.clone()?,
+        )
+    }
+
+    /// 基差率超过 threshold 的方向和可成交的基差率
+    pub fn opportunity(&self, threshold: &BigDecimal) -> Option<(Direction, &BigDecimal)> {
+        if self.carry > *threshold {
+            Some((Direction::CashAndCarry, &self.carry))
+        } else if self.reverse < -threshold.clone() {
+            Some((Direction::ReverseCarry, &self.reverse))
+        } else {
+            None
+        }
+    }
+
+    /// 平掉 direction 方向的仓位时可成交的基差率，平仓按反方向吃盘口
+    pub fn close_rate(&self, direction: Direction) -> &BigDecimal {
+        match direction {
+            Direction::CashAndCarry => &self.reverse,
+            Direction::ReverseCarry => &self.carry,
+        }
+    }
+
+    /// direction 方向开仓时现货的成交价
+    pub fn spot_px(&self, direction: Direction) -> &BigDecimal {
+        match direction {
+            Direction::CashAndCarry => &self.spot_ask,
+            Direction::ReverseCarry => &self.spot_bid,
+        }
+    }
+
+    /// direction 方向开仓时合约的成交价
+    pub fn swap_px(&self, direction: Direction) -> &BigDecimal {
+        match direction {
+            Direction::CashAndCarry => &self.swap_bid,
+            Direction::ReverseCarry => &self.swap_ask,
+        }
+    }
+}
+
+/// 按深度计算的可成交规模
+#[derive(Debug, Clone, PartialEq)]
+pub struct DepthBasis {
+    /// 扣除手续费后基差率仍不低于阈值的最大数量，单位为币
+    pub size: BigDecimal,
+    /// 两条腿吃掉 size 的成交均价，合约价格已换算为现货的计价币
+    pub spot_vwap: BigDecimal,
+    pub swap_vwap: BigDecimal,
+    /// 按成交均价计算、扣除手续费后的收益率
+    pub net_rate: BigDecimal,
+    /// 按 size 成交扣除手续费后的预期收益，以现货的计价币计
+    pub profit: BigDecimal,
+}
+
+/// 同时吃两边深度，找出按成交均价扣除 fee 后基差率不低于 threshold 的最大数量
+/// fee 为两条腿合计的手续费率；rate 把合约价格换算为现货的计价币，见 QuotePath
+/// 合约的张数用 swap_symbol 换算为币，币本位合约按每一档的价格换算
+/// 第一档就达不到阈值时返回 None
+pub fn depth_basis(
+    spot: &OrderBook,
+    swap: &OrderBook,
+    swap_symbol: &Symbol,
+    direction: Direction,
+    rate: &BigDecimal,
+    fee: &BigDecimal,
+    threshold: &BigDecimal,
+) -> Option<DepthBasis> {
+    let (spot_levels, swap_levels): (Vec<&Level>, Vec<&Level>) = match direction {
+        Direction::CashAndCarry => (spot.asks().collect(), swap.bids().collect()),
+        Direction::ReverseCarry => (spot.bids().collect(), swap.asks().collect()),
+    };
+    let spot_levels: Vec<(BigDecimal, BigDecimal)> = spot_levels.into_iter().map(|l| (l.px.clone(), l.sz.clone())).collect();
+    let swap_levels: Vec<(BigDecimal, BigDecimal)> = swap_levels
+        .into_iter()
+        .filter_map(|l| Some((&l.px * rate, swap_symbol.contracts_to_coin(&l.sz, &l.px)?)))
+        .collect();
+    // 买现货、卖合约时收益为 合约 - 现货，反向时相反
+    let sign = match direction {
+        Direction::CashAndCarry => BigDecimal::from(1),
+        Direction::ReverseCarry => BigDecimal::from(-1),
+    };
+    let k = threshold + fee;
+
+    let (mut size, mut spot_notional, mut swap_notional) = (BigDecimal::zero(), BigDecimal::zero(), BigDecimal::zero());
+    let (mut i, mut j) = (0, 0);
+    let mut spot_left = spot_levels.first().map(|l| l.1.clone()).unwrap_or_default();
+    let mut swap_left = swap_levels.first().map(|l| l.1.clone()).unwrap_or_default();
+    while i < spot_levels.len() && j < swap_levels.len() {
+        let (p, s) = (&spot_levels[i].0, &swap_levels[j].0);
+        let q = if spot_left < swap_left { spot_left.clone() } else { swap_left.clone() };
+        // 收益率不低于 k 等价于 sign*(W-S) - k*S >= 0，在当前档加入 x 后左边变化 x*(sign*(s-p) - k*p)
+        let slack = &sign * (&swap_notional - &spot_notional) - &k * &spot_notional;
+        let marginal = &sign * (s - p) - &k * p;
+        let take = if marginal >= BigDecimal::zero() {
+            q.clone()
+        } else {
+            let max = slack / -marginal;
+            if max < q { max } else { q.clone() }
+        };
+        if take <= BigDecimal::zero() {
+            break;
+        }
+        spot_notional += &take * p;
+        swap_notional += &take * s;
+        size += &take;
+        if take < q {
+            break;
+        }
+        spot_left -= &q;
+        swap_left -= &q;
+        if spot_left.is_zero() {
+            i += 1;
+            spot_left = spot_levels.get(i).map(|l| l.1.clone()).unwrap_or_default();
+        }
+        if swap_left.is_zero() {
+            j += 1;
+            swap_left = swap_levels.get(j).map(|l| l.1.clone()).unwrap_or_default();
+        }
+    }
+    if size.is_zero() {
+        return None;
+    }
+    let gross = &sign * (&swap_notional - &spot_notional);
+    Some(DepthBasis {
+        spot_vwap: &spot_notional / &size,
+        swap_vwap: &swap_notional / &size,
+        net_rate: &gross / &spot_notional - fee,
+        profit: gross - fee * &spot_notional,
+        size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::{self, dec};
+    use crate::model::order_book::BookUpdate;
+
+    fn book(inst_id: &str, bids: &[(&str, &str)], asks: &[(&str, &str)]) -> OrderBook {
+        let levels = |levels: &[(&str, &str)]| levels.iter().map(|(px, sz)| Level::parse(px, sz, 1).unwrap()).collect();
+        let mut book = OrderBook::new(inst_id);
+        book.apply_snapshot(BookUpdate {
+            bids: levels(bids),
+            asks: levels(asks),
+            ..Default::default()
+        })
+        .unwrap();
+        book
+    }
+
+    fn swap_symbol() -> Symbol {
+        fixture::swap("BTC-USDT-SWAP", "1", "0.1")
+    }
+
+    #[test]
+    fn test_executable_basis() {
+        let basis = Basis::new(dec("99"), dec("100"), dec("102"), dec("103")).unwrap();
+        assert_eq!(basis.carry, dec("0.02"));
+        assert_eq!(basis.reverse, dec("4") / dec("99"));
+        assert_eq!(basis.opportunity(&dec("0.01")), Some((Direction::CashAndCarry, &dec("0.02"))));
+        assert_eq!(basis.opportunity(&dec("0.02")), None);
+        assert_eq!(basis.spot_px(Direction::CashAndCarry), &dec("100"));
+        assert_eq!(basis.close_rate(Direction::CashAndCarry), &basis.reverse);
+
+        // 合约卖一低于现货买一
+        let basis = Basis::new(dec("100"), dec("101"), dec("97"), dec("98")).unwrap();
+        assert_eq!(basis.reverse, dec("-0.02"));
+        assert_eq!(basis.opportunity(&dec("0.01")), Some((Direction::ReverseCarry, &dec("-0.02"))));
+        assert_eq!(basis.swap_px(Direction::ReverseCarry), &dec("98"));
+
+        // 价差小于买卖价差时两个方向都无法成交
+        let basis = Basis::new(dec("99"), dec("101"), dec("100"), dec("102")).unwrap();
+        assert!(basis.carry < BigDecimal::zero() && basis.reverse > BigDecimal::zero());
+        assert_eq!(basis.opportunity(&BigDecimal::zero()), None);
+
+        assert!(Basis::new(dec("0"), dec("100"), dec("100"), dec("100")).is_none());
+    }
+
+    #[test]
+    fn test_depth_basis() {
+        let spot = book("BTC-USDT", &[("99", "5")], &[("100", "1"), ("101", "1"), ("103", "10")]);
+        // 合约面值 0.1，10 张为 1 个币
+        let swap = book("BTC-USDT-SWAP", &[("103", "10"), ("102.5", "10"), ("101", "100")], &[("104", "50")]);
+        let (one, fee, threshold) = (dec("1"), dec("0.002"), dec("0.005"));
+
+        let depth = depth_basis(&spot, &swap, &swap_symbol(), Direction::CashAndCarry, &one, &fee, &threshold).unwrap();
+        // 前两档全部成交，第三档每多成交一个币收益减少，只能成交到扣费后收益率正好等于阈值
+        assert_eq!(depth.size.round(4), dec("3.1367"));
+        assert_eq!(depth.net_rate.round(10), dec("0.005"));
+        assert_eq!(depth.profit.round(10), (&depth.spot_vwap * &depth.size * dec("0.005")).round(10));
+        assert!(depth.spot_vwap > dec("100") && depth.swap_vwap < dec("103"));
+
+        // 第一档扣费后也达不到阈值
+        assert!(depth_basis(&spot, &swap, &swap_symbol(), Direction::CashAndCarry, &one, &fee, &dec("0.03")).is_none());
+
+        // 反向：卖现货吃买盘 99，买合约吃卖盘 104，没有收益
+        assert!(depth_basis(&spot, &swap, &swap_symbol(), Direction::ReverseCarry, &one, &fee, &threshold).is_none());
+    }
+}
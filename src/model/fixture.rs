@@ -0,0 +1,112 @@
+//! 测试用的交易规则和行情，交易货币和计价币从 instId 中解析
+//! 需要其他取值时用结构体更新语法覆盖对应字段
+
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, Zero};
+
+use crate::model::symbol::{Category, ContractType, FutureAlias, IndexTicker, RuleType, State, Symbol, Ticker};
+
+/// 从字符串构造 BigDecimal
+pub fn dec(s: &str) -> BigDecimal {
+    BigDecimal::from_str(s).unwrap()
+}
+
+fn split(inst_id: &str) -> (String, String) {
+    let mut parts = inst_id.split('-');
+    let base = parts.next().unwrap_or_default().to_string();
+    let quote = parts.next().unwrap_or_default().to_string();
+    (base, quote)
+}
+
+/// 币币，lotSz 和 minSz 相同，tickSz 为 0.1
+pub fn spot(inst_id: &str, lot_sz: &str) -> Symbol {
+    let (base, quote) = split(inst_id);
+    Symbol {
+        inst_id: inst_id.to_string(),
+        base,
+        quote,
+        category: Category::Spot,
+        list_time: 0,
+        exp_time: 0,
+        state: State::Live,
+        inst_family: String::new(),
+        settle_ccy: None,
+        tick_sz: dec("0.1"),
+        lot_sz: dec(lot_sz),
+        min_sz: dec(lot_sz),
+        ct_val: None,
+        ct_mult: None,
+        ct_val_ccy: None,
+        ct_type: None,
+        lever: None,
+        max_lmt_sz: None,
+        max_mkt_sz: None,
+        rule_type: RuleType::Normal,
+        open_type: None,
+        opt_type: None,
+        stk: None,
+        alias: None,
+    }
+}
+
+/// 以计价币结算的正向永续，每张 ct_val 个交易货币
+pub fn swap(inst_id: &str, lot_sz: &str, ct_val: &str) -> Symbol {
+    let spot = spot(inst_id, lot_sz);
+    Symbol {
+        category: Category::Swap,
+        inst_family: format!("{}-{}", spot.base, spot.quote),
+        settle_ccy: Some(spot.quote.clone()),
+        ct_val: Some(dec(ct_val)),
+        ct_mult: Some(dec("1")),
+        ct_val_ccy: Some(spot.base.clone()),
+        ct_type: Some(ContractType::Linear),
+        ..spot
+    }
+}
+
+/// 每张 0.01 个交易货币、lotSz 为 1 的正向交割合约
+pub fn future(inst_id: &str, alias: FutureAlias, exp_time: u64) -> Symbol {
+    Symbol {
+        category: Category::Future,
+        exp_time,
+        alias: Some(alias),
+        ..swap(inst_id, "1", "0.01")
+    }
+}
+
+/// 买一卖一各 1 个的行情，最新成交价和开盘价取买一价
+pub fn ticker(inst_id: &str, bid: &str, ask: &str) -> Ticker {
+    let (base, quote) = split(inst_id);
+    Ticker {
+        inst_id: inst_id.to_string(),
+        base,
+        quote,
+        last: dec(bid),
+        last_sz: dec("1"),
+        bid_px: Some(dec(bid)),
+        bid_sz: Some(dec("1")),
+        ask_px: Some(dec(ask)),
+        ask_sz: Some(dec("1")),
+        open_24h: dec(bid),
+        high_24h: dec(ask),
+        low_24h: dec(bid),
+        vol_24h: BigDecimal::zero(),
+        vol_ccy_24h: BigDecimal::zero(),
+        sod_utc0: dec(bid),
+        sod_utc8: dec(bid),
+        ts: 0,
+    }
+}
+
+/// 开盘价和最高最低价都等于 px 的指数行情
+pub fn index(inst_id: &str, px: &str) -> IndexTicker {
+    IndexTicker {
+        inst_id: inst_id.to_string(),
+        idx_px: dec(px),
+        open_24h: dec(px),
+        high_24h: dec(px),
+        low_24h: dec(px),
+        ts: 0,
+    }
+}
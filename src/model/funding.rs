@@ -0,0 +1,135 @@
+use bigdecimal::{BigDecimal, Zero};
+use serde::{Deserialize, Serialize};
+
+use crate::model::basis::Direction;
+
+/// 没有下一期收取时间时按 8 小时结算一次
+const DEFAULT_INTERVAL_MS: u64 = 8 * 3600 * 1000;
+const YEAR_MS: u64 = 365 * 24 * 3600 * 1000;
+
+/// 永续合约当前的资金费率
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FundingRate {
+    pub inst_id: String,
+    /// 本期资金费率，在 funding_time 收取
+    pub funding_rate: BigDecimal,
+    /// 下一期预测的资金费率，部分合约不提供
+    pub next_funding_rate: Option<BigDecimal>,
+    /// 本期资金费的收取时间
+    pub funding_time: u64,
+    /// 下一期资金费的收取时间
+    pub next_funding_time: u64,
+    /// 资金费率的上下限
+    pub min_funding_rate: Option<BigDecimal>,
+    pub max_funding_rate: Option<BigDecimal>,
+    pub ts: u64,
+}
+
+impl FundingRate {
+    /// 资金费的结算间隔，由本期和下期的收取时间计算
+    pub fn interval_ms(&self) -> u64 {
+        match self.next_funding_time.saturating_sub(self.funding_time) {
+            0 => DEFAULT_INTERVAL_MS,
+            interval => interval,
+        }
+    }
+
+    /// 一年的结算次数
+    pub fn periods_per_year(&self) -> BigDecimal {
+        BigDecimal::from(YEAR_MS) / BigDecimal::from(self.interval_ms())
+    }
+
+    /// 持有期间预计的每期费率，有预测值时取本期和下期的平均
+    pub fn expected_rate(&self) -> BigDecimal {
+        match &self.next_funding_rate {
+            Some(next) => (&self.funding_rate + next) / BigDecimal::from(2),
+            None => self.funding_rate.clone(),
+        }
+    }
+
+    /// 按 expected_rate 年化的资金费收益
+    /// 做空合约在费率为正时收取资金费，做多合约在费率为负时收取
+    pub fn annualized_income(&self, direction: Direction) -> BigDecimal {
+        let annual = self.expected_rate() * self.periods_per_year();
+        match direction {
+            Direction::CashAndCarry => annual,
+            Direction::ReverseCarry => -annual,
+        }
+    }
+}
+
+/// 历史资金费率
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FundingRateHistory {
+    pub inst_id: String,
+    pub funding_rate: BigDecimal,
+    /// 实际收取的资金费率
+    pub realized_rate: BigDecimal,
+    pub funding_time: u64,
+}
+
+/// 现货-永续套利的年化预期收益
+/// exec_rate 为开仓时可成交的基差率，假设在 hold_days 天内收敛到 0，期间按 funding 收取资金费
+/// 没有资金费率时只计算基差收敛的收益
+pub fn annualized_carry(direction: Direction, exec_rate: &BigDecimal, funding: Option<&FundingRate>, hold_days: u32) -> BigDecimal {
+    let basis = if hold_days == 0 {
+        BigDecimal::zero()
+    } else {
+        exec_rate.abs() * BigDecimal::from(365) / BigDecimal::from(hold_days)
+    };
+    match funding {
+        Some(funding) => basis + funding.annualized_income(direction),
+        None => basis,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::dec;
+
+    fn funding(rate: &str, next: Option<&str>, interval_hours: u64) -> FundingRate {
+        FundingRate {
+            inst_id: "BTC-USDT-SWAP".to_string(),
+            funding_rate: dec(rate),
+            next_funding_rate: next.map(dec),
+            funding_time: 1_700_000_000_000,
+            next_funding_time: 1_700_000_000_000 + interval_hours * 3600 * 1000,
+            min_funding_rate: None,
+            max_funding_rate: None,
+            ts: 0,
+        }
+    }
+
+    #[test]
+    fn test_annualized_funding() {
+        // 8 小时一次，一年 1095 次
+        let f = funding("0.0001", None, 8);
+        assert_eq!(f.periods_per_year(), dec("1095"));
+        assert_eq!(f.annualized_income(Direction::CashAndCarry), dec("0.1095"));
+        assert_eq!(f.annualized_income(Direction::ReverseCarry), dec("-0.1095"));
+
+        // 4 小时一次，取本期和预测的平均
+        let f = funding("0.0001", Some("0.0003"), 4);
+        assert_eq!(f.expected_rate(), dec("0.0002"));
+        assert_eq!(f.annualized_income(Direction::CashAndCarry), dec("0.438"));
+
+        let f = FundingRate {
+            next_funding_time: 0,
+            ..funding("0.0001", None, 8)
+        };
+        assert_eq!(f.interval_ms(), DEFAULT_INTERVAL_MS);
+    }
+
+    #[test]
+    fn test_annualized_carry() {
+        let f = funding("0.0001", None, 8);
+        // 1% 的基差 7 天收敛，年化 52.14%，再加上资金费
+        let carry = annualized_carry(Direction::CashAndCarry, &dec("0.01"), Some(&f), 7);
+        assert_eq!(carry.round(4), dec("0.6309"));
+        // 反向套利做多合约，需要支付资金费
+        let carry = annualized_carry(Direction::ReverseCarry, &dec("-0.01"), Some(&f), 7);
+        assert_eq!(carry.round(4), dec("0.4119"));
+        assert_eq!(annualized_carry(Direction::CashAndCarry, &dec("0.01"), None, 365), dec("0.01"));
+    }
+}
@@ -0,0 +1,257 @@
+use std::fmt;
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use chrono::NaiveDate;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::model::symbol::{Category, OptionType};
+
+/// 产品 ID 中除 base/quote 以外的部分
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum InstKind {
+    /// 币币/杠杆，如 BTC-USDT
+    Spot,
+    /// 永续，如 BTC-USDT-SWAP
+    Swap,
+    /// 交割，如 BTC-USDT-250328
+    Future { expiry: NaiveDate },
+    /// 期权，如 BTC-USD-250328-60000-C
+    Option {
+        expiry: NaiveDate,
+        strike: BigDecimal,
+        opt_type: OptionType,
+    },
+}
+
+/// 解析后的产品 ID
+/// 衍生品的 quote 为 BTC-USD-SWAP 中的 USD，USD 表示币本位（反向），USDT/USDC 表示 U 本位（正向）
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InstId {
+    pub base: String,
+    pub quote: String,
+    pub kind: InstKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstIdError(pub String);
+
+impl fmt::Display for InstIdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid inst id: {}", self.0)
+    }
+}
+
+impl std::error::Error for InstIdError {}
+
+/// 交割/期权 ID 中的日期格式，如 250328
+const EXPIRY_FORMAT: &str = "%y%m%d";
+
+impl InstId {
+    pub fn spot(base: &str, quote: &str) -> Self {
+        InstId {
+            base: base.to_string(),
+            quote: quote.to_string(),
+            kind: InstKind::Spot,
+        }
+    }
+
+    pub fn swap(base: &str, quote: &str) -> Self {
+        InstId {
+            base: base.to_string(),
+            quote: quote.to_string(),
+            kind: InstKind::Swap,
+        }
+    }
+
+    pub fn future(base: &str, quote: &str, expiry: NaiveDate) -> Self {
+        InstId {
+            base: base.to_string(),
+            quote: quote.to_string(),
+            kind: InstKind::Future { expiry },
+        }
+    }
+
+    /// 产品类别，币币和杠杆的 ID 相同，统一返回 Spot
+    pub fn category(&self) -> Category {
+        match self.kind {
+            InstKind::Spot => Category::Spot,
+            InstKind::Swap => Category::Swap,
+            InstKind::Future { .. } => Category::Future,
+            InstKind::Option { .. } => Category::Option,
+        }
+    }
+
+    /// 交易对，如 BTC-USDT-SWAP 对应 BTC-USDT
+    /// 用于在不同类别之间匹配同一个币对
+    pub fn pair(&self) -> InstId {
+        InstId::spot(&self.base, &self.quote)
+    }
+
+    /// 交易品种，如 BTC-USD-250328-60000-C 对应 BTC-USD
+    pub fn family(&self) -> String {
+        self.pair().to_string()
+    }
+
+    pub fn expiry(&self) -> Option<NaiveDate> {
+        match &self.kind {
+            InstKind::Future { expiry } | InstKind::Option { expiry, .. } => Some(*expiry),
+            _ => None,
+        }
+    }
+
+    pub fn is_derivative(&self) -> bool {
+        self.kind != InstKind::Spot
+    }
+
+    /// 是否为币本位（反向）合约，如 BTC-USD-SWAP
+    pub fn is_inverse(&self) -> bool {
+        self.is_derivative() && self.quote == "USD"
+    }
+
+    /// 保证金币种：币本位合约为 base，U 本位合约和币币为 quote
+    pub fn margin_ccy(&self) -> &str {
+        if self.is_inverse() { &self.base } else { &self.quote }
+    }
+}
+
+impl FromStr for InstId {
+    type Err = InstIdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || InstIdError(s.to_string());
+        let parse_expiry = |d: &str| -> Result<NaiveDate, InstIdError> {
+            if d.len() != 6 {
+                return Err(err());
+            }
+            NaiveDate::parse_from_str(d, EXPIRY_FORMAT).map_err(|_| err())
+        };
+
+        let parts: Vec<&str> = s.split('-').collect();
+        if parts.len() < 2 || parts[0].is_empty() || parts[1].is_empty() {
+            return Err(err());
+        }
+        let (base, quote) = (parts[0], parts[1]);
+        let kind = match parts.len() {
+            2 => InstKind::Spot,
+            3 if parts[2] == "SWAP" => InstKind::Swap,
+            3 => InstKind::Future { expiry: parse_expiry(parts[2])? },
+            5 => InstKind::Option {
+                expiry: parse_expiry(parts[2])?,
+                strike: BigDecimal::from_str(parts[3]).map_err(|_| err())?,
+                opt_type: OptionType::from_str(parts[4]).ok_or_else(err)?,
+            },
+            _ => return Err(err()),
+        };
+        Ok(InstId {
+            base: base.to_string(),
+            quote: quote.to_string(),
+            kind,
+        })
+    }
+}
+
+impl fmt::Display for InstId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            InstKind::Spot => write!(f, "{}-{}", self.base, self.quote),
+            InstKind::Swap => write!(f, "{}-{}-SWAP", self.base, self.quote),
+            InstKind::Future { expiry } => write!(f, "{}-{}-{}", self.base, self.quote, expiry.format(EXPIRY_FORMAT)),
+            InstKind::Option { expiry, strike, opt_type } => write!(
+                f,
+                "{}-{}-{}-{}-{}",
+                self.base,
+                self.quote,
+                expiry.format(EXPIRY_FORMAT),
+                strike,
+                opt_type.as_str()
+            ),
+        }
+    }
+}
+
+impl Serialize for InstId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for InstId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        InstId::from_str(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_format() {
+        let cases = [
+            ("BTC-USDT", InstId::spot("BTC", "USDT")),
+            ("BTC-USDT-SWAP", InstId::swap("BTC", "USDT")),
+            ("BTC-USD-SWAP", InstId::swap("BTC", "USD")),
+            ("BTC-USDT-250328", InstId::future("BTC", "USDT", date(2025, 3, 28))),
+            (
+                "BTC-USD-250328-60000-C",
+                InstId {
+                    base: "BTC".to_string(),
+                    quote: "USD".to_string(),
+                    kind: InstKind::Option {
+                        expiry: date(2025, 3, 28),
+                        strike: BigDecimal::from(60000),
+                        opt_type: OptionType::Call,
+                    },
+                },
+            ),
+        ];
+        for (s, expected) in cases {
+            let id = InstId::from_str(s).unwrap();
+            assert_eq!(id, expected);
+            assert_eq!(id.to_string(), s);
+        }
+    }
+
+    #[test]
+    fn test_category_and_margin() {
+        let inverse = InstId::from_str("BTC-USD-SWAP").unwrap();
+        assert_eq!(inverse.category(), Category::Swap);
+        assert!(inverse.is_inverse());
+        assert_eq!(inverse.margin_ccy(), "BTC");
+        assert_eq!(inverse.family(), "BTC-USD");
+
+        let linear = InstId::from_str("ETH-USDC-SWAP").unwrap();
+        assert!(!linear.is_inverse());
+        assert_eq!(linear.margin_ccy(), "USDC");
+        assert_eq!(linear.pair(), InstId::spot("ETH", "USDC"));
+
+        let spot = InstId::from_str("BTC-USD").unwrap();
+        assert!(!spot.is_inverse());
+        assert_eq!(spot.expiry(), None);
+
+        let future = InstId::from_str("BTC-USD-250328").unwrap();
+        assert_eq!(future.category(), Category::Future);
+        assert_eq!(future.expiry(), Some(date(2025, 3, 28)));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for s in ["", "BTC", "BTC-", "BTC-USDT-PERP", "BTC-USDT-2503", "BTC-USD-250328-60000-X", "BTC-USD-250328-abc-C", "A-B-C-D"] {
+            assert!(InstId::from_str(s).is_err(), "{} should be invalid", s);
+        }
+    }
+
+    #[test]
+    fn test_serde_as_string() {
+        let id = InstId::from_str("BTC-USDT-SWAP").unwrap();
+        assert_eq!(serde_json::to_string(&id).unwrap(), "\"BTC-USDT-SWAP\"");
+        let back: InstId = serde_json::from_str("\"BTC-USDT-SWAP\"").unwrap();
+        assert_eq!(back, id);
+    }
+}
@@ -1 +1,15 @@
-pub mod symbol;
\ No newline at end of file
+pub mod account;
+pub mod algo;
+pub mod basis;
+pub mod funding;
+pub mod inst_id;
+pub mod order;
+pub mod order_book;
+pub mod quote;
+pub mod spread;
+pub mod symbol;
+pub mod term;
+pub mod triangle;
+
+#[cfg(test)]
+pub(crate) mod fixture;
@@ -0,0 +1,503 @@
+use bigdecimal::{BigDecimal, Zero};
+use serde::{Deserialize, Serialize};
+
+use crate::model::symbol::{Category, State, Symbol};
+
+/// 订单方向
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl Side {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Side::Buy => "buy",
+            Side::Sell => "sell",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<Side> {
+        match s {
+            "buy" => Some(Side::Buy),
+            "sell" => Some(Side::Sell),
+            _ => None,
+        }
+    }
+    pub fn opposite(&self) -> Side {
+        match self {
+            Side::Buy => Side::Sell,
+            Side::Sell => Side::Buy,
+        }
+    }
+}
+
+/// 持仓方向
+/// 买卖模式下为 Net，开平仓模式下为 Long/Short
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PosSide {
+    Long,
+    Short,
+    Net,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl PosSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PosSide::Long => "long",
+            PosSide::Short => "short",
+            PosSide::Net => "net",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<PosSide> {
+        match s {
+            "long" => Some(PosSide::Long),
+            "short" => Some(PosSide::Short),
+            "net" => Some(PosSide::Net),
+            _ => None,
+        }
+    }
+}
+
+/// 交易模式
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TdMode {
+    /// 非保证金，币币交易
+    Cash,
+    /// 全仓
+    Cross,
+    /// 逐仓
+    Isolated,
+    /// 现货逐仓，仅适用于现货带单
+    SpotIsolated,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl TdMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TdMode::Cash => "cash",
+            TdMode::Cross => "cross",
+            TdMode::Isolated => "isolated",
+            TdMode::SpotIsolated => "spot_isolated",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<TdMode> {
+        match s {
+            "cash" => Some(TdMode::Cash),
+            "cross" => Some(TdMode::Cross),
+            "isolated" => Some(TdMode::Isolated),
+            "spot_isolated" => Some(TdMode::SpotIsolated),
+            _ => None,
+        }
+    }
+}
+
+/// 订单类型
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OrderType {
+    Market,
+    Limit,
+    /// 只做 maker 单
+    PostOnly,
+    /// 全部成交或立即取消
+    Fok,
+    /// 立即成交并取消剩余
+    Ioc,
+    /// 市价委托立即成交并取消剩余，仅适用于交割、永续
+    OptimalLimitIoc,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl OrderType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderType::Market => "market",
+            OrderType::Limit => "limit",
+            OrderType::PostOnly => "post_only",
+            OrderType::Fok => "fok",
+            OrderType::Ioc => "ioc",
+            OrderType::OptimalLimitIoc => "optimal_limit_ioc",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<OrderType> {
+        match s {
+            "market" => Some(OrderType::Market),
+            "limit" => Some(OrderType::Limit),
+            "post_only" => Some(OrderType::PostOnly),
+            "fok" => Some(OrderType::Fok),
+            "ioc" => Some(OrderType::Ioc),
+            "optimal_limit_ioc" => Some(OrderType::OptimalLimitIoc),
+            _ => None,
+        }
+    }
+}
+
+/// 市价单 sz 的单位，仅适用于币币市价单
+/// 默认买单为计价货币，卖单为交易货币
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TgtCcy {
+    BaseCcy,
+    QuoteCcy,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl TgtCcy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TgtCcy::BaseCcy => "base_ccy",
+            TgtCcy::QuoteCcy => "quote_ccy",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<TgtCcy> {
+        match s {
+            "base_ccy" => Some(TgtCcy::BaseCcy),
+            "quote_ccy" => Some(TgtCcy::QuoteCcy),
+            _ => None,
+        }
+    }
+}
+
+/// 订单状态
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OrderState {
+    Live,
+    PartiallyFilled,
+    Filled,
+    Canceled,
+    /// 做市商保护机制导致的自动撤单
+    MmpCanceled,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl OrderState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderState::Live => "live",
+            OrderState::PartiallyFilled => "partially_filled",
+            OrderState::Filled => "filled",
+            OrderState::Canceled => "canceled",
+            OrderState::MmpCanceled => "mmp_canceled",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<OrderState> {
+        match s {
+            "live" => Some(OrderState::Live),
+            "partially_filled" => Some(OrderState::PartiallyFilled),
+            "filled" => Some(OrderState::Filled),
+            "canceled" => Some(OrderState::Canceled),
+            "mmp_canceled" => Some(OrderState::MmpCanceled),
+            _ => None,
+        }
+    }
+
+    /// 订单已经结束，不会再有成交
+    pub fn is_final(&self) -> bool {
+        matches!(self, OrderState::Filled | OrderState::Canceled | OrderState::MmpCanceled)
+    }
+}
+
+/// 订单
+/// fill_* 为最近一笔成交，只在有新成交的推送中有值
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Order {
+    pub ord_id: String,
+    pub cl_ord_id: Option<String>,
+    pub inst_id: String,
+    pub category: Category,
+    pub side: Side,
+    pub pos_side: Option<PosSide>,
+    pub td_mode: TdMode,
+    pub ord_type: OrderType,
+    /// 市价单为 None
+    pub px: Option<BigDecimal>,
+    pub sz: BigDecimal,
+    /// 累计成交数量
+    pub acc_fill_sz: BigDecimal,
+    /// 成交均价，没有成交时为 None
+    pub avg_px: Option<BigDecimal>,
+    pub state: OrderState,
+    pub fill_px: Option<BigDecimal>,
+    pub fill_sz: Option<BigDecimal>,
+    pub trade_id: Option<String>,
+    /// 手续费，负数表示扣除
+    pub fee: Option<BigDecimal>,
+    pub fee_ccy: Option<String>,
+    pub c_time: u64,
+    pub u_time: u64,
+}
+
+/// 下单请求
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OrderRequest {
+    pub inst_id: String,
+    pub td_mode: TdMode,
+    pub side: Side,
+    /// 开平仓模式下必填
+    pub pos_side: Option<PosSide>,
+    pub ord_type: OrderType,
+    /// 数量，合约为张数，币币为币的数量，币币市价单的单位见 tgt_ccy
+    pub sz: BigDecimal,
+    /// 价格，市价单和 optimal_limit_ioc 不需要
+    pub px: Option<BigDecimal>,
+    /// 自定义订单 ID，1-32 位字母和数字
+    pub cl_ord_id: Option<String>,
+    /// 只减仓，仅适用于保证金模式
+    pub reduce_only: Option<bool>,
+    pub tgt_ccy: Option<TgtCcy>,
+}
+
+impl OrderRequest {
+    pub fn limit(inst_id: &str, td_mode: TdMode, side: Side, sz: BigDecimal, px: BigDecimal) -> Self {
+        OrderRequest {
+            inst_id: inst_id.to_string(),
+            td_mode,
+            side,
+            pos_side: None,
+            ord_type: OrderType::Limit,
+            sz,
+            px: Some(px),
+            cl_ord_id: None,
+            reduce_only: None,
+            tgt_ccy: None,
+        }
+    }
+
+    pub fn market(inst_id: &str, td_mode: TdMode, side: Side, sz: BigDecimal) -> Self {
+        OrderRequest {
+            inst_id: inst_id.to_string(),
+            td_mode,
+            side,
+            pos_side: None,
+            ord_type: OrderType::Market,
+            sz,
+            px: None,
+            cl_ord_id: None,
+            reduce_only: None,
+            tgt_ccy: None,
+        }
+    }
+
+    /// 数量是否以计价货币表示，币币市价买单不指定 tgt_ccy 时默认为计价货币
+    pub fn is_quote_sized(&self, category: &Category) -> bool {
+        *category == Category::Spot
+            && self.ord_type == OrderType::Market
+            && match self.tgt_ccy {
+                Some(tgt_ccy) => tgt_ccy == TgtCcy::QuoteCcy,
+                None => self.side == Side::Buy,
+            }
+    }
+
+    /// 按产品的交易规则检查订单，返回不符合的原因
+    /// 数量以计价货币表示的币币市价单不检查数量精度
+    /// 币币的 maxMktSz 以 USDT 计，只用于检查以 USDT 下单的市价单
+    pub fn validate(&self, symbol: &Symbol) -> Result<(), String> {
+        if self.inst_id != symbol.inst_id {
+            return Err(format!("order is for {} but rules are for {}", self.inst_id, symbol.inst_id));
+        }
+        if symbol.state != State::Live {
+            return Err(format!("instrument state is {}", symbol.state.as_str()));
+        }
+        let is_market = matches!(self.ord_type, OrderType::Market | OrderType::OptimalLimitIoc);
+        let quote_sized = self.is_quote_sized(&symbol.category);
+        if !quote_sized {
+            check_size(&self.sz, symbol)?;
+        } else if self.sz <= BigDecimal::zero() {
+            return Err(format!("sz {} must be positive", self.sz));
+        }
+        let max_sz = if !is_market {
+            symbol.max_lmt_sz.as_ref()
+        } else if symbol.category != Category::Spot || (quote_sized && symbol.quote == "USDT") {
+            symbol.max_mkt_sz.as_ref()
+        } else {
+            None
+        };
+        if let Some(max_sz) = max_sz
+            && self.sz > *max_sz
+        {
+            return Err(format!("sz {} is above max size {}", self.sz, max_sz));
+        }
+        match &self.px {
+            Some(px) => check_price(px, symbol),
+            None if is_market => Ok(()),
+            None => Err(format!("px is required for {} orders", self.ord_type.as_str())),
+        }
+    }
+}
+
+/// 数量必须为 lotSz 的整数倍且不小于 minSz
+pub(crate) fn check_size(sz: &BigDecimal, symbol: &Symbol) -> Result<(), String> {
+    if *sz < symbol.min_sz {
+        return Err(format!("sz {} is below min size {}", sz, symbol.min_sz));
+    }
+    if !symbol.lot_sz.is_zero() && !(sz % &symbol.lot_sz).is_zero() {
+        return Err(format!("sz {} is not a multiple of lot size {}", sz, symbol.lot_sz));
+    }
+    Ok(())
+}
+
+/// 价格必须为正数且是 tickSz 的整数倍
+pub(crate) fn check_price(px: &BigDecimal, symbol: &Symbol) -> Result<(), String> {
+    if *px <= BigDecimal::zero() {
+        return Err(format!("px {} must be positive", px));
+    }
+    if !symbol.tick_sz.is_zero() && !(px % &symbol.tick_sz).is_zero() {
+        return Err(format!("px {} is not a multiple of tick size {}", px, symbol.tick_sz));
+    }
+    Ok(())
+}
+
+/// 撤单请求，ord_id 和 cl_ord_id 必须传一个，都传时以 ord_id 为主
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CancelRequest {
+    pub inst_id: String,
+    pub ord_id: Option<String>,
+    pub cl_ord_id: Option<String>,
+}
+
+impl CancelRequest {
+    pub fn new(inst_id: &str, ord_id: &str) -> Self {
+        CancelRequest {
+            inst_id: inst_id.to_string(),
+            ord_id: Some(ord_id.to_string()),
+            cl_ord_id: None,
+        }
+    }
+}
+
+/// 改单请求，new_sz 和 new_px 至少传一个
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AmendRequest {
+    pub inst_id: String,
+    pub ord_id: Option<String>,
+    pub cl_ord_id: Option<String>,
+    /// 修改后的数量，包含已成交的数量
+    pub new_sz: Option<BigDecimal>,
+    pub new_px: Option<BigDecimal>,
+    /// 修改失败时是否自动撤单
+    pub cxl_on_fail: Option<bool>,
+    /// 自定义的修改事件 ID
+    pub req_id: Option<String>,
+}
+
+impl AmendRequest {
+    pub fn validate(&self, symbol: &Symbol) -> Result<(), String> {
+        if self.inst_id != symbol.inst_id {
+            return Err(format!("order is for {} but rules are for {}", self.inst_id, symbol.inst_id));
+        }
+        if self.new_sz.is_none() && self.new_px.is_none() {
+            return Err("either new_sz or new_px is required".to_string());
+        }
+        if let Some(sz) = &self.new_sz {
+            check_size(sz, symbol)?;
+        }
+        if let Some(px) = &self.new_px {
+            check_price(px, symbol)?;
+        }
+        Ok(())
+    }
+}
+
+/// 下单、撤单、改单的回执
+/// 批量操作中每个订单单独返回结果，s_code 为 "0" 表示成功
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OrderAck {
+    pub ord_id: String,
+    pub cl_ord_id: Option<String>,
+    /// 改单时的自定义 ID
+    pub req_id: Option<String>,
+    pub s_code: String,
+    pub s_msg: String,
+    pub ts: u64,
+}
+
+impl OrderAck {
+    pub fn is_success(&self) -> bool {
+        self.s_code == "0"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::{self, dec};
+
+    fn btc_usdt() -> Symbol {
+        Symbol {
+            min_sz: dec("0.00001"),
+            max_lmt_sz: Some(dec("100")),
+            max_mkt_sz: Some(dec("1000000")),
+            ..fixture::spot("BTC-USDT", "0.00000001")
+        }
+    }
+
+    #[test]
+    fn test_validate_order() {
+        let symbol = btc_usdt();
+        let ok = OrderRequest::limit("BTC-USDT", TdMode::Cash, Side::Buy, dec("0.001"), dec("30000.1"));
+        assert_eq!(ok.validate(&symbol), Ok(()));
+
+        let cases = [
+            OrderRequest::limit("BTC-USDT", TdMode::Cash, Side::Buy, dec("0.000001"), dec("30000")),
+            OrderRequest::limit("BTC-USDT", TdMode::Cash, Side::Buy, dec("0.000010001"), dec("30000")),
+            OrderRequest::limit("BTC-USDT", TdMode::Cash, Side::Buy, dec("0.001"), dec("30000.05")),
+            OrderRequest::limit("BTC-USDT", TdMode::Cash, Side::Buy, dec("101"), dec("30000")),
+            OrderRequest::limit("ETH-USDT", TdMode::Cash, Side::Buy, dec("1"), dec("3000")),
+            OrderRequest {
+                px: None,
+                ..ok.clone()
+            },
+        ];
+        for order in cases {
+            assert!(order.validate(&symbol).is_err(), "{:?} should be rejected", order);
+        }
+
+        // 以计价货币下的市价买单不检查数量精度，maxMktSz 以 USDT 计
+        let quote = OrderRequest {
+            tgt_ccy: Some(TgtCcy::QuoteCcy),
+            ..OrderRequest::market("BTC-USDT", TdMode::Cash, Side::Buy, dec("100.123"))
+        };
+        assert_eq!(quote.validate(&symbol), Ok(()));
+        assert_eq!(
+            OrderRequest { sz: dec("1000001"), ..quote }.validate(&symbol),
+            Err("sz 1000001 is above max size 1000000".to_string())
+        );
+
+        // 不指定 tgt_ccy 的市价买单默认以计价货币下单
+        let buy = OrderRequest::market("BTC-USDT", TdMode::Cash, Side::Buy, dec("500.5"));
+        assert!(buy.is_quote_sized(&symbol.category));
+        assert_eq!(buy.validate(&symbol), Ok(()));
+
+        // 以交易货币下的市价单不与以 USDT 计的 maxMktSz 比较，也不受 maxLmtSz 限制
+        let sell = OrderRequest::market("BTC-USDT", TdMode::Cash, Side::Sell, dec("2000000"));
+        assert!(!sell.is_quote_sized(&symbol.category));
+        assert_eq!(sell.validate(&symbol), Ok(()));
+        let limit = OrderRequest::limit("BTC-USDT", TdMode::Cash, Side::Sell, dec("100.00000001"), dec("30000"));
+        assert_eq!(limit.validate(&symbol), Err("sz 100.00000001 is above max size 100".to_string()));
+
+        let suspended = Symbol {
+            state: State::Suspend,
+            ..btc_usdt()
+        };
+        assert!(ok.validate(&suspended).is_err());
+    }
+
+    #[test]
+    fn test_validate_amend() {
+        let symbol = btc_usdt();
+        let amend = AmendRequest {
+            inst_id: "BTC-USDT".to_string(),
+            ord_id: Some("1".to_string()),
+            cl_ord_id: None,
+            new_sz: None,
+            new_px: Some(dec("30000.2")),
+            cxl_on_fail: None,
+            req_id: None,
+        };
+        assert_eq!(amend.validate(&symbol), Ok(()));
+        assert!(AmendRequest { new_px: None, ..amend.clone() }.validate(&symbol).is_err());
+        assert!(AmendRequest { new_px: Some(dec("30000.25")), ..amend }.validate(&symbol).is_err());
+    }
+}
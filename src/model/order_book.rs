@@ -0,0 +1,301 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use bigdecimal::{BigDecimal, Zero};
+
+/// 参与校验和计算的档位数
+const CHECKSUM_DEPTH: usize = 25;
+
+/// 深度中的一档
+/// 保留 OKX 返回的原始字符串，校验和需要按原始字符串计算，如 "0.10" 不能写成 "0.1"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Level {
+    pub px: BigDecimal,
+    /// 数量，合约为张数，币币为币的数量
+    pub sz: BigDecimal,
+    /// 该价格上的订单数
+    pub orders: u64,
+    raw_px: String,
+    raw_sz: String,
+}
+
+impl Level {
+    /// 解析 OKX 的价格和数量字符串，格式错误时返回 None
+    pub fn parse(px: &str, sz: &str, orders: u64) -> Option<Level> {
+        Some(Level {
+            px: BigDecimal::from_str(px).ok()?,
+            sz: BigDecimal::from_str(sz).ok()?,
+            orders,
+            raw_px: px.to_string(),
+            raw_sz: sz.to_string(),
+        })
+    }
+}
+
+/// 买盘或卖盘
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookSide {
+    Bid,
+    Ask,
+}
+
+/// 一次深度推送，快照或增量
+/// 增量中数量为 0 的档位表示删除该价格
+#[derive(Debug, Clone, Default)]
+pub struct BookUpdate {
+    pub bids: Vec<Level>,
+    pub asks: Vec<Level>,
+    pub ts: u64,
+    pub seq_id: Option<i64>,
+    pub prev_seq_id: Option<i64>,
+    /// 前 25 档的 CRC32 校验和，books5、bbo-tbt 没有
+    pub checksum: Option<i32>,
+}
+
+/// 本地深度与服务端不一致，需要重新订阅获取快照
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BookError {
+    ChecksumMismatch { expected: i32, actual: i32 },
+    /// 增量的 prevSeqId 与本地最后的 seqId 不相等，中间有推送丢失
+    SequenceGap { seq_id: i64, prev_seq_id: i64 },
+}
+
+impl fmt::Display for BookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BookError::ChecksumMismatch { expected, actual } => {
+                write!(f, "book checksum mismatch: expected {}, actual {}", expected, actual)
+            }
+            BookError::SequenceGap { seq_id, prev_seq_id } => {
+                write!(f, "book sequence gap: local seqId {}, prevSeqId {}", seq_id, prev_seq_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BookError {}
+
+/// 本地维护的深度
+#[derive(Debug, Clone)]
+pub struct OrderBook {
+    pub inst_id: String,
+    bids: BTreeMap<BigDecimal, Level>,
+    asks: BTreeMap<BigDecimal, Level>,
+    pub seq_id: Option<i64>,
+    pub ts: u64,
+}
+
+impl OrderBook {
+    pub fn new(inst_id: &str) -> Self {
+        OrderBook {
+            inst_id: inst_id.to_string(),
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            seq_id: None,
+            ts: 0,
+        }
+    }
+
+    /// 用快照替换整个深度
+    pub fn apply_snapshot(&mut self, update: BookUpdate) -> Result<(), BookError> {
+        self.bids.clear();
+        self.asks.clear();
+        self.merge(update)
+    }
+
+    /// 应用增量，校验序号和校验和
+    /// 返回错误时本地深度已不可用，需要重新获取快照
+    pub fn apply_update(&mut self, update: BookUpdate) -> Result<(), BookError> {
+        if let (Some(seq_id), Some(prev_seq_id)) = (self.seq_id, update.prev_seq_id)
+            && seq_id != prev_seq_id
+        {
+            return Err(BookError::SequenceGap { seq_id, prev_seq_id });
+        }
+        self.merge(update)
+    }
+
+    fn merge(&mut self, update: BookUpdate) -> Result<(), BookError> {
+        for (levels, book) in [(update.bids, &mut self.bids), (update.asks, &mut self.asks)] {
+            for level in levels {
+                if level.sz.is_zero() {
+                    book.remove(&level.px);
+                } else {
+                    book.insert(level.px.clone(), level);
+                }
+            }
+        }
+        self.seq_id = update.seq_id.or(self.seq_id);
+        self.ts = update.ts;
+        match update.checksum {
+            Some(expected) if expected != self.checksum() => Err(BookError::ChecksumMismatch {
+                expected,
+                actual: self.checksum(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// 买盘，价格从高到低
+    pub fn bids(&self) -> impl Iterator<Item = &Level> {
+        self.bids.values().rev()
+    }
+
+    /// 卖盘，价格从低到高
+    pub fn asks(&self) -> impl Iterator<Item = &Level> {
+        self.asks.values()
+    }
+
+    pub fn best_bid(&self) -> Option<&Level> {
+        self.bids().next()
+    }
+
+    pub fn best_ask(&self) -> Option<&Level> {
+        self.asks().next()
+    }
+
+    /// 前 n 档的买盘和卖盘
+    pub fn depth(&self, n: usize) -> (Vec<&Level>, Vec<&Level>) {
+        (self.bids().take(n).collect(), self.asks().take(n).collect())
+    }
+
+    /// 吃掉 side 一侧 size 数量的成交均价，买入吃卖盘，卖出吃买盘
+    /// 深度不足时返回 None
+    pub fn fill_price(&self, side: BookSide, size: &BigDecimal) -> Option<BigDecimal> {
+        if *size <= BigDecimal::zero() {
+            return None;
+        }
+        let levels: Box<dyn Iterator<Item = &Level>> = match side {
+            BookSide::Bid => Box::new(self.bids()),
+            BookSide::Ask => Box::new(self.asks()),
+        };
+        let mut remaining = size.clone();
+        let mut notional = BigDecimal::zero();
+        for level in levels {
+            let take = if level.sz < remaining { level.sz.clone() } else { remaining.clone() };
+            notional += &take * &level.px;
+            remaining -= take;
+            if remaining.is_zero() {
+                return Some(notional / size);
+            }
+        }
+        None
+    }
+
+    /// OKX 的校验和：前 25 档按 买1价:买1量:卖1价:卖1量:买2价... 拼接，一侧不足时跳过，取 CRC32 的有符号值
+    pub fn checksum(&self) -> i32 {
+        crc32fast::hash(self.checksum_string().as_bytes()) as i32
+    }
+
+    fn checksum_string(&self) -> String {
+        let bids: Vec<&Level> = self.bids().take(CHECKSUM_DEPTH).collect();
+        let asks: Vec<&Level> = self.asks().take(CHECKSUM_DEPTH).collect();
+        let mut parts = Vec::new();
+        for i in 0..CHECKSUM_DEPTH {
+            for level in [bids.get(i), asks.get(i)].into_iter().flatten() {
+                parts.push(level.raw_px.as_str());
+                parts.push(level.raw_sz.as_str());
+            }
+        }
+        parts.join(":")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::dec;
+
+    fn level(px: &str, sz: &str) -> Level {
+        Level::parse(px, sz, 1).unwrap()
+    }
+
+    fn snapshot(bids: &[(&str, &str)], asks: &[(&str, &str)]) -> BookUpdate {
+        BookUpdate {
+            bids: bids.iter().map(|(p, s)| level(p, s)).collect(),
+            asks: asks.iter().map(|(p, s)| level(p, s)).collect(),
+            ts: 1,
+            seq_id: Some(100),
+            prev_seq_id: Some(-1),
+            checksum: None,
+        }
+    }
+
+    #[test]
+    fn test_checksum_string() {
+        // OKX 文档中的例子
+        let mut book = OrderBook::new("BTC-USDT");
+        book.apply_snapshot(snapshot(&[("3366.1", "7"), ("3366", "6")], &[("3366.8", "9"), ("3368", "8")]))
+            .unwrap();
+        assert_eq!(book.checksum_string(), "3366.1:7:3366.8:9:3366:6:3368:8");
+
+        book.apply_snapshot(snapshot(&[("3366.1", "7")], &[("3366.8", "9"), ("3368", "8"), ("3372", "8")]))
+            .unwrap();
+        assert_eq!(book.checksum_string(), "3366.1:7:3366.8:9:3368:8:3372:8");
+        assert_eq!(book.checksum(), crc32fast::hash(b"3366.1:7:3366.8:9:3368:8:3372:8") as i32);
+    }
+
+    #[test]
+    fn test_apply_update() {
+        let mut book = OrderBook::new("BTC-USDT");
+        book.apply_snapshot(snapshot(&[("100", "1"), ("99", "2")], &[("101", "1"), ("102", "3")]))
+            .unwrap();
+
+        // 删除 100 的买盘，新增 100.5 的买盘，修改 102 的卖盘
+        let update = BookUpdate {
+            bids: vec![level("100", "0"), level("100.5", "4")],
+            asks: vec![level("102", "5")],
+            ts: 2,
+            seq_id: Some(101),
+            prev_seq_id: Some(100),
+            checksum: Some(crc32fast::hash(b"100.5:4:101:1:99:2:102:5") as i32),
+        };
+        book.apply_update(update).unwrap();
+        assert_eq!(book.best_bid().unwrap().px, dec("100.5"));
+        assert_eq!(book.best_ask().unwrap().px, dec("101"));
+        let (bids, asks) = book.depth(5);
+        assert_eq!(bids.len(), 2);
+        assert_eq!(asks[1].sz, dec("5"));
+        assert_eq!(book.seq_id, Some(101));
+    }
+
+    #[test]
+    fn test_detects_gap_and_checksum_mismatch() {
+        let mut book = OrderBook::new("BTC-USDT");
+        book.apply_snapshot(snapshot(&[("100", "1")], &[("101", "1")])).unwrap();
+
+        let gap = BookUpdate {
+            seq_id: Some(105),
+            prev_seq_id: Some(103),
+            ..Default::default()
+        };
+        assert_eq!(
+            book.apply_update(gap),
+            Err(BookError::SequenceGap { seq_id: 100, prev_seq_id: 103 })
+        );
+
+        let bad = BookUpdate {
+            bids: vec![level("100", "2")],
+            seq_id: Some(101),
+            prev_seq_id: Some(100),
+            checksum: Some(12345),
+            ..Default::default()
+        };
+        assert!(matches!(book.apply_update(bad), Err(BookError::ChecksumMismatch { expected: 12345, .. })));
+    }
+
+    #[test]
+    fn test_fill_price() {
+        let mut book = OrderBook::new("BTC-USDT");
+        book.apply_snapshot(snapshot(&[("99", "1"), ("98", "1")], &[("101", "1"), ("103", "2")]))
+            .unwrap();
+
+        assert_eq!(book.fill_price(BookSide::Ask, &dec("1")), Some(dec("101")));
+        // 1 * 101 + 1 * 103
+        assert_eq!(book.fill_price(BookSide::Ask, &dec("2")), Some(dec("102")));
+        // 1 * 99 + 0.5 * 98
+        assert_eq!(book.fill_price(BookSide::Bid, &dec("1.5")).unwrap().round(4), dec("98.6667"));
+        assert_eq!(book.fill_price(BookSide::Ask, &dec("4")), None);
+        assert_eq!(book.fill_price(BookSide::Bid, &dec("0")), None);
+    }
+}
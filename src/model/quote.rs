@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use bigdecimal::{BigDecimal, One, Zero};
+use serde::Deserialize;
+
+use crate::model::inst_id::InstId;
+use crate::model::symbol::{IndexTicker, Ticker};
+
+/// 计价币换算配置
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct QuoteConfig {
+    /// 视为等价、可以互相比较的计价币
+    pub currencies: Vec<String>,
+    /// 按指数价格换算，关闭时按 1:1 换算
+    pub use_index: bool,
+}
+
+impl Default for QuoteConfig {
+    fn default() -> Self {
+        QuoteConfig {
+            currencies: vec!["USD".to_string(), "USDT".to_string(), "USDC".to_string()],
+            use_index: true,
+        }
+    }
+}
+
+/// 计价币之间的换算
+/// 优先使用两个计价币之间的现货盘口（如 USDC-USDT），没有时按各自的 USD 指数换算
+#[derive(Debug, Clone)]
+pub struct QuoteConverter {
+    config: QuoteConfig,
+    /// 1 单位计价币对应的 USD，来自 USDT-USD 等指数
+    usd: HashMap<String, BigDecimal>,
+    /// 现货中间价，key 为 (base, quote)
+    cross: HashMap<(String, String), BigDecimal>,
+}
+
+impl QuoteConverter {
+    pub fn new(config: QuoteConfig) -> Self {
+        let usd = HashMap::from([("USD".to_string(), BigDecimal::one())]);
+        QuoteConverter {
+            config,
+            usd,
+            cross: HashMap::new(),
+        }
+    }
+
+    pub fn config(&self) -> &QuoteConfig {
+        &self.config
+    }
+
+    /// 两个计价币是否可以比较
+    pub fn is_equivalent(&self, a: &str, b: &str) -> bool {
+        a == b || (self.contains(a) && self.contains(b))
+    }
+
+    fn contains(&self, ccy: &str) -> bool {
+        self.config.currencies.iter().any(|c| c == ccy)
+    }
+
+    /// 用 quoteCcy=USD 的指数行情更新各计价币的 USD 价格
+    pub fn update_index(&mut self, index: &[IndexTicker]) {
+        for t in index {
+            let Some((ccy, "USD")) = t.inst_id.split_once('-') else {
+                continue;
+            };
+            if self.contains(ccy) && t.idx_px > BigDecimal::zero() {
+                self.usd.insert(ccy.to_string(), t.idx_px.clone());
+            }
+        }
+    }
+
+    /// 用计价币之间的现货盘口更新换算价格，盘口为空的忽略
+    pub fn update_spot(&mut self, tickers: &[Ticker]) {
+        for t in tickers {
+            if !self.contains(&t.base) || !self.contains(&t.quote) {
+                continue;
+            }
+            let (Some(bid), Some(ask)) = (&t.bid_px, &t.ask_px) else {
+                continue;
+            };
+            if *bid <= BigDecimal::zero() || *ask <= BigDecimal::zero() {
+                continue;
+            }
+            self.cross.insert((t.base.clone(), t.quote.clone()), (bid + ask) / BigDecimal::from(2));
+        }
+    }
+
+    /// 1 单位 from 对应多少 to，缺少价格时返回 None
+    pub fn rate(&self, from: &str, to: &str) -> Option<BigDecimal> {
+        if from == to || !self.config.use_index {
+            return Some(BigDecimal::one());
+        }
+        if let Some(px) = self.cross.get(&(from.to_string(), to.to_string())) {
+            return Some(px.clone());
+        }
+        if let Some(px) = self.cross.get(&(to.to_string(), from.to_string())) {
+            return Some(BigDecimal::one() / px);
+        }
+        Some(self.usd.get(from)? / self.usd.get(to)?)
+    }
+
+    /// 现货和合约之间的换算路径，计价币不等价或缺少价格时返回 None
+    pub fn path(&self, spot: &InstId, swap: &InstId) -> Option<QuotePath> {
+        if spot.base != swap.base || !self.is_equivalent(&spot.quote, &swap.quote) {
+            return None;
+        }
+        Some(QuotePath {
+            spot_quote: spot.quote.clone(),
+            swap_quote: swap.quote.clone(),
+            rate: self.rate(&swap.quote, &spot.quote)?,
+            inverse: swap.is_inverse(),
+        })
+    }
+}
+
+/// 一组现货-合约比较的计价路径
+/// 币本位合约的价格同样是每个币的 USD 价格，与正向合约一样按计价币换算；区别在于面值以 USD 计、保证金为币
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuotePath {
+    pub spot_quote: String,
+    pub swap_quote: String,
+    /// 合约价格乘以 rate 换算为现货的计价币
+    pub rate: BigDecimal,
+    /// 合约是否为币本位
+    pub inverse: bool,
+}
+
+impl QuotePath {
+    /// 计价币相同，不需要换算
+    pub fn is_direct(&self) -> bool {
+        self.spot_quote == self.swap_quote
+    }
+
+    /// 合约价格换算为现货的计价币
+    pub fn convert(&self, swap_px: &BigDecimal) -> BigDecimal {
+        swap_px * &self.rate
+    }
+}
+
+impl fmt::Display for QuotePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_direct() {
+            write!(f, "{}", self.spot_quote)?;
+        } else {
+            write!(f, "{}→{} @{:.6}", self.swap_quote, self.spot_quote, self.rate)?;
+        }
+        if self.inverse {
+            write!(f, " (币本位)")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::{dec, index, ticker};
+    use std::str::FromStr;
+
+    fn inst(s: &str) -> InstId {
+        InstId::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_index_conversion() {
+        let mut converter = QuoteConverter::new(QuoteConfig::default());
+        converter.update_index(&[index("USDT-USD", "0.999"), index("USDC-USD", "1.0005"), index("BTC-USD", "60000")]);
+
+        // 币本位合约的 USD 价格换算为 USDT
+        let path = converter.path(&inst("BTC-USDT"), &inst("BTC-USD-SWAP")).unwrap();
+        assert!(path.inverse && !path.is_direct());
+        assert_eq!(path.rate, dec("1") / dec("0.999"));
+        assert_eq!(path.convert(&dec("59940")).round(8), dec("60000"));
+        assert_eq!(path.to_string(), "USD→USDT @1.001001 (币本位)");
+
+        let path = converter.path(&inst("BTC-USDT"), &inst("BTC-USDT-SWAP")).unwrap();
+        assert!(path.is_direct());
+        assert_eq!(path.rate, dec("1"));
+        assert_eq!(path.to_string(), "USDT");
+
+        // 不在等价集合中或币种不同的不比较
+        assert!(converter.path(&inst("BTC-EUR"), &inst("BTC-USDT-SWAP")).is_none());
+        assert!(converter.path(&inst("ETH-USDT"), &inst("BTC-USDT-SWAP")).is_none());
+    }
+
+    #[test]
+    fn test_cross_rate_and_missing_index() {
+        let mut converter = QuoteConverter::new(QuoteConfig::default());
+        // 没有 USDC 指数时无法换算
+        assert!(converter.rate("USDC", "USDT").is_none());
+
+        converter.update_spot(&[ticker("USDC-USDT", "0.9998", "1.0000")]);
+        assert_eq!(converter.rate("USDC", "USDT"), Some(dec("0.9999")));
+        assert_eq!(converter.rate("USDT", "USDC"), Some(dec("1") / dec("0.9999")));
+
+        // 关闭指数换算时按 1:1
+        let converter = QuoteConverter::new(QuoteConfig {
+            use_index: false,
+            ..Default::default()
+        });
+        assert_eq!(converter.rate("USD", "USDT"), Some(dec("1")));
+    }
+}
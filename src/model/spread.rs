@@ -0,0 +1,157 @@
+use std::collections::VecDeque;
+
+use bigdecimal::{BigDecimal, Zero};
+
+use crate::model::symbol::{FutureAlias, Symbol, Ticker};
+
+const YEAR_MS: u64 = 365 * 24 * 3600 * 1000;
+
+/// 同一交易品种两个交割合约之间的价差，远月减近月
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarSpread {
+    pub near_inst_id: String,
+    pub far_inst_id: String,
+    pub near_alias: Option<FutureAlias>,
+    pub far_alias: Option<FutureAlias>,
+    pub near_exp_time: u64,
+    pub far_exp_time: u64,
+    /// 中间价的价差
+    pub spread: BigDecimal,
+    /// spread / 近月中间价
+    pub spread_rate: BigDecimal,
+    /// spread_rate 按两个合约的交割间隔年化，即持有近月换到远月的年化成本
+    pub annualized: BigDecimal,
+    /// 买远月、卖近月可成交的价差，远月卖一 - 近月买一
+    pub buy_px: BigDecimal,
+    /// 卖远月、买近月可成交的价差，远月买一 - 近月卖一
+    pub sell_px: BigDecimal,
+}
+
+impl CalendarSpread {
+    /// 任意一侧盘口为空，或 far 不晚于 near 交割时返回 None
+    pub fn new(near: &Symbol, far: &Symbol, near_ticker: &Ticker, far_ticker: &Ticker) -> Option<CalendarSpread> {
+        let interval = far.exp_time.checked_sub(near.exp_time).filter(|i| *i > 0)?;
+        let near_mid = near_ticker.mid_px()?;
+        if near_mid <= BigDecimal::zero() {
+            return None;
+        }
+        let spread = far_ticker.mid_px()? - &near_mid;
+        let spread_rate = &spread / &near_mid;
+        let annualized = &spread_rate * BigDecimal::from(YEAR_MS) / BigDecimal::from(interval);
+        Some(CalendarSpread {
+            near_inst_id: near.inst_id.clone(),
+            far_inst_id: far.inst_id.clone(),
+            near_alias: near.alias,
+            far_alias: far.alias,
+            near_exp_time: near.exp_time,
+            far_exp_time: far.exp_time,
+            buy_px: far_ticker.ask_px.as_ref()? - near_ticker.bid_px.as_ref()?,
+            sell_px: far_ticker.bid_px.as_ref()? - near_ticker.ask_px.as_ref()?,
+            spread,
+            spread_rate,
+            annualized,
+        })
+    }
+}
+
+/// 最近 window 个样本的滚动均值和标准差
+#[derive(Debug, Clone)]
+pub struct RollingBand {
+    window: usize,
+    samples: VecDeque<BigDecimal>,
+}
+
+impl RollingBand {
+    pub fn new(window: usize) -> Self {
+        RollingBand {
+            window,
+            samples: VecDeque::with_capacity(window),
+        }
+    }
+
+    /// 加入一个样本，超出窗口时丢弃最早的
+    pub fn push(&mut self, value: BigDecimal) {
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(value);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn mean(&self) -> Option<BigDecimal> {
+        if self.samples.is_empty() {
+            return None;
+        }
+        let sum: BigDecimal = self.samples.iter().sum();
+        Some(sum / BigDecimal::from(self.samples.len() as u64))
+    }
+
+    /// 总体标准差
+    pub fn std_dev(&self) -> Option<BigDecimal> {
+        let mean = self.mean()?;
+        let var: BigDecimal = self.samples.iter().map(|v| (v - &mean).square()).sum();
+        (var / BigDecimal::from(self.samples.len() as u64)).sqrt()
+    }
+
+    /// value 偏离均值的标准差倍数，样本不足两个或标准差为 0 时返回 None
+    pub fn z_score(&self, value: &BigDecimal) -> Option<BigDecimal> {
+        if self.samples.len() < 2 {
+            return None;
+        }
+        let std_dev = self.std_dev().filter(|s| !s.is_zero())?;
+        Some((value - self.mean()?) / std_dev)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::{dec, future, ticker};
+
+    const DAY_MS: u64 = 24 * 3600 * 1000;
+
+    #[test]
+    fn test_calendar_spread() {
+        let near = future("BTC-USDT-250328", FutureAlias::Quarter, 100 * DAY_MS);
+        let far = future("BTC-USDT-250627", FutureAlias::NextQuarter, 173 * DAY_MS);
+        let spread = CalendarSpread::new(&near, &far, &ticker(&near.inst_id, "99", "101"), &ticker(&far.inst_id, "100", "104")).unwrap();
+        assert_eq!(spread.spread, dec("2"));
+        assert_eq!(spread.spread_rate, dec("0.02"));
+        // 间隔 73 天，年化为 5 倍
+        assert_eq!(spread.annualized, dec("0.1"));
+        assert_eq!(spread.buy_px, dec("5"));
+        assert_eq!(spread.sell_px, dec("-1"));
+
+        assert!(CalendarSpread::new(&far, &near, &ticker(&far.inst_id, "99", "101"), &ticker(&near.inst_id, "100", "104")).is_none());
+    }
+
+    #[test]
+    fn test_rolling_band() {
+        let mut band = RollingBand::new(4);
+        band.push(dec("1"));
+        assert_eq!(band.z_score(&dec("2")), None);
+        for v in ["3", "1", "3"] {
+            band.push(dec(v));
+        }
+        assert_eq!(band.mean(), Some(dec("2")));
+        assert_eq!(band.std_dev(), Some(dec("1")));
+        assert_eq!(band.z_score(&dec("5")), Some(dec("3")));
+
+        // 超出窗口后丢弃最早的样本
+        band.push(dec("5"));
+        assert_eq!(band.len(), 4);
+        assert_eq!(band.mean(), Some(dec("3")));
+
+        let mut flat = RollingBand::new(3);
+        flat.push(dec("1"));
+        flat.push(dec("1"));
+        assert_eq!(flat.z_score(&dec("1")), None);
+    }
+}
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
-use bigdecimal::BigDecimal;
+use bigdecimal::{BigDecimal, Zero};
+use chrono::{DateTime, Utc};
+
+use crate::model::inst_id::InstId;
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub enum Category{
@@ -10,6 +13,7 @@ pub enum Category{
     Option,
 }
 
+#[allow(clippy::should_implement_trait)]
 impl Category{
     pub fn as_str(&self)->&'static str{
         match self{
@@ -20,6 +24,16 @@ impl Category{
             Category::Option=>"OPTION",
         }
     }
+    pub fn from_str(s: &str) -> Option<Category> {
+        match s {
+            "SPOT" => Some(Category::Spot),
+            "MARGIN" => Some(Category::Margin),
+            "SWAP" => Some(Category::Swap),
+            "FUTURES" => Some(Category::Future),
+            "OPTION" => Some(Category::Option),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -30,6 +44,7 @@ pub enum State {
     Test,
 }
 
+#[allow(clippy::should_implement_trait)]
 impl State {
     pub fn as_str(&self) -> &'static str {
         match self {
@@ -50,6 +65,150 @@ impl State {
     }
 }
 
+/// 合约类型，仅适用于交割/永续
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum ContractType {
+    /// 正向合约，以 USDT/USDC 保证金，面值以币计
+    Linear,
+    /// 反向合约，以币保证金，面值以 USD 计
+    Inverse,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl ContractType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContractType::Linear => "linear",
+            ContractType::Inverse => "inverse",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<ContractType> {
+        match s {
+            "linear" => Some(ContractType::Linear),
+            "inverse" => Some(ContractType::Inverse),
+            _ => None,
+        }
+    }
+}
+
+/// 交易规则类型
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum RuleType {
+    Normal,
+    /// 盘前交易
+    PreMarket,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl RuleType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RuleType::Normal => "normal",
+            RuleType::PreMarket => "pre_market",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<RuleType> {
+        match s {
+            "normal" => Some(RuleType::Normal),
+            "pre_market" => Some(RuleType::PreMarket),
+            _ => None,
+        }
+    }
+}
+
+/// 开盘类型，只适用于 SPOT/MARGIN
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum OpenType {
+    /// 定价开盘
+    FixPrice,
+    /// 提前挂单
+    PreQuote,
+    /// 集合竞价
+    CallAuction,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl OpenType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OpenType::FixPrice => "fix_price",
+            OpenType::PreQuote => "pre_quote",
+            OpenType::CallAuction => "call_auction",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<OpenType> {
+        match s {
+            "fix_price" => Some(OpenType::FixPrice),
+            "pre_quote" => Some(OpenType::PreQuote),
+            "call_auction" => Some(OpenType::CallAuction),
+            _ => None,
+        }
+    }
+}
+
+/// 期权类型
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptionType {
+    Call,
+    Put,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl OptionType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OptionType::Call => "C",
+            OptionType::Put => "P",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<OptionType> {
+        match s {
+            "C" => Some(OptionType::Call),
+            "P" => Some(OptionType::Put),
+            _ => None,
+        }
+    }
+}
+
+/// 交割合约的日期别名，按交割时间从近到远排列
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FutureAlias {
+    ThisWeek,
+    NextWeek,
+    ThisMonth,
+    NextMonth,
+    Quarter,
+    NextQuarter,
+    ThirdQuarter,
+}
+
+#[allow(clippy::should_implement_trait)]
+impl FutureAlias {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FutureAlias::ThisWeek => "this_week",
+            FutureAlias::NextWeek => "next_week",
+            FutureAlias::ThisMonth => "this_month",
+            FutureAlias::NextMonth => "next_month",
+            FutureAlias::Quarter => "quarter",
+            FutureAlias::NextQuarter => "next_quarter",
+            FutureAlias::ThirdQuarter => "third_quarter",
+        }
+    }
+    pub fn from_str(s: &str) -> Option<FutureAlias> {
+        match s {
+            "this_week" => Some(FutureAlias::ThisWeek),
+            "next_week" => Some(FutureAlias::NextWeek),
+            "this_month" => Some(FutureAlias::ThisMonth),
+            "next_month" => Some(FutureAlias::NextMonth),
+            "quarter" => Some(FutureAlias::Quarter),
+            "next_quarter" => Some(FutureAlias::NextQuarter),
+            "third_quarter" => Some(FutureAlias::ThirdQuarter),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Symbol {
     pub inst_id: String,
@@ -59,6 +218,95 @@ pub struct Symbol {
     pub list_time: u64,
     pub exp_time: u64,
     pub state: State,
+    /// 交易品种，如 BTC-USD，仅适用于杠杆/交割/永续/期权
+    pub inst_family: String,
+    /// 盈亏结算和保证金币种，仅适用于交割/永续/期权
+    pub settle_ccy: Option<String>,
+    /// 下单价格精度
+    pub tick_sz: BigDecimal,
+    /// 下单数量精度，合约的数量单位是张，现货的数量单位是交易货币
+    pub lot_sz: BigDecimal,
+    /// 最小下单数量
+    pub min_sz: BigDecimal,
+    /// 合约面值，仅适用于交割/永续/期权
+    pub ct_val: Option<BigDecimal>,
+    /// 合约乘数，仅适用于交割/永续/期权
+    pub ct_mult: Option<BigDecimal>,
+    /// 合约面值计价币种，正向合约为交易货币如 BTC，反向合约为 USD
+    pub ct_val_ccy: Option<String>,
+    /// 合约类型，仅适用于交割/永续
+    pub ct_type: Option<ContractType>,
+    /// 最大杠杆倍数，不适用于币币、期权
+    pub lever: Option<BigDecimal>,
+    /// 限价单的单笔最大委托数量
+    pub max_lmt_sz: Option<BigDecimal>,
+    /// 市价单的单笔最大委托数量
+    pub max_mkt_sz: Option<BigDecimal>,
+    pub rule_type: RuleType,
+    /// 开盘类型，只适用于 SPOT/MARGIN
+    pub open_type: Option<OpenType>,
+    /// 期权类型，仅适用于期权
+    pub opt_type: Option<OptionType>,
+    /// 行权价格，仅适用于期权
+    pub stk: Option<BigDecimal>,
+    /// 合约日期别名，仅适用于交割；交割日期以 exp_time 为准
+    pub alias: Option<FutureAlias>,
+}
+
+impl Symbol {
+    /// 交易对，用于在现货和合约之间匹配同一个币对
+    pub fn pair_id(&self) -> InstId {
+        InstId::spot(&self.base, &self.quote)
+    }
+
+    /// 交割/行权时间，exp_time 为 0 表示没有到期时间
+    pub fn expiry(&self) -> Option<DateTime<Utc>> {
+        if self.exp_time == 0 {
+            return None;
+        }
+        DateTime::from_timestamp_millis(self.exp_time as i64)
+    }
+
+    /// 是否为盘前合约
+    pub fn is_pre_market(&self) -> bool {
+        self.rule_type == RuleType::PreMarket
+    }
+
+    /// 每张合约对应的面值，ct_val * ct_mult
+    /// 正向合约以交易货币计，反向合约以 USD 计
+    pub fn contract_value(&self) -> Option<BigDecimal> {
+        let ct_val = self.ct_val.as_ref()?;
+        Some(ct_val * self.ct_mult.clone().unwrap_or(BigDecimal::from(1)))
+    }
+
+    /// 合约张数换算为币的数量，px 为合约价格，反向合约需要用它把 USD 面值换算为币
+    /// 现货/杠杆直接返回数量本身
+    pub fn contracts_to_coin(&self, contracts: &BigDecimal, px: &BigDecimal) -> Option<BigDecimal> {
+        if self.category == Category::Spot || self.category == Category::Margin {
+            return Some(contracts.clone());
+        }
+        let value = contracts * self.contract_value()?;
+        match self.ct_type {
+            Some(ContractType::Inverse) if !px.is_zero() => Some(value / px),
+            Some(ContractType::Inverse) => None,
+            _ => Some(value),
+        }
+    }
+
+    /// 币的数量换算为合约张数，不做取整，下单前需要按 lot_sz 处理
+    pub fn coin_to_contracts(&self, coin: &BigDecimal, px: &BigDecimal) -> Option<BigDecimal> {
+        if self.category == Category::Spot || self.category == Category::Margin {
+            return Some(coin.clone());
+        }
+        let value = self.contract_value()?;
+        if value.is_zero() {
+            return None;
+        }
+        match self.ct_type {
+            Some(ContractType::Inverse) => Some(coin * px / value),
+            _ => Some(coin / value),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -66,16 +314,77 @@ pub struct Ticker {
     pub inst_id: String,
     pub base: String,
     pub quote: String,
+    /// 从未成交时为 0，开盘价和成交量等统计字段同样如此
     pub last: BigDecimal,
     pub last_sz: BigDecimal,
-    pub bid_px: BigDecimal,
-    pub bid_sz: BigDecimal,
-    pub ask_px: BigDecimal,
-    pub ask_sz: BigDecimal,
+    /// 盘口字段在没有挂单时为 None
+    pub bid_px: Option<BigDecimal>,
+    pub bid_sz: Option<BigDecimal>,
+    pub ask_px: Option<BigDecimal>,
+    pub ask_sz: Option<BigDecimal>,
     pub open_24h: BigDecimal,
     pub high_24h: BigDecimal,
     pub low_24h: BigDecimal,
     pub vol_24h: BigDecimal,
     pub vol_ccy_24h: BigDecimal,
+    /// UTC 0 时开盘价
+    pub sod_utc0: BigDecimal,
+    /// UTC+8 时开盘价
+    pub sod_utc8: BigDecimal,
     pub ts: u64,
-}
\ No newline at end of file
+}
+
+impl Ticker {
+    /// 交易对，用于在现货和合约之间匹配同一个币对
+    pub fn pair_id(&self) -> InstId {
+        InstId::spot(&self.base, &self.quote)
+    }
+
+    /// 买一和卖一的中间价，任意一侧为空时返回 None
+    pub fn mid_px(&self) -> Option<BigDecimal> {
+        Some((self.bid_px.as_ref()? + self.ask_px.as_ref()?) / BigDecimal::from(2))
+    }
+}
+
+/// 指数行情，如 BTC-USD、USDT-USD
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IndexTicker {
+    pub inst_id: String,
+    /// 最新指数价格
+    pub idx_px: BigDecimal,
+    pub open_24h: BigDecimal,
+    pub high_24h: BigDecimal,
+    pub low_24h: BigDecimal,
+    pub ts: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture;
+    use std::str::FromStr;
+
+    fn swap(ct_type: ContractType, ct_val: &str) -> Symbol {
+        Symbol {
+            ct_type: Some(ct_type),
+            ..fixture::swap("BTC-USDT-SWAP", "1", ct_val)
+        }
+    }
+
+    #[test]
+    fn test_linear_contracts_to_coin() {
+        let symbol = swap(ContractType::Linear, "0.01");
+        let px = BigDecimal::from(50000);
+        assert_eq!(symbol.contracts_to_coin(&BigDecimal::from(3), &px), Some(BigDecimal::from_str("0.03").unwrap()));
+        assert_eq!(symbol.coin_to_contracts(&BigDecimal::from_str("0.03").unwrap(), &px), Some(BigDecimal::from(3)));
+    }
+
+    #[test]
+    fn test_inverse_contracts_to_coin() {
+        // 反向合约每张 100 USD
+        let symbol = swap(ContractType::Inverse, "100");
+        let px = BigDecimal::from(50000);
+        assert_eq!(symbol.contracts_to_coin(&BigDecimal::from(10), &px), Some(BigDecimal::from_str("0.02").unwrap()));
+        assert_eq!(symbol.coin_to_contracts(&BigDecimal::from_str("0.02").unwrap(), &px), Some(BigDecimal::from(10)));
+    }
+}
@@ -0,0 +1,191 @@
+use bigdecimal::BigDecimal;
+
+use crate::model::basis::Basis;
+use crate::model::quote::QuotePath;
+use crate::model::symbol::{FutureAlias, Symbol, Ticker};
+
+const YEAR_MS: u64 = 365 * 24 * 3600 * 1000;
+
+/// 按到期前的剩余时间把基差率年化，已到期时返回 None
+pub fn annualize(rate: &BigDecimal, now: u64, exp_time: u64) -> Option<BigDecimal> {
+    let remaining = exp_time.checked_sub(now).filter(|r| *r > 0)?;
+    Some(rate * BigDecimal::from(YEAR_MS) / BigDecimal::from(remaining))
+}
+
+/// 一个交割合约相对现货和永续的基差
+#[derive(Debug, Clone, PartialEq)]
+pub struct TermPoint {
+    pub inst_id: String,
+    pub alias: Option<FutureAlias>,
+    pub exp_time: u64,
+    /// 按盘口计算的基差，合约价格已换算为现货的计价币
+    pub basis: Basis,
+    /// 合约中间价相对现货中间价的基差率
+    pub mid_rate: BigDecimal,
+    /// mid_rate 按剩余时间年化
+    pub annualized: BigDecimal,
+    /// 买现货、卖交割合约可成交的年化收益
+    pub carry_annualized: BigDecimal,
+    /// 卖现货、买交割合约可成交的年化基差，为负时有收益
+    pub reverse_annualized: BigDecimal,
+    /// 相对同一交易品种永续中间价的年化价差，没有永续时为 None
+    pub swap_annualized: Option<BigDecimal>,
+}
+
+impl TermPoint {
+    /// 盘口为空或合约已到期时返回 None
+    /// swap 与交割合约属于同一交易品种，不需要换算计价币
+    pub fn new(
+        symbol: &Symbol,
+        future: &Ticker,
+        spot: &Ticker,
+        swap: Option<&Ticker>,
+        path: &QuotePath,
+        now: u64,
+    ) -> Option<TermPoint> {
+        let basis = Basis::new(
+            spot.bid_px.clone()?,
+            spot.ask_px.clone()?,
+            path.convert(future.bid_px.as_ref()?),
+            path.convert(future.ask_px.as_ref()?),
+        )?;
+        let future_mid = future.mid_px()?;
+        let spot_mid = spot.mid_px()?;
+        let mid_rate = (path.convert(&future_mid) - &spot_mid) / &spot_mid;
+        let swap_annualized = swap
+            .and_then(|s| s.mid_px())
+            .and_then(|swap_mid| annualize(&((&future_mid - &swap_mid) / &swap_mid), now, symbol.exp_time));
+        Some(TermPoint {
+            inst_id: symbol.inst_id.clone(),
+            alias: symbol.alias,
+            exp_time: symbol.exp_time,
+            annualized: annualize(&mid_rate, now, symbol.exp_time)?,
+            carry_annualized: annualize(&basis.carry, now, symbol.exp_time)?,
+            reverse_annualized: annualize(&basis.reverse, now, symbol.exp_time)?,
+            swap_annualized,
+            mid_rate,
+            basis,
+        })
+    }
+}
+
+/// 同一交易品种下各交割合约的期限结构，按交割时间从近到远排列
+#[derive(Debug, Clone, PartialEq)]
+pub struct TermStructure {
+    /// 交易品种，如 BTC-USD
+    pub inst_family: String,
+    /// 作为比较基准的现货
+    pub spot_inst_id: String,
+    pub path: QuotePath,
+    pub points: Vec<TermPoint>,
+}
+
+/// 期限结构倒挂：远月合约的价格低于近月
+/// near 为 None 表示最近的合约已经低于现货
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inversion<'a> {
+    pub near: Option<&'a TermPoint>,
+    pub far: &'a TermPoint,
+}
+
+impl TermStructure {
+    pub fn new(inst_family: String, spot_inst_id: String, path: QuotePath, mut points: Vec<TermPoint>) -> Self {
+        points.sort_by_key(|p| p.exp_time);
+        TermStructure {
+            inst_family,
+            spot_inst_id,
+            path,
+            points,
+        }
+    }
+
+    /// 相邻合约之间的倒挂，现货视为期限为 0 的一点
+    /// 所有合约以同一个现货为基准，比较 mid_rate 等同于比较价格
+    pub fn inversions(&self) -> Vec<Inversion<'_>> {
+        let mut inversions = Vec::new();
+        let mut near: Option<&TermPoint> = None;
+        for far in self.points.iter() {
+            let near_rate = near.map(|n| n.mid_rate.clone()).unwrap_or_default();
+            if far.mid_rate < near_rate {
+                inversions.push(Inversion { near, far });
+            }
+            near = Some(far);
+        }
+        inversions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::{self, dec, ticker};
+
+    const NOW: u64 = 1_700_000_000_000;
+    const DAY_MS: u64 = 24 * 3600 * 1000;
+
+    fn future(inst_id: &str, alias: FutureAlias, days: u64) -> Symbol {
+        fixture::future(inst_id, alias, NOW + days * DAY_MS)
+    }
+
+    fn direct() -> QuotePath {
+        QuotePath {
+            spot_quote: "USDT".to_string(),
+            swap_quote: "USDT".to_string(),
+            rate: dec("1"),
+            inverse: false,
+        }
+    }
+
+    #[test]
+    fn test_annualize() {
+        assert_eq!(annualize(&dec("0.01"), NOW, NOW + 73 * DAY_MS), Some(dec("0.05")));
+        assert_eq!(annualize(&dec("0.01"), NOW, NOW), None);
+        assert_eq!(annualize(&dec("0.01"), NOW + 1, NOW), None);
+    }
+
+    #[test]
+    fn test_term_point() {
+        let spot = ticker("BTC-USDT", "99.9", "100.1");
+        let swap = ticker("BTC-USDT-SWAP", "100.4", "100.6");
+        let symbol = future("BTC-USDT-250328", FutureAlias::Quarter, 73);
+        let point = TermPoint::new(&symbol, &ticker("BTC-USDT-250328", "100.9", "101.1"), &spot, Some(&swap), &direct(), NOW).unwrap();
+        assert_eq!(point.mid_rate, dec("0.01"));
+        assert_eq!(point.annualized, dec("0.05"));
+        // 买现货按卖一 100.1，卖合约按买一 100.9
+        assert_eq!(point.basis.carry, dec("0.8") / dec("100.1"));
+        assert_eq!(point.swap_annualized.unwrap().round(6), (dec("0.5") / dec("100.5") * dec("5")).round(6));
+
+        // 已到期的合约不参与计算
+        let expired = future("BTC-USDT-231114", FutureAlias::ThisWeek, 0);
+        assert!(TermPoint::new(&expired, &ticker("BTC-USDT-231114", "100", "101"), &spot, None, &direct(), NOW).is_none());
+    }
+
+    #[test]
+    fn test_inversions() {
+        let spot = ticker("BTC-USDT", "99.9", "100.1");
+        let point = |inst_id: &str, alias, days, bid: &str, ask: &str| {
+            TermPoint::new(&future(inst_id, alias, days), &ticker(inst_id, bid, ask), &spot, None, &direct(), NOW).unwrap()
+        };
+        let points = vec![
+            point("BTC-USDT-QUARTER", FutureAlias::Quarter, 90, "100.9", "101.1"),
+            point("BTC-USDT-WEEK", FutureAlias::ThisWeek, 3, "100.2", "100.4"),
+            point("BTC-USDT-NEXT", FutureAlias::NextQuarter, 180, "100.7", "100.9"),
+        ];
+        let term = TermStructure::new("BTC-USDT".to_string(), "BTC-USDT".to_string(), direct(), points);
+        let aliases: Vec<_> = term.points.iter().map(|p| p.alias.unwrap()).collect();
+        assert_eq!(aliases, vec![FutureAlias::ThisWeek, FutureAlias::Quarter, FutureAlias::NextQuarter]);
+
+        // 次季度低于季度
+        let inversions = term.inversions();
+        assert_eq!(inversions.len(), 1);
+        assert_eq!(inversions[0].near.unwrap().inst_id, "BTC-USDT-QUARTER");
+        assert_eq!(inversions[0].far.inst_id, "BTC-USDT-NEXT");
+
+        // 最近的合约低于现货
+        let points = vec![point("BTC-USDT-WEEK", FutureAlias::ThisWeek, 3, "99.7", "99.9")];
+        let term = TermStructure::new("BTC-USDT".to_string(), "BTC-USDT".to_string(), direct(), points);
+        let inversions = term.inversions();
+        assert_eq!(inversions.len(), 1);
+        assert!(inversions[0].near.is_none());
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use bigdecimal::{BigDecimal, One, Zero};
+
+use crate::model::order::Side;
+use crate::model::symbol::Ticker;
+
+/// 币种图中的一条边：在 inst_id 上吃单，把 from 换成 to
+#[derive(Debug, Clone, PartialEq)]
+pub struct Edge {
+    pub inst_id: String,
+    pub from: String,
+    pub to: String,
+    /// 买入时吃卖一，卖出时吃买一
+    pub side: Side,
+    pub px: BigDecimal,
+    /// 1 单位 from 扣除吃单手续费后换到的 to
+    pub rate: BigDecimal,
+    /// 盘口第一档最多能换掉的 from 数量
+    pub capacity: BigDecimal,
+}
+
+/// 由现货行情构成的币种图，每个交易对对应买入和卖出两条边
+#[derive(Debug, Clone, Default)]
+pub struct CurrencyGraph {
+    edges: HashMap<String, Vec<Edge>>,
+}
+
+impl CurrencyGraph {
+    /// taker_fee 为吃单手续费率，盘口为空的交易对忽略
+    pub fn from_tickers<'a>(tickers: impl IntoIterator<Item = &'a Ticker>, taker_fee: &BigDecimal) -> Self {
+        let keep = BigDecimal::one() - taker_fee;
+        let positive = |v: &Option<BigDecimal>| v.clone().filter(|v| *v > BigDecimal::zero());
+        let mut graph = CurrencyGraph::default();
+        for t in tickers {
+            if let (Some(ask), Some(ask_sz)) = (positive(&t.ask_px), positive(&t.ask_sz)) {
+                // 用计价币买入交易货币
+                graph.add(Edge {
+                    inst_id: t.inst_id.clone(),
+                    from: t.quote.clone(),
+                    to: t.base.clone(),
+                    side: Side::Buy,
+                    rate: &keep / &ask,
+                    capacity: &ask_sz * &ask,
+                    px: ask,
+                });
+            }
+            if let (Some(bid), Some(bid_sz)) = (positive(&t.bid_px), positive(&t.bid_sz)) {
+                // 卖出交易货币换成计价币
+                graph.add(Edge {
+                    inst_id: t.inst_id.clone(),
+                    from: t.base.clone(),
+                    to: t.quote.clone(),
+                    side: Side::Sell,
+                    rate: &bid * &keep,
+                    capacity: bid_sz,
+                    px: bid,
+                });
+            }
+        }
+        graph
+    }
+
+    fn add(&mut self, edge: Edge) {
+        self.edges.entry(edge.from.clone()).or_default().push(edge);
+    }
+
+    pub fn edges_from(&self, ccy: &str) -> &[Edge] {
+        self.edges.get(ccy).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// 从 start 出发经过两个币种回到 start、扣除手续费后收益率超过 min_profit 的环路
+    pub fn triangles(&self, start: &str, min_profit: &BigDecimal) -> Vec<Cycle> {
+        let mut cycles = Vec::new();
+        for first in self.edges_from(start) {
+            for second in self.edges_from(&first.to) {
+                if second.to == start {
+                    continue;
+                }
+                for third in self.edges_from(&second.to).iter().filter(|e| e.to == start) {
+                    let cycle = Cycle::new(vec![first.clone(), second.clone(), third.clone()]);
+                    if cycle.profit_rate > *min_profit {
+                        cycles.push(cycle);
+                    }
+                }
+            }
+        }
+        cycles
+    }
+}
+
+/// 首尾相接的一组兑换
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cycle {
+    pub legs: Vec<Edge>,
+    /// 1 单位起始币种走完一圈后的收益率，已扣除手续费
+    pub profit_rate: BigDecimal,
+    /// 按各腿盘口第一档数量计算的最大起始数量
+    pub max_size: BigDecimal,
+}
+
+impl Cycle {
+    pub fn new(legs: Vec<Edge>) -> Self {
+        let mut product = BigDecimal::one();
+        let mut max_size: Option<BigDecimal> = None;
+        for leg in legs.iter() {
+            // 进入这一腿的数量为 起始数量 * 之前各腿的 rate，不能超过这一腿的容量
+            let bound = &leg.capacity / &product;
+            max_size = Some(match max_size {
+                Some(size) if size < bound => size,
+                _ => bound,
+            });
+            product *= &leg.rate;
+        }
+        Cycle {
+            legs,
+            profit_rate: product - BigDecimal::one(),
+            max_size: max_size.unwrap_or_default(),
+        }
+    }
+
+    pub fn start(&self) -> &str {
+        self.legs.first().map(|l| l.from.as_str()).unwrap_or_default()
+    }
+
+    /// 按 max_size 成交的预期收益，以起始币种计
+    pub fn profit(&self) -> BigDecimal {
+        &self.max_size * &self.profit_rate
+    }
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.start())?;
+        for leg in self.legs.iter() {
+            write!(f, "→{}", leg.to)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::fixture::{self, dec};
+
+    fn ticker(inst_id: &str, bid: &str, bid_sz: &str, ask: &str, ask_sz: &str) -> Ticker {
+        Ticker {
+            bid_sz: Some(dec(bid_sz)),
+            ask_sz: Some(dec(ask_sz)),
+            ..fixture::ticker(inst_id, bid, ask)
+        }
+    }
+
+    #[test]
+    fn test_triangle_after_fees() {
+        let tickers = vec![
+            ticker("BTC-USDT", "99", "1", "100", "2"),
+            ticker("ETH-BTC", "0.049", "10", "0.05", "30"),
+            ticker("ETH-USDT", "5.2", "40", "5.3", "40"),
+        ];
+        // 不计手续费：USDT→BTC→ETH→USDT 为 1/100 / 0.05 * 5.2 = 1.04
+        let graph = CurrencyGraph::from_tickers(&tickers, &BigDecimal::zero());
+        let cycles = graph.triangles("USDT", &BigDecimal::zero());
+        assert_eq!(cycles.len(), 1);
+        let cycle = &cycles[0];
+        assert_eq!(cycle.to_string(), "USDT→BTC→ETH→USDT");
+        assert_eq!(cycle.profit_rate, dec("0.04"));
+        assert_eq!(cycle.legs.iter().map(|l| l.side).collect::<Vec<_>>(), vec![Side::Buy, Side::Buy, Side::Sell]);
+        // 第一腿最多 200 USDT，第二腿最多 1.5 BTC 即 150 USDT，第三腿最多 40 ETH 即 200 USDT
+        assert_eq!(cycle.max_size, dec("150"));
+        assert_eq!(cycle.profit(), dec("6"));
+
+        // 三腿各 1.5% 的手续费吃掉全部收益
+        let graph = CurrencyGraph::from_tickers(&tickers, &dec("0.015"));
+        assert!(graph.triangles("USDT", &BigDecimal::zero()).is_empty());
+    }
+
+    #[test]
+    fn test_skip_empty_book() {
+        let mut empty = ticker("BTC-USDT", "99", "1", "100", "2");
+        empty.ask_px = None;
+        let graph = CurrencyGraph::from_tickers(&[empty], &BigDecimal::zero());
+        assert!(graph.edges_from("USDT").is_empty());
+        assert_eq!(graph.edges_from("BTC").len(), 1);
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::action::auth::Credentials;
+use crate::action::client::{ClientConfig, Environment};
+use crate::action::rate_limit::{Limit, LimitMode};
+use crate::action::sim::SimConfig;
+use crate::logic::calendar::CalendarConfig;
+use crate::logic::futures::FuturesConfig;
+use crate::logic::hedge::HedgeConfig;
+use crate::logic::triangle::TriangleConfig;
+use crate::model::quote::QuoteConfig;
+
+/// conf/okx.yml 中的配置
+#[derive(Debug, Default, Deserialize)]
+pub struct AppConfig {
+    /// live 或 demo
+    /// 未配置时使用实盘行情，但不允许下单；只有显式配置为 live 才会在实盘下单
+    pub environment: Option<Environment>,
+    /// REST 接口地址，默认 https://www.okx.com
+    pub rest_host: Option<String>,
+    pub api_key: Option<String>,
+    pub secret_key: Option<String>,
+    pub passphrase: Option<String>,
+    /// 覆盖默认的接口限速，key 为接口 path，如 /api/v5/market/tickers
+    #[serde(default)]
+    pub rate_limits: HashMap<String, Limit>,
+    /// 超出限速时等待(delay)还是直接拒绝(reject)
+    pub rate_limit_mode: Option<LimitMode>,
+    /// 现货-永续对冲执行，不配置时只发送提醒
+    pub hedge: Option<HedgeConfig>,
+    /// 配置后对冲在模拟盘上执行，不需要 API Key
+    pub paper: Option<SimConfig>,
+    /// 现货-永续比较时视为等价的计价币，不配置时在 USD、USDT、USDC 之间按指数换算
+    pub quotes: Option<QuoteConfig>,
+    /// 交割合约期限结构扫描，配置后才启用
+    pub futures: Option<FuturesConfig>,
+    /// 交割合约跨期价差监控，配置后才启用
+    pub calendar: Option<CalendarConfig>,
+    /// 现货三角套利扫描，配置后才启用
+    pub triangle: Option<TriangleConfig>,
+}
+
+impl AppConfig {
+    /// 配置文件不存在时使用默认配置，文件格式错误时直接退出，避免带着错误的配置运行
+    pub fn load(path: &str) -> AppConfig {
+        match std::fs::read_to_string(path) {
+            Ok(content) => serde_yaml::from_str(&content).unwrap_or_else(|e| panic!("invalid config {}: {}", path, e)),
+            Err(_) => AppConfig::default(),
+        }
+    }
+
+    pub fn credentials(&self) -> Option<Credentials> {
+        match (&self.api_key, &self.secret_key, &self.passphrase) {
+            (Some(api_key), Some(secret_key), Some(passphrase)) => Some(Credentials::new(api_key, secret_key, passphrase)),
+            _ => None,
+        }
+    }
+
+    pub fn client_config(&self) -> ClientConfig {
+        let mut config = ClientConfig {
+            credentials: self.credentials(),
+            environment: self.environment.unwrap_or(Environment::Live),
+            live_trading: self.environment == Some(Environment::Live),
+            ..Default::default()
+        };
+        if let Some(rest_host) = &self.rest_host {
+            config.base_url = rest_host.clone();
+        }
+        config.rate_limit.limits.extend(self.rate_limits.iter().map(|(k, v)| (k.clone(), *v)));
+        if let Some(mode) = self.rate_limit_mode {
+            config.rate_limit.mode = mode;
+        }
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_trading_requires_explicit_live() {
+        let config: AppConfig = serde_yaml::from_str("rest_host: http://127.0.0.1:8080").unwrap();
+        let client_config = config.client_config();
+        assert_eq!(client_config.environment, Environment::Live);
+        assert!(!client_config.live_trading);
+        assert_eq!(client_config.base_url, "http://127.0.0.1:8080");
+
+        let config: AppConfig = serde_yaml::from_str("environment: live").unwrap();
+        assert!(config.client_config().live_trading);
+
+        let config: AppConfig = serde_yaml::from_str("environment: demo").unwrap();
+        let client_config = config.client_config();
+        assert_eq!(client_config.environment, Environment::Demo);
+        assert!(!client_config.live_trading);
+    }
+
+    #[test]
+    fn test_rate_limit_overrides() {
+        let yaml = "rate_limit_mode: reject\nrate_limits:\n  /api/v5/market/tickers:\n    requests: 5\n    interval_ms: 2000\n";
+        let config: AppConfig = serde_yaml::from_str(yaml).unwrap();
+        let client_config = config.client_config();
+        assert_eq!(client_config.rate_limit.mode, LimitMode::Reject);
+        assert_eq!(client_config.rate_limit.limits["/api/v5/market/tickers"], Limit::new(5, 2000));
+        // 其他接口保持默认
+        assert_eq!(client_config.rate_limit.limits["/api/v5/market/books"], Limit::new(40, 2000));
+    }
+
+    #[test]
+    fn test_credentials_need_all_fields() {
+        let config: AppConfig = serde_yaml::from_str("api_key: key\nsecret_key: secret").unwrap();
+        assert!(config.credentials().is_none());
+
+        let config: AppConfig = serde_yaml::from_str("api_key: key\nsecret_key: secret\npassphrase: pass").unwrap();
+        assert_eq!(config.credentials(), Some(Credentials::new("key", "secret", "pass")));
+    }
+
+    #[test]
+    fn test_hedge_config_defaults() {
+        let config: AppConfig = serde_yaml::from_str("environment: demo").unwrap();
+        assert!(config.hedge.is_none());
+
+        let config: AppConfig = serde_yaml::from_str("hedge:\n  notional: 500\n  allow_reverse: true\n").unwrap();
+        let hedge = config.hedge.unwrap();
+        assert_eq!(hedge.notional, bigdecimal::BigDecimal::from(500));
+        assert!(hedge.allow_reverse);
+        assert_eq!(hedge.max_pairs, 1);
+    }
+
+    #[test]
+    fn test_quote_config() {
+        let config: AppConfig = serde_yaml::from_str("quotes:\n  currencies: [USDT, USDC]\n").unwrap();
+        let quotes = config.quotes.unwrap();
+        assert_eq!(quotes.currencies, vec!["USDT", "USDC"]);
+        assert!(quotes.use_index);
+    }
+
+    #[test]
+    fn test_futures_config() {
+        let config: AppConfig = serde_yaml::from_str("futures:\n  max_yield: 0.5\n").unwrap();
+        let futures = config.futures.unwrap();
+        assert_eq!(futures.max_yield, bigdecimal::BigDecimal::new(5.into(), 1));
+        assert_eq!(futures.min_expiry_hours, 24);
+    }
+}
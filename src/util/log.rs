@@ -1,3 +1,19 @@
+use log::{error, warn};
+
+use crate::action::error::OkxError;
+
 pub fn init_log() {
     log4rs::init_file("conf/log.yml", Default::default()).unwrap();
-}
\ No newline at end of file
+}
+
+/// 按错误类型记录 REST 调用失败
+/// 限频只是暂时的，返回结构变化则需要人工处理
+pub fn log_okx_error(what: &str, e: &OkxError) {
+    if e.is_rate_limited() {
+        warn!("{} throttled by okx: {}", what, e);
+    } else if e.is_decode() {
+        error!("{} payload changed shape: {}", what, e);
+    } else {
+        warn!("{} failed: {}", what, e);
+    }
+}